@@ -0,0 +1,55 @@
+//! Computes "customers also bought" data for the gateway.
+//!
+//! Reads purchases of complete orders exported from orders history as json array of
+//! `{ "customer_id": <user id>, "base_product_id": <base product id> }` and writes co-purchases
+//! to `recommendations.co_purchases_path` from the gateway config.
+//!
+//! Usage: `co_purchases <purchases.json>`
+extern crate gateway_lib;
+extern crate serde_json;
+
+use std::env;
+use std::fs::{self, File};
+use std::path::Path;
+use std::process;
+
+use gateway_lib::config::Config;
+use gateway_lib::graphql::models::{CoPurchases, Purchase};
+
+/// Co-purchases kept for every product
+const CO_PURCHASES_LIMIT: usize = 50;
+
+/// Best sellers used to top up recommendations
+const BEST_SELLERS_LIMIT: usize = 1000;
+
+fn main() {
+    let purchases_path = match env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("Usage: co_purchases <purchases.json>");
+            process::exit(1);
+        }
+    };
+
+    let config = Config::new().expect("Can't load gateway configs. Please check your /config folder.");
+
+    let purchases_file = File::open(&purchases_path).expect("Can't open purchases file");
+    let purchases: Vec<Purchase> = serde_json::from_reader(purchases_file).expect("Can't parse purchases file");
+    let co_purchases = CoPurchases::compute(purchases, CO_PURCHASES_LIMIT, BEST_SELLERS_LIMIT);
+
+    // write to temporary file first, so the gateway never reads a partially written file
+    let co_purchases_path = Path::new(&config.recommendations.co_purchases_path);
+    if let Some(dir) = co_purchases_path.parent() {
+        fs::create_dir_all(dir).expect("Can't create co-purchases directory");
+    }
+    let tmp_path = co_purchases_path.with_extension("json.tmp");
+    let tmp_file = File::create(&tmp_path).expect("Can't create co-purchases file");
+    serde_json::to_writer(tmp_file, &co_purchases).expect("Can't write co-purchases file");
+    fs::rename(&tmp_path, co_purchases_path).expect("Can't replace co-purchases file");
+
+    println!(
+        "Co-purchases of {} products written to {}",
+        co_purchases.products.len(),
+        co_purchases_path.display()
+    );
+}
@@ -8,6 +8,7 @@ use stq_static_resources::Currency;
 use config_crate::{Config as RawConfig, ConfigError, Environment, File};
 
 use sentry_integration::SentryConfig;
+use url::UrlBuilder;
 
 /// The only supported fiat currency to buy products with.
 /// Remove as soon as multi fiat currency becomes available
@@ -26,6 +27,9 @@ pub struct Gateway {
     pub http_client_retries: usize,
     pub records_limit: usize,
     pub http_timeout_ms: u64,
+    pub invoice_updates_timeout_ms: u64,
+    pub invoice_updates_poll_interval_ms: u64,
+    pub batch_concurrency: usize,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -40,6 +44,12 @@ pub struct Config {
     pub notifications_microservice: Microservice,
     pub delivery_microservice: Microservice,
     pub jwt: JWT,
+    pub checkout_quote: CheckoutQuote,
+    pub downloads: Downloads,
+    pub carrier: Carrier,
+    pub recommendations: Recommendations,
+    pub search: Search,
+    pub saved_searches: SavedSearches,
     pub cors: CORS,
     pub graylog: Option<GrayLogConfig>,
     pub sentry: Option<SentryConfig>,
@@ -51,6 +61,55 @@ pub struct JWT {
     pub leeway: i64,
 }
 
+/// Signing key and lifetime of checkout quote tokens
+#[derive(Debug, Deserialize, Clone)]
+pub struct CheckoutQuote {
+    pub secret: String,
+    pub ttl_secs: i64,
+}
+
+/// Signing key and lifetime of file download links
+#[derive(Debug, Deserialize, Clone)]
+pub struct Downloads {
+    pub secret: String,
+    pub ttl_secs: i64,
+}
+
+/// Source of shipment tracking events
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CarrierAdapter {
+    Delivery,
+    File,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Carrier {
+    pub adapter: CarrierAdapter,
+    /// Json file with tracking events for `file` adapter
+    pub events_path: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Recommendations {
+    /// Json file with co-purchases written by `co_purchases` job
+    pub co_purchases_path: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Search {
+    /// Json file with synonym groups, e.g. `[{ "language": "en", "words": ["tv", "television"] }]`
+    pub synonyms_path: String,
+    /// Custom attributes holding EAN/UPC barcodes of products
+    pub barcode_attribute_ids: Vec<i32>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SavedSearches {
+    /// How often saved searches are checked for new products, 0 turns the alerts off
+    pub alerts_interval_s: u64,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct CORS {
     pub domain: String,
@@ -60,7 +119,8 @@ pub struct CORS {
 impl Config {
     /// Creates config from base.toml, which are overwritten by <env>.toml, where
     /// env is one of development, test, production. After that it could be overwritten
-    /// by env variables like STQ_GATEWAY_URL (this will override `url` field in config)
+    /// by env variables like STQ_GATEWAY_URL (this will override `url` field in config),
+    /// fields of sections are separated by double underscore, e.g. STQ_GATEWAY_CHECKOUT_QUOTE__SECRET
     pub fn new() -> Result<Self, ConfigError> {
         let mut s = RawConfig::new();
 
@@ -70,7 +130,7 @@ impl Config {
         s.merge(File::with_name(&format!("config/{}", env.to_string())).required(false))?;
 
         // Add in settings from the environment (with a prefix of STQ_GATEWAY)
-        s.merge(Environment::with_prefix("STQ_GATEWAY"))?;
+        s.merge(Environment::with_prefix("STQ_GATEWAY").separator("__"))?;
 
         s.try_into()
     }
@@ -94,4 +154,14 @@ impl Config {
             StqService::Delivery => self.delivery_microservice.url.clone(),
         }
     }
+
+    /// Starts url of the microservice endpoint
+    pub fn url(&self, service: StqService) -> UrlBuilder {
+        UrlBuilder::new(self.service_url(service))
+    }
+
+    /// Starts url of the saga microservice endpoint
+    pub fn saga_url(&self) -> UrlBuilder {
+        UrlBuilder::new(self.saga_microservice.url.clone())
+    }
 }
@@ -0,0 +1,189 @@
+//! Files served as raw bytes with their own content type, the application only responds with strings,
+//! so download routes are handled before the requests reach it
+use std::sync::Arc;
+use std::time::Duration;
+
+use failure::Context as FailureContext;
+use failure::Error as FailureError;
+use futures::prelude::*;
+use futures_cpupool::CpuPool;
+use hyper;
+use hyper::header::{
+    AccessControlAllowOrigin, AccessControlMaxAge, Charset, ContentDisposition, ContentLength, ContentType, DispositionParam,
+    DispositionType,
+};
+use hyper::mime::{self, Mime};
+use hyper::server::{Request, Response, Service};
+use hyper::Method::Get;
+use hyper::StatusCode;
+use juniper::FieldError;
+
+use stq_http::client::{ClientHandle, TimeLimitedHttpClient};
+use stq_http::controller::Application;
+use stq_http::errors::Codeable;
+use stq_http::request_util::CorrelationToken;
+use stq_router::RouteParser;
+use stq_routes::service::Service as StqService;
+
+use super::parse_token_payload;
+use super::receipt;
+use super::routes::{self, Route};
+use config::Config;
+use errors::Error;
+use graphql::context::{check_jwt_not_revoked, Context};
+use graphql::models::jwt::JWTPayload;
+use graphql::schema::order_export;
+use sentry_integration::log_and_capture_error;
+
+/// File sent as the response body
+#[derive(Clone, Debug)]
+pub struct DownloadFile {
+    pub file_name: String,
+    pub content_type: String,
+    pub content: Vec<u8>,
+}
+
+/// Serves download routes and passes other requests to the application
+pub struct DownloadsService {
+    app: Application<Error>,
+    route_parser: Arc<RouteParser<Route>>,
+    cpu_pool: CpuPool,
+    http_client: ClientHandle,
+    jwt_public_key: Vec<u8>,
+    jwt_leeway: i64,
+    config: Config,
+}
+
+impl DownloadsService {
+    pub fn new(
+        app: Application<Error>,
+        http_client: ClientHandle,
+        cpu_pool: CpuPool,
+        jwt_public_key: Vec<u8>,
+        jwt_leeway: i64,
+        config: Config,
+    ) -> Self {
+        Self {
+            app,
+            route_parser: Arc::new(routes::create_route_parser()),
+            cpu_pool,
+            http_client,
+            jwt_public_key,
+            jwt_leeway,
+            config,
+        }
+    }
+}
+
+impl Service for DownloadsService {
+    type Request = Request;
+    type Response = Response;
+    type Error = hyper::Error;
+    type Future = Box<Future<Item = Response, Error = hyper::Error>>;
+
+    fn call(&self, req: Request) -> Self::Future {
+        let route = match (req.method(), self.route_parser.test(req.path())) {
+            (&Get, Some(route @ Route::OrdersExport(_))) | (&Get, Some(route @ Route::OrderReceipt(_))) => route,
+            _ => return Box::new(self.app.call(req)),
+        };
+
+        let path = req.path().to_string();
+        let correlation_token = req.headers().get::<CorrelationToken>().map(|token| token.clone());
+        let token_payload = parse_token_payload(req.headers(), &self.jwt_public_key, self.jwt_leeway);
+        let request_timeout = Duration::from_millis(self.config.gateway.http_timeout_ms);
+        let client = TimeLimitedHttpClient::new(self.http_client.clone(), request_timeout);
+        let config = self.config.clone();
+        let cors_domain = self.config.cors.domain.clone();
+        let cors_max_age = self.config.cors.max_age;
+
+        Box::new(
+            self.cpu_pool
+                .spawn_fn(move || download(route, client, config, correlation_token, token_payload))
+                .then(move |result| {
+                    let response = match result {
+                        Ok(file) => {
+                            debug!("Response with file {}: GET {}", file.file_name, path);
+                            file_response(file)
+                        }
+                        Err(e) => {
+                            let response = error_response(&e);
+                            debug!("Response with error {}: GET {}", e, path);
+                            response
+                        }
+                    };
+                    Ok(response
+                        .with_header(AccessControlAllowOrigin::Value(cors_domain))
+                        .with_header(AccessControlMaxAge(cors_max_age)))
+                }),
+        )
+    }
+}
+
+/// Export links carry their own signed claims, other downloads are authorized by the bearer token
+fn download(
+    route: Route,
+    client: TimeLimitedHttpClient<ClientHandle>,
+    config: Config,
+    correlation_token: Option<CorrelationToken>,
+    token_payload: Option<JWTPayload>,
+) -> Result<DownloadFile, FailureError> {
+    match route {
+        Route::OrdersExport(token) => {
+            let claims = order_export::export_claims(&config, &token).map_err(|e| forbidden(&e))?;
+            check_jwt_not_revoked(&client, &claims.user, config.service_url(StqService::Users)).map_err(|e| forbidden(&e))?;
+            let context = Context::new(client, Some(claims.user.clone()), None, None, None, config, correlation_token);
+            order_export::check_export_access(&context, claims.store_id).map_err(|e| forbidden(&e))?;
+            order_export::export_store_orders(&context, &claims).map_err(|e| internal(&e))
+        }
+        Route::OrderReceipt(order_slug) => {
+            let payload = token_payload.ok_or_else(|| {
+                format_err!("Authorization is required to download receipt of order {}", order_slug).context(Error::Forbidden)
+            })?;
+            check_jwt_not_revoked(&client, &payload, config.service_url(StqService::Users)).map_err(|e| forbidden(&e))?;
+            let context = Context::new(client, Some(payload), None, None, None, config, correlation_token);
+            receipt::order_receipt(&context, &order_slug)
+        }
+        route => Err(format_err!("Route {:?} is not a download", route).context(Error::NotFound).into()),
+    }
+}
+
+fn file_response(file: DownloadFile) -> Response {
+    let content_type = file.content_type.parse::<Mime>().unwrap_or(mime::APPLICATION_OCTET_STREAM);
+    Response::new()
+        .with_header(ContentType(content_type))
+        .with_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(
+                Charset::Ext("UTF-8".to_string()),
+                None,
+                file.file_name.into_bytes(),
+            )],
+        })
+        .with_header(ContentLength(file.content.len() as u64))
+        .with_body(file.content)
+}
+
+fn error_response(e: &FailureError) -> Response {
+    let status = e
+        .causes()
+        .filter_map(|cause| cause.downcast_ref::<FailureContext<Error>>())
+        .map(|context| context.get_context().code())
+        .next()
+        .unwrap_or(StatusCode::InternalServerError);
+    if status == StatusCode::InternalServerError {
+        log_and_capture_error(e);
+    }
+
+    Response::new()
+        .with_status(status)
+        .with_header(ContentType::plaintext())
+        .with_body(e.to_string())
+}
+
+fn forbidden(e: &FieldError) -> FailureError {
+    format_err!("{:?}", e).context(Error::Forbidden).into()
+}
+
+fn internal(e: &FieldError) -> FailureError {
+    format_err!("Download failed: {:?}", e).context(Error::HttpClient).into()
+}
@@ -0,0 +1,150 @@
+//! Long polling of invoice changes for the checkout page.
+//!
+//! The request asked for an `invoiceUpdated` GraphQL subscription, but juniper 0.9 has no
+//! subscriptions (the schema root is only `Query` and `Mutation`) and the hyper 0.11 server of
+//! `stq_http` can not upgrade connections to websockets. Until both are upgraded the checkout page
+//! long-polls `GET /invoices/<id>/updates/<revision>`, which answers as soon as the invoice changes.
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use failure::Error as FailureError;
+use futures::future::{self, Either, Loop};
+use futures::prelude::*;
+use hyper::Method;
+use juniper::FieldError;
+use serde_json;
+use sha2::{Digest, Sha256};
+use tokio_core::reactor::{Handle, Timeout};
+use uuid::Uuid;
+
+use stq_routes::model::Model;
+use stq_routes::service::Service;
+use stq_types::{OrderId, OrderIdentifier};
+
+use errors::Error;
+use graphql::context::Context;
+use graphql::models::*;
+use graphql::schema::order as order_module;
+
+/// Invoice snapshot returned to the client, `revision` changes
+/// whenever invoice state, captured amount, transactions or payment intent status change
+#[derive(Clone, Debug, Serialize)]
+pub struct InvoiceUpdate {
+    pub invoice: Invoice,
+    pub payment_intent_status: Option<PaymentIntentStatus>,
+    pub revision: String,
+}
+
+impl InvoiceUpdate {
+    fn new(invoice: Invoice, payment_intent_status: Option<PaymentIntentStatus>) -> Result<Self, FailureError> {
+        let snapshot = serde_json::to_string(&(
+            &invoice.state,
+            &invoice.amount_captured,
+            &invoice.transactions,
+            &payment_intent_status,
+        ))
+        .map_err(|e| format_err!("Could not serialize invoice {}: {}", invoice.invoice_id, e).context(Error::Parse))?;
+
+        // revision goes to the url of the next poll, so it is a fixed size hash of the snapshot,
+        // sha256 is the same in all replicas whatever they are built with
+        let revision = Sha256::digest(snapshot.as_bytes())
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+
+        Ok(Self {
+            invoice,
+            payment_intent_status,
+            revision,
+        })
+    }
+}
+
+/// Invoice can be watched by the buyer of its orders and by managers of their stores
+pub fn check_access(context: &Context, invoice_id: &str) -> Result<(), FailureError> {
+    let invoice_id = parse_invoice_id(invoice_id)?;
+    let url = context.config.url(Service::Billing).path("invoices/by-id").segment(invoice_id).path("order_ids").build();
+    let order_ids = context.request::<Vec<OrderId>>(Method::Get, url, None).wait().map_err(from_field_error)?;
+
+    let current_user_id = context.user.as_ref().map(|user| user.user_id);
+    for order_id in order_ids {
+        let order = match order_module::try_get_order(context, OrderIdentifier::Id(order_id)).map_err(from_field_error)? {
+            Some(order) => order.0,
+            None => continue,
+        };
+        if current_user_id == Some(order.customer) || context.permissions().manages_store_billing(order.store).map_err(from_field_error)? {
+            return Ok(());
+        }
+    }
+
+    Err(format_err!("Invoice {} is available only to the buyer and managers", invoice_id)
+        .context(Error::Forbidden)
+        .into())
+}
+
+/// Waits until invoice differs from `known_revision` or timeout is reached.
+/// Without `known_revision` current invoice state is returned immediately.
+pub fn wait_for_update(
+    context: Context,
+    handle: Handle,
+    invoice_id: String,
+    known_revision: Option<String>,
+) -> Box<Future<Item = InvoiceUpdate, Error = FailureError>> {
+    let invoice_id = match parse_invoice_id(&invoice_id) {
+        Ok(invoice_id) => invoice_id,
+        Err(e) => return Box::new(future::err(e)),
+    };
+
+    let timeout = Duration::from_millis(context.config.gateway.invoice_updates_timeout_ms);
+    let poll_interval = Duration::from_millis(context.config.gateway.invoice_updates_poll_interval_ms);
+    let deadline = Instant::now() + timeout;
+    let context = Rc::new(context);
+
+    Box::new(future::loop_fn(known_revision, move |known_revision| {
+        let handle = handle.clone();
+
+        fetch_invoice_update(&context, invoice_id).and_then(move |update| {
+            let changed = known_revision.as_ref().map(|revision| *revision != update.revision).unwrap_or(true);
+            if changed || Instant::now() >= deadline {
+                Either::A(future::ok(Loop::Break(update)))
+            } else {
+                Either::B(
+                    future::result(Timeout::new(poll_interval, &handle))
+                        .flatten()
+                        .map(move |_| Loop::Continue(known_revision))
+                        .map_err(From::from),
+                )
+            }
+        })
+    }))
+}
+
+fn parse_invoice_id(invoice_id: &str) -> Result<Uuid, FailureError> {
+    Uuid::parse_str(invoice_id).map_err(|e| format_err!("Invalid invoice id {}: {}", invoice_id, e).context(Error::Parse).into())
+}
+
+fn fetch_invoice_update(context: &Context, invoice_id: Uuid) -> Box<Future<Item = InvoiceUpdate, Error = FailureError>> {
+    let invoice_url = context.config.url(Service::Billing).path("invoices/by-id").segment(invoice_id).build();
+    let payment_intent_url = context
+        .config
+        .url(Service::Billing)
+        .model(Model::PaymentIntent)
+        .model(Model::Invoice)
+        .segment(invoice_id)
+        .build();
+
+    Box::new(
+        context
+            .request::<Option<Invoice>>(Method::Get, invoice_url, None)
+            .join(context.request::<Option<PaymentIntent>>(Method::Get, payment_intent_url, None))
+            .map_err(from_field_error)
+            .and_then(move |(invoice, payment_intent)| {
+                let invoice = invoice.ok_or_else(|| format_err!("Invoice {} not found", invoice_id).context(Error::NotFound))?;
+                InvoiceUpdate::new(invoice, payment_intent.map(|payment_intent| payment_intent.status))
+            }),
+    )
+}
+
+fn from_field_error(e: FieldError) -> FailureError {
+    format_err!("Billing request failed: {:?}", e).context(Error::HttpClient).into()
+}
@@ -9,6 +9,7 @@ use futures::prelude::*;
 use futures_cpupool::CpuPool;
 use hyper::header::{Authorization, Bearer};
 use hyper::server::Request;
+use hyper::Headers;
 use hyper::Method::{Get, Post};
 use jsonwebtoken::{decode, Algorithm, Validation};
 use juniper::http::GraphQLRequest;
@@ -27,16 +28,22 @@ use stq_router::RouteParser;
 use stq_routes::service::Service;
 use stq_static_resources::Currency;
 use stq_types::SessionId;
+use tokio_core::reactor::Handle;
 
 use self::routes::Route;
 use config::Config;
 use errors::Error;
 use graphql::context::{check_jwt_not_revoked, Context, GraphQLResponse};
+use graphql::microservice::CoPurchasesCache;
 use graphql::models::jwt::JWTPayload;
+use graphql::models::Synonyms;
 use graphql::schema::Schema;
 use sentry_integration::log_and_capture_error;
 
+pub mod downloads;
 pub mod graphiql;
+pub mod invoice_updates;
+pub mod receipt;
 pub mod routes;
 
 pub struct ControllerImpl {
@@ -47,6 +54,9 @@ pub struct ControllerImpl {
     jwt_leeway: i64,
     config: Config,
     schema: Arc<Schema>,
+    handle: Handle,
+    co_purchases_cache: Arc<CoPurchasesCache>,
+    synonyms: Arc<Synonyms>,
 }
 
 impl ControllerImpl {
@@ -58,6 +68,9 @@ impl ControllerImpl {
         jwt_leeway: i64,
         config: Config,
         schema: Arc<Schema>,
+        handle: Handle,
+        co_purchases_cache: Arc<CoPurchasesCache>,
+        synonyms: Arc<Synonyms>,
     ) -> Self {
         let route_parser = Arc::new(routes::create_route_parser());
 
@@ -69,6 +82,9 @@ impl ControllerImpl {
             cpu_pool,
             config,
             schema,
+            handle,
+            co_purchases_cache,
+            synonyms,
         }
     }
 }
@@ -83,6 +99,8 @@ impl Controller for ControllerImpl {
         let jwt_public_key = self.jwt_public_key.clone();
         let cpu_pool = self.cpu_pool.clone();
         let schema = self.schema.clone();
+        let co_purchases_cache = self.co_purchases_cache.clone();
+        let synonyms = self.synonyms.clone();
 
         let request_timeout = Duration::from_millis(self.config.gateway.http_timeout_ms);
         let client = TimeLimitedHttpClient::new(self.http_client.clone(), request_timeout);
@@ -94,18 +112,7 @@ impl Controller for ControllerImpl {
 
                 (&Post, Some(Route::Graphql)) => {
                     let headers = req.headers().clone();
-                    let auth_header = headers.get::<Authorization<Bearer>>();
-
-                    let mut validation = Validation {
-                        leeway,
-                        ..Validation::new(Algorithm::RS256)
-                    };
-                    let token_payload = auth_header.and_then(move |auth| {
-                        let token = auth.0.token.as_ref();
-                        decode::<JWTPayload>(token, jwt_public_key.as_ref(), &validation)
-                            .ok()
-                            .map(|t| t.claims)
-                    });
+                    let token_payload = parse_token_payload(&headers, &jwt_public_key, leeway);
 
                     let session_id_header = headers.get::<SessionIdHeader>().and_then(|sid| sid.parse::<SessionId>().ok());
                     let currency_header = headers.get::<CurrencyHeader>().and_then(|sid| sid.parse::<Currency>().ok());
@@ -120,6 +127,7 @@ impl Controller for ControllerImpl {
                                     .into()
                             })
                             .and_then(move |graphql_req| {
+                                let background_pool = cpu_pool.clone();
                                 cpu_pool
                                     .spawn_fn(move || {
                                         if let Some(ref payload) = token_payload {
@@ -136,7 +144,10 @@ impl Controller for ControllerImpl {
                                             fiat_currency_header,
                                             config,
                                             correlation_token,
-                                        );
+                                        )
+                                        .with_background_pool(background_pool)
+                                        .with_co_purchases_cache(co_purchases_cache)
+                                        .with_synonyms(synonyms);
                                         let resp = graphql_req.execute(&*schema, &graphql_context);
                                         serde_json::to_value(resp)
                                     })
@@ -145,6 +156,35 @@ impl Controller for ControllerImpl {
                     )
                 }
 
+                (&Get, Some(Route::InvoiceUpdated(invoice_id, known_revision))) => {
+                    let headers = req.headers().clone();
+                    let token_payload = parse_token_payload(&headers, &jwt_public_key, leeway);
+                    let correlation_token = headers.get::<CorrelationToken>().map(|token| token.clone());
+                    let handle = self.handle.clone();
+
+                    match token_payload {
+                        Some(payload) => serialize_future::<_, FailureError, _>(
+                            cpu_pool
+                                .spawn_fn({
+                                    let invoice_id = invoice_id.clone();
+                                    move || {
+                                        check_jwt_not_revoked(&client, &payload, config.service_url(Service::Users))
+                                            .map_err(|e| format_err!("{:?}", e).context(Error::Forbidden))?;
+                                        let context = Context::new(client, Some(payload), None, None, None, config, correlation_token);
+                                        invoice_updates::check_access(&context, &invoice_id)?;
+                                        Ok(context)
+                                    }
+                                })
+                                .and_then(move |context| invoice_updates::wait_for_update(context, handle, invoice_id, known_revision)),
+                        ),
+                        None => Box::new(future::err(
+                            format_err!("Authorization is required to watch invoice {}", invoice_id)
+                                .context(Error::Forbidden)
+                                .into(),
+                        )),
+                    }
+                }
+
                 (&Get, Some(Route::VerifyEmail(token))) => {
                     let body = json!({ "token": token }).to_string();
                     let url = format!("{}/email_verify_apply", saga_addr);
@@ -359,3 +399,14 @@ impl Controller for ControllerImpl {
         )
     }
 }
+
+pub fn parse_token_payload(headers: &Headers, jwt_public_key: &[u8], leeway: i64) -> Option<JWTPayload> {
+    let validation = Validation {
+        leeway,
+        ..Validation::new(Algorithm::RS256)
+    };
+    headers.get::<Authorization<Bearer>>().and_then(|auth| {
+        let token = auth.0.token.as_ref();
+        decode::<JWTPayload>(token, jwt_public_key, &validation).ok().map(|t| t.claims)
+    })
+}
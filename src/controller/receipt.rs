@@ -0,0 +1,179 @@
+//! PDF receipt of the order for the buyer, store managers and financial managers
+use failure::Error as FailureError;
+use futures::Future;
+use hyper::Method;
+use juniper::FieldError;
+
+use stq_api::orders::Order;
+use stq_routes::service::Service;
+use stq_static_resources::{Currency, Language, Translation};
+use stq_types::{OrderSlug, ProductId};
+
+use super::downloads::DownloadFile;
+use errors::Error;
+use graphql::context::Context;
+use graphql::models::*;
+use graphql::schema::base_product as base_product_module;
+
+pub fn order_receipt(context: &Context, order_slug: &str) -> Result<DownloadFile, FailureError> {
+    let order_slug = order_slug
+        .parse::<i32>()
+        .map(OrderSlug)
+        .map_err(|e| format_err!("Invalid order slug {}: {}", order_slug, e).context(Error::Parse))?;
+    let order = context
+        .get_orders_microservice()
+        .get_order_by_slug(order_slug)
+        .map_err(from_field_error)?
+        .ok_or_else(|| format_err!("Order {} not found", order_slug).context(Error::NotFound))?
+        .0;
+
+    let is_buyer = context.user.as_ref().map(|user| user.user_id == order.customer).unwrap_or(false);
+    let is_manager = context.permissions().manages_store_billing(order.store).map_err(from_field_error)?;
+    if !is_buyer && !is_manager {
+        return Err(format_err!("Receipt of order {} is available only to the buyer and managers", order_slug)
+            .context(Error::Forbidden)
+            .into());
+    }
+
+    // billing renders the PDF, so a failure there fails only the download
+    let document = receipt_document(context, &order, is_manager).map_err(from_field_error)?;
+    let content = context
+        .get_billing_microservice()
+        .render_receipt(order.id, document)
+        .map_err(from_field_error)?;
+    Ok(DownloadFile {
+        file_name: format!("receipt-{}.pdf", order_slug),
+        content_type: "application/pdf".to_string(),
+        content,
+    })
+}
+
+/// Fees are internal to the seller, so they are shown to managers only
+fn receipt_document(context: &Context, order: &Order, with_fees: bool) -> Result<ReceiptDocument, FieldError> {
+    let currency = order.currency;
+    let money = |amount: f64| payment_amount(amount, currency);
+    let store = context.get_stores_microservice().get_store_by_id(order.store)?;
+    let address: Address = order.address.clone().into();
+
+    let mut document = ReceiptDocument::new();
+    document
+        .title(&format!("Receipt for order {}", order.slug))
+        .text(&format!("Date: {}", order.created_at.format("%Y-%m-%d %H:%M UTC")))
+        .text(&format!(
+            "Store: {}",
+            store.map(|store| english(store.name)).unwrap_or_else(|| order.store.to_string())
+        ));
+
+    document
+        .heading("Buyer")
+        .text(&order.receiver_name)
+        .text(&order.receiver_email)
+        .text(&address.value.unwrap_or_default());
+
+    let price = Money::from_f64(order.price.0, currency).times(order.quantity);
+    document.heading("Items").row(
+        &format!("{} x {}", product_name(context, order.product)?, order.quantity.0),
+        &format!("{} {}", price, currency.code()),
+    );
+    if let Some(discount) = order.product_discount.map(|discount| discount.0).filter(|discount| *discount > 0.0) {
+        document.row("Product discount", &format!("-{}", money(discount)));
+    }
+    if let Some(discount) = order.coupon_discount.map(|discount| discount.0).filter(|discount| *discount > 0.0) {
+        let label = match order.coupon_percent {
+            Some(percent) => format!("Coupon discount ({}%)", percent),
+            None => "Coupon discount".to_string(),
+        };
+        document.row(&label, &format!("-{}", money(discount)));
+    }
+    let delivery = match order.delivery_company {
+        Some(ref company) => format!("Delivery ({})", company),
+        None => "Delivery".to_string(),
+    };
+    document
+        .row(&delivery, &money(order.delivery_price))
+        .total("Total", &money(order.total_amount.0));
+
+    let invoice_url = context.config.url(Service::Billing).path("invoices/by-order-id").segment(order.id).build();
+    if let Some(invoice) = context.request::<Option<Invoice>>(Method::Get, invoice_url, None).wait()? {
+        document
+            .heading("Payment")
+            .text(&format!("Invoice: {}", invoice.invoice_id))
+            .text(&format!("State: {:?}", invoice.state))
+            .row("Paid", &payment_amount(invoice.amount_captured.0, invoice.currency));
+        if let Some(wallet) = invoice.wallet {
+            document.text(&format!("Wallet: {}", wallet));
+        }
+    }
+
+    if with_fees {
+        let billing = context.get_billing_microservice();
+        let fee = billing.get_fee_by_order_id(order.id)?;
+        let stripe_fee = billing.order(order.id)?.and_then(|order_billing| order_billing.stripe_fee);
+        if fee.is_some() || stripe_fee.is_some() {
+            document.heading("Fees");
+        }
+        if let Some(fee) = fee {
+            document.row(&format!("Platform fee ({:?})", fee.status), &payment_amount(fee.amount, fee.currency));
+        }
+        if let Some(stripe_fee) = stripe_fee {
+            document.row("Payment processing fee", &money(stripe_fee));
+        }
+    }
+
+    render_seller(context, order, &mut document)?;
+    Ok(document)
+}
+
+fn render_seller(context: &Context, order: &Order, document: &mut ReceiptDocument) -> Result<(), FieldError> {
+    let billing = context.get_billing_microservice();
+    match billing.billing_type(order.store)? {
+        Some(BillingType::International) => {
+            if let Some(info) = billing.international_billing_info(order.store)? {
+                document
+                    .heading("Seller")
+                    .text(&info.name)
+                    .text(&info.recipient_address)
+                    .text(&format!("{}, {}", info.city, info.country))
+                    .text(&format!("Bank: {}, {}", info.bank, info.bank_address))
+                    .text(&format!("SWIFT: {}", info.swift.0))
+                    .text(&format!("Account: {} ({})", info.account, info.currency.code()));
+            }
+        }
+        Some(BillingType::Russia) => {
+            if let Some(info) = billing.russia_billing_info(order.store)? {
+                document
+                    .heading("Seller")
+                    .text(&info.beneficiary_full_name)
+                    .text(&format!("Tax id: {}", info.tax_id))
+                    .text(&format!("Bank: {}", info.bank_name))
+                    .text(&format!("BIC: {}", info.swift_bic.0))
+                    .text(&format!("Current account: {}", info.current_account))
+                    .text(&format!("Correspondent account: {}", info.correspondent_account));
+            }
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+fn payment_amount(amount: f64, currency: Currency) -> String {
+    format!("{} {}", Money::from_f64(amount, currency), currency.code())
+}
+
+fn product_name(context: &Context, product_id: ProductId) -> Result<String, FieldError> {
+    Ok(base_product_module::try_get_base_product_by_product(context, product_id)?
+        .map(|base_product| english(base_product.name))
+        .unwrap_or_else(|| format!("Product {}", product_id)))
+}
+
+fn english(translations: Vec<Translation>) -> String {
+    let english = translations.iter().find(|translation| translation.lang == Language::En).cloned();
+    english
+        .or_else(|| translations.into_iter().next())
+        .map(|translation| translation.text)
+        .unwrap_or_default()
+}
+
+fn from_field_error(e: FieldError) -> FailureError {
+    format_err!("Receipt request failed: {:?}", e).context(Error::HttpClient).into()
+}
@@ -9,6 +9,9 @@ pub enum Route {
     VerifyEmail(String),
     ResetPassword,
     RegisterDevice,
+    InvoiceUpdated(String, Option<String>),
+    OrderReceipt(String),
+    OrdersExport(String),
 }
 
 pub fn create_route_parser() -> RouteParser<Route> {
@@ -21,5 +24,21 @@ pub fn create_route_parser() -> RouteParser<Route> {
     });
     router.add_route(r"^/reset_password$", || Route::ResetPassword);
     router.add_route(r"^/register_device", || Route::RegisterDevice);
+    router.add_route_with_params(r"^/invoices/([^/]+)/updates$", |params| {
+        params.get(0).map(|id| Route::InvoiceUpdated(id.to_string(), None))
+    });
+    router.add_route_with_params(r"^/invoices/([^/]+)/updates/([^/]+)$", |params| {
+        if let (Some(id), Some(revision)) = (params.get(0), params.get(1)) {
+            Some(Route::InvoiceUpdated(id.to_string(), Some(revision.to_string())))
+        } else {
+            None
+        }
+    });
+    router.add_route_with_params(r"^/orders/([^/]+)/receipt$", |params| {
+        params.get(0).map(|slug| Route::OrderReceipt(slug.to_string()))
+    });
+    router.add_route_with_params(r"^/exports/orders/([^/]+)$", |params| {
+        params.get(0).map(|token| Route::OrdersExport(token.to_string()))
+    });
     router
 }
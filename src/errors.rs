@@ -5,6 +5,9 @@ use serde_json;
 use stq_api::errors::{Error as ApiError, ErrorMessage};
 use stq_http::errors::{Codeable, PayloadCarrier};
 
+/// Error code of the graphql errors returned when the current user is not allowed to do the action
+pub const FORBIDDEN_CODE: i32 = 120;
+
 #[derive(Debug, Fail)]
 pub enum Error {
     #[fail(display = "Not found")]
@@ -1,8 +1,13 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::SystemTime;
 
 use chrono::prelude::*;
 use futures::future;
 use futures::prelude::*;
+use futures_cpupool::CpuPool;
 use hyper::header::{Authorization, Cookie, Headers};
 use juniper;
 use juniper::parser::SourcePosition;
@@ -13,7 +18,8 @@ use serde::ser;
 use serde::ser::SerializeMap;
 use uuid::Uuid;
 
-use config::Config;
+use config::{CarrierAdapter, Config};
+use url::{Url, UrlBuilder};
 
 use http::{
     header::{HeaderName, HeaderValue},
@@ -25,13 +31,14 @@ use stq_http::request_util::{CorrelationToken, Currency as CurrencyHeader, FiatC
 use stq_routes::model::Model;
 use stq_routes::service::Service;
 use stq_static_resources::Currency;
-use stq_types::{SessionId, StoresRole};
+use stq_types::{CompanyId, SessionId, StoreId, StoresRole, UserId};
 
-use graphql::models::jwt::JWTPayload;
-use graphql::models::User;
+use graphql::models::jwt::{JWTPayload, JWT};
+use graphql::models::{BillingMicroserviceRole, Company, CurrencyExchangeData, NewRole, Synonyms, User, Visitor};
 
 use graphql::microservice::{
-    BillingService, BillingServiceImpl, DeliveryService, DeliveryServiceImpl, OrdersService, OrdersServiceImpl, SagaService,
+    BillingService, BillingServiceImpl, CarrierService, CarrierServiceImpl, CoPurchasesCache, DeliveryService, DeliveryServiceImpl,
+    FileCarrierService, FileRecommendationsService, OrdersService, OrdersServiceImpl, RecommendationsService, SagaService,
     SagaServiceImpl, StoresService, StoresServiceImpl,
 };
 
@@ -44,12 +51,27 @@ pub struct Context {
     pub correlation_token: Option<CorrelationToken>,
     pub uuid: String,
     pub config: Config,
+    permissions_cache: Mutex<PermissionsCache>,
+    companies_cache: Mutex<HashMap<CompanyId, Option<Company>>>,
+    exchange_rates: Mutex<Option<CurrencyExchangeData>>,
+    background_pool: Option<CpuPool>,
+    co_purchases_cache: Arc<CoPurchasesCache>,
+    synonyms: Arc<Synonyms>,
 }
 
 pub struct Permissions<'r> {
     context: &'r Context,
 }
 
+/// Roles of the current user and store owners fetched once per request,
+/// lists of orders check them for every order
+#[derive(Default)]
+struct PermissionsCache {
+    store_roles: Option<Vec<StoresRole>>,
+    billing_roles: Option<Vec<NewRole<BillingMicroserviceRole>>>,
+    store_owners: HashMap<StoreId, Option<UserId>>,
+}
+
 impl juniper::Context for Context {}
 
 impl Context {
@@ -73,10 +95,56 @@ impl Context {
             uuid,
             config,
             correlation_token,
+            permissions_cache: Mutex::new(PermissionsCache::default()),
+            companies_cache: Mutex::new(HashMap::new()),
+            exchange_rates: Mutex::new(None),
+            background_pool: None,
+            co_purchases_cache: Arc::new(CoPurchasesCache::default()),
+            synonyms: Arc::new(Synonyms::default()),
+        }
+    }
+
+    /// Co-purchases shared by the requests, without it the file is read by every request
+    pub fn with_co_purchases_cache(mut self, cache: Arc<CoPurchasesCache>) -> Self {
+        self.co_purchases_cache = cache;
+        self
+    }
+
+    /// Synonyms read at startup, without them product searches are not expanded
+    pub fn with_synonyms(mut self, synonyms: Arc<Synonyms>) -> Self {
+        self.synonyms = synonyms;
+        self
+    }
+
+    pub fn synonyms(&self) -> &Synonyms {
+        &self.synonyms
+    }
+
+    /// Pool running requests whose results the response does not wait for
+    pub fn with_background_pool(mut self, cpu_pool: CpuPool) -> Self {
+        self.background_pool = Some(cpu_pool);
+        self
+    }
+
+    /// Runs the request in background, without the pool it is completed before returning
+    pub fn spawn<F>(&self, request: F)
+    where
+        F: Future<Item = (), Error = ()> + Send + 'static,
+    {
+        match self.background_pool {
+            Some(ref cpu_pool) => cpu_pool.spawn(request).forget(),
+            None => {
+                let _ = request.wait();
+            }
         }
     }
 
     pub fn get_rest_api_client(&self, s: Service) -> RestApiClient {
+        self.get_rest_api_client_as(s, self.user.clone().map(|u| u.user_id))
+    }
+
+    /// Client acting on behalf of `user_id`, e.g. of the user who has just logged in
+    pub fn get_rest_api_client_as(&self, s: Service, user_id: Option<UserId>) -> RestApiClient {
         let header_name = HeaderName::from_static("correlation-token");
 
         let headers = match self.correlation_token.clone() {
@@ -86,10 +154,10 @@ impl Context {
         .into_iter()
         .collect::<HeaderMap>();
 
-        RestApiClient::new_with_default_headers(&self.config.service_url(s), self.user.clone().map(|u| u.user_id), Some(headers))
+        RestApiClient::new_with_default_headers(&self.config.service_url(s), user_id, Some(headers))
     }
 
-    pub fn request<T>(&self, method: hyper::Method, url: String, body: Option<String>) -> Box<Future<Item = T, Error = FieldError> + Send>
+    pub fn request<T>(&self, method: hyper::Method, url: Url, body: Option<String>) -> Box<Future<Item = T, Error = FieldError> + Send>
     where
         T: DeserializeOwned + 'static + Send,
     {
@@ -129,7 +197,7 @@ impl Context {
 
         Box::new(
             self.http_client
-                .request_json(method, url.clone(), body, Some(headers))
+                .request_json(method, url.to_string(), body, Some(headers))
                 .map_err(Error::into_graphql)
                 .then(move |r| {
                     let d = Local::now() - dt;
@@ -137,7 +205,7 @@ impl Context {
                         Err(e) => {
                             info!(
                                 "Request to microservice: {:?} failed with error `{:?}`, elapsed time: {}.{:03}, correlation token: {}",
-                                url,
+                                url.as_str(),
                                 e,
                                 d.num_seconds(),
                                 d.num_milliseconds(),
@@ -148,7 +216,7 @@ impl Context {
                         Ok(x) => {
                             info!(
                                 "Request to microservice: {:?}, elapsed time: {}.{:03}, correlation token: {}",
-                                url,
+                                url.as_str(),
                                 d.num_seconds(),
                                 d.num_milliseconds(),
                                 correlation_token,
@@ -163,7 +231,7 @@ impl Context {
     pub fn request_without_auth<T>(
         &self,
         method: hyper::Method,
-        url: String,
+        url: Url,
         body: Option<String>,
     ) -> Box<Future<Item = T, Error = FieldError> + Send>
     where
@@ -176,7 +244,7 @@ impl Context {
 
         Box::new(
             self.http_client
-                .request_json(method, url.clone(), body, Some(headers))
+                .request_json(method, url.to_string(), body, Some(headers))
                 .map_err(Error::into_graphql)
                 .then(move |r| {
                     let d = Local::now() - dt;
@@ -184,7 +252,7 @@ impl Context {
                         Err(e) => {
                             info!(
                                 "Request to microservice: {:?} failed with error `{:?}`, elapsed time: {}.{:03}, correlation token: {}",
-                                url,
+                                url.as_str(),
                                 e,
                                 d.num_seconds(),
                                 d.num_milliseconds(),
@@ -195,7 +263,7 @@ impl Context {
                         Ok(x) => {
                             info!(
                                 "Request to microservice: {:?}, elapsed time: {}.{:03}, correlation token: {}",
-                                url,
+                                url.as_str(),
                                 d.num_seconds(),
                                 d.num_milliseconds(),
                                 correlation_token,
@@ -226,6 +294,50 @@ impl Context {
         Box::new(DeliveryServiceImpl::new(self))
     }
 
+    /// Delivery company fetched once per request, shipments resolve it in several fields
+    pub fn company(&self, company_id: CompanyId) -> FieldResult<Option<Company>> {
+        if let Some(company) = self.companies_cache.lock().unwrap_or_else(|e| e.into_inner()).get(&company_id) {
+            return Ok(company.clone());
+        }
+
+        let company = self.get_delivery_microservice().get_company(company_id)?;
+        self.companies_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(company_id, company.clone());
+        Ok(company)
+    }
+
+    /// Exchange rates fetched once per request, so that all prices of the response are converted with the same rates
+    pub fn exchange_rates(&self) -> FieldResult<CurrencyExchangeData> {
+        if let Some(ref exchange_rates) = *self.exchange_rates.lock().unwrap_or_else(|e| e.into_inner()) {
+            return Ok(exchange_rates.clone());
+        }
+
+        let exchange_rates = self.get_stores_microservice().get_currency_exchange_info()?.data;
+        *self.exchange_rates.lock().unwrap_or_else(|e| e.into_inner()) = Some(exchange_rates.clone());
+        Ok(exchange_rates)
+    }
+
+    /// Converts prices of the rest of the request with the rates frozen by a checkout quote
+    pub fn freeze_exchange_rates(&self, exchange_rates: CurrencyExchangeData) {
+        *self.exchange_rates.lock().unwrap_or_else(|e| e.into_inner()) = Some(exchange_rates);
+    }
+
+    pub fn get_carrier_service<'r>(&'r self) -> Box<dyn CarrierService + 'r> {
+        match self.config.carrier.adapter {
+            CarrierAdapter::Delivery => Box::new(CarrierServiceImpl::new(self)),
+            CarrierAdapter::File => Box::new(FileCarrierService::new(self.config.carrier.events_path.clone().unwrap_or_default())),
+        }
+    }
+
+    pub fn get_recommendations_service<'r>(&'r self) -> Box<dyn RecommendationsService + 'r> {
+        Box::new(FileRecommendationsService::new(
+            self.config.recommendations.co_purchases_path.clone(),
+            self.co_purchases_cache.clone(),
+        ))
+    }
+
     pub fn get_stores_microservice<'r>(&'r self) -> Box<dyn StoresService + 'r> {
         Box::new(StoresServiceImpl::new(self))
     }
@@ -234,17 +346,141 @@ impl Context {
         Box::new(OrdersServiceImpl::new(self))
     }
 
+    pub fn get_notifications_microservice<'r>(&'r self) -> Box<dyn NotificationsService + 'r> {
+        Box::new(NotificationsServiceImpl::new(self))
+    }
+
     pub fn permissions(&self) -> Permissions {
         Permissions { context: &self }
     }
+
+    /// Id of the logged in user, `message` tells which action requires the user
+    pub fn current_user_id(&self, message: &str) -> FieldResult<UserId> {
+        self.user.as_ref().map(|user| user.user_id).ok_or_else(|| {
+            FieldError::new(
+                message,
+                graphql_value!({ "code": 100, "details": { "No user id in request header." }}),
+            )
+        })
+    }
+
+    /// Logged in user, or anonymous session if there is no user
+    pub fn visitor(&self) -> Option<Visitor> {
+        match (self.user.as_ref(), self.session_id) {
+            (Some(user), _) => Some(user.user_id.into()),
+            (None, Some(session_id)) => Some(session_id.into()),
+            (None, None) => None,
+        }
+    }
+
+    /// Visitor whose wishlist or history is used, `message` tells which action requires the visitor
+    pub fn current_visitor(&self, message: &str) -> FieldResult<Visitor> {
+        self.visitor().ok_or_else(|| {
+            FieldError::new(
+                message,
+                graphql_value!({ "code": 100, "details": { "No user id or session id in request header." }}),
+            )
+        })
+    }
+
+    /// Payload of the token issued to the user, its signature is checked with the public key from config
+    pub fn verify_jwt(&self, jwt: &JWT) -> FieldResult<JWTPayload> {
+        let mut jwt_public_key = vec![];
+        File::open(&self.config.jwt.public_key_path)?.read_to_end(&mut jwt_public_key)?;
+        jwt.verified_payload(&jwt_public_key, self.config.jwt.leeway).map_err(|e| {
+            FieldError::new(
+                "Could not read token payload.",
+                graphql_value!({ "code": 100, "details": { format!("{}", e) }}),
+            )
+        })
+    }
 }
 
 impl<'r> Permissions<'r> {
     pub fn store_roles(&self) -> FieldResult<Vec<StoresRole>> {
-        if let Some(current_user_id) = self.context.user.as_ref().map(|jwt| jwt.user_id) {
-            return self.context.get_stores_microservice().roles(current_user_id);
+        let current_user_id = match self.context.user.as_ref().map(|jwt| jwt.user_id) {
+            Some(current_user_id) => current_user_id,
+            None => return Ok(Vec::new()),
+        };
+        if let Some(ref roles) = self.cache().store_roles {
+            return Ok(roles.clone());
         }
-        Ok(Vec::new())
+
+        let roles = self.context.get_stores_microservice().roles(current_user_id)?;
+        self.cache().store_roles = Some(roles.clone());
+        Ok(roles)
+    }
+
+    pub fn billing_roles(&self) -> FieldResult<Vec<NewRole<BillingMicroserviceRole>>> {
+        let current_user_id = match self.context.user.as_ref().map(|jwt| jwt.user_id) {
+            Some(current_user_id) => current_user_id,
+            None => return Ok(Vec::new()),
+        };
+        if let Some(ref roles) = self.cache().billing_roles {
+            return Ok(roles.clone());
+        }
+
+        let roles = self.context.get_billing_microservice().roles(current_user_id)?;
+        self.cache().billing_roles = Some(roles.clone());
+        Ok(roles)
+    }
+
+    pub fn store_owner(&self, store_id: StoreId) -> FieldResult<Option<UserId>> {
+        if let Some(owner) = self.cache().store_owners.get(&store_id) {
+            return Ok(*owner);
+        }
+
+        let owner = self
+            .context
+            .get_stores_microservice()
+            .get_store_by_id(store_id)?
+            .map(|store| store.user_id);
+        self.cache().store_owners.insert(store_id, owner);
+        Ok(owner)
+    }
+
+    /// Owner of the store or the user with store manager role for it
+    pub fn is_store_manager(&self, store_id: StoreId) -> FieldResult<bool> {
+        let current_user_id = match self.context.user.as_ref().map(|jwt| jwt.user_id) {
+            Some(current_user_id) => current_user_id,
+            None => return Ok(false),
+        };
+
+        let has_role = self
+            .billing_roles()?
+            .into_iter()
+            .any(|role| role.name == BillingMicroserviceRole::StoreManager && role.data == Some(store_id));
+        if has_role {
+            return Ok(true);
+        }
+
+        Ok(self.store_owner(store_id)? == Some(current_user_id))
+    }
+
+    /// Owner and managers of the store manage its products and orders, as well as superusers
+    pub fn manages_store(&self, store_id: StoreId) -> FieldResult<bool> {
+        let is_superuser = self.store_roles()?.contains(&StoresRole::Superuser)
+            || self
+                .billing_roles()?
+                .into_iter()
+                .any(|role| role.name == BillingMicroserviceRole::Superuser);
+
+        Ok(is_superuser || self.is_store_manager(store_id)?)
+    }
+
+    /// Financial managers and managers of the store can manage billing of its orders
+    pub fn manages_store_billing(&self, store_id: StoreId) -> FieldResult<bool> {
+        let has_role = self.billing_roles()?.into_iter().any(|role| match role.name {
+            BillingMicroserviceRole::Superuser | BillingMicroserviceRole::FinancialManager => true,
+            BillingMicroserviceRole::StoreManager | BillingMicroserviceRole::User => false,
+        });
+
+        Ok(has_role || self.is_store_manager(store_id)?)
+    }
+
+    fn cache(&self) -> MutexGuard<PermissionsCache> {
+        // the cache only holds fetched values, so it is still valid if other thread panicked
+        self.context.permissions_cache.lock().unwrap_or_else(|e| e.into_inner())
     }
 }
 
@@ -255,9 +491,9 @@ pub fn check_jwt_not_revoked(
 ) -> Result<(), FieldError> {
     let mut headers = Headers::new();
     headers.set(Authorization(jwt_payload.to_string()));
-    let url = format!("{}/{}/current", users_url, Model::User.to_url());
+    let url = UrlBuilder::new(users_url).model(Model::User).path("current").build();
     let user = http_client
-        .request_json::<Option<User>>(hyper::Method::Get, url, None, Some(headers))
+        .request_json::<Option<User>>(hyper::Method::Get, url.into_string(), None, Some(headers))
         .map_err(Error::into_graphql)
         .wait()?;
     if let Some(user) = user {
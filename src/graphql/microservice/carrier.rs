@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::fs::File;
+
+use futures::Future;
+use hyper::Method;
+use juniper::FieldResult;
+
+use stq_routes::model::Model;
+use stq_routes::service::Service;
+
+use graphql::context::Context;
+use graphql::models::*;
+use url::UrlBuilder;
+
+/// Source of shipment tracking events, chosen by `carrier.adapter` config
+pub trait CarrierService {
+    fn tracking_events(&self, company: &Company, tracking_number: &str) -> FieldResult<Vec<TrackingEvent>>;
+}
+
+/// Fetches tracking events through delivery microservice, which talks to the carrier APIs
+pub struct CarrierServiceImpl<'ctx> {
+    context: &'ctx Context,
+}
+
+impl<'ctx> CarrierServiceImpl<'ctx> {
+    pub fn new(context: &'ctx Context) -> Self {
+        CarrierServiceImpl { context }
+    }
+
+    fn base_url(&self) -> String {
+        self.context.config.service_url(Service::Delivery)
+    }
+
+    fn request_url(&self) -> UrlBuilder {
+        UrlBuilder::new(self.base_url())
+    }
+}
+
+impl<'ctx> CarrierService for CarrierServiceImpl<'ctx> {
+    fn tracking_events(&self, company: &Company, tracking_number: &str) -> FieldResult<Vec<TrackingEvent>> {
+        let url = self
+            .request_url()
+            .model(Model::Company)
+            .segment(company.id)
+            .path("tracking")
+            .segment(tracking_number)
+            .build();
+        self.context.request(Method::Get, url, None).wait()
+    }
+}
+
+/// Reads tracking events from json file `{ "<company id>": { "<tracking number>": [<event>] } }`,
+/// used in tests instead of the real carriers
+pub struct FileCarrierService {
+    path: String,
+}
+
+impl FileCarrierService {
+    pub fn new(path: String) -> Self {
+        FileCarrierService { path }
+    }
+}
+
+impl CarrierService for FileCarrierService {
+    fn tracking_events(&self, company: &Company, tracking_number: &str) -> FieldResult<Vec<TrackingEvent>> {
+        let file = File::open(&self.path)?;
+        let mut events: HashMap<String, HashMap<String, Vec<TrackingEvent>>> = serde_json::from_reader(file)?;
+        Ok(events
+            .remove(&company.id.to_string())
+            .and_then(|mut by_number| by_number.remove(tracking_number))
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stq_static_resources::Currency;
+    use stq_types::CompanyId;
+
+    use super::*;
+
+    const FIXTURE_PATH: &str = "config/fixtures/tracking_events.json";
+
+    fn company(id: i32) -> Company {
+        Company {
+            id: CompanyId(id),
+            name: "Test delivery".to_string(),
+            label: "TEST".to_string(),
+            description: None,
+            deliveries_from: vec![],
+            currency: Currency::STQ,
+            logo: String::new(),
+            tracking_url_template: None,
+        }
+    }
+
+    #[test]
+    fn file_carrier_returns_events_of_tracking_number() {
+        let events = FileCarrierService::new(FIXTURE_PATH.to_string())
+            .tracking_events(&company(1), "TEST000001")
+            .unwrap();
+
+        let statuses = events.iter().map(|event| event.status).collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![TrackingStatus::InfoReceived, TrackingStatus::InTransit, TrackingStatus::Delivered]
+        );
+        assert_eq!(events[1].location, Some("Moscow".to_string()));
+    }
+
+    #[test]
+    fn file_carrier_returns_no_events_of_unknown_shipment() {
+        let carrier = FileCarrierService::new(FIXTURE_PATH.to_string());
+
+        assert!(carrier.tracking_events(&company(1), "UNKNOWN").unwrap().is_empty());
+        assert!(carrier.tracking_events(&company(2), "TEST000001").unwrap().is_empty());
+    }
+
+    #[test]
+    fn file_carrier_fails_without_file() {
+        let carrier = FileCarrierService::new("config/fixtures/missing.json".to_string());
+
+        assert!(carrier.tracking_events(&company(1), "TEST000001").is_err());
+    }
+}
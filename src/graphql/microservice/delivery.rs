@@ -4,12 +4,22 @@ use juniper::FieldResult;
 
 use stq_routes::model::Model;
 use stq_routes::service::Service;
+use stq_types::{CompanyId, OrderId};
 
 use graphql::context::Context;
 use graphql::models::*;
+use url::UrlBuilder;
 
 pub trait DeliveryService {
     fn update_user_delivery_address(&self, input: UpdateUserDeliveryAddressFullInput) -> FieldResult<UserDeliveryAddress>;
+
+    fn get_company(&self, company_id: CompanyId) -> FieldResult<Option<Company>>;
+
+    fn create_shipment(&self, input: NewShipment) -> FieldResult<Shipment>;
+
+    fn shipments_by_order_id(&self, order_id: OrderId) -> FieldResult<Vec<Shipment>>;
+
+    fn delete_shipment(&self, shipment_id: ShipmentId) -> FieldResult<Option<Shipment>>;
 }
 
 pub struct DeliveryServiceImpl<'ctx> {
@@ -25,17 +35,37 @@ impl<'ctx> DeliveryServiceImpl<'ctx> {
         self.context.config.service_url(Service::Delivery)
     }
 
-    fn request_url(&self, request: &str) -> String {
-        format!("{}/{}", self.base_url(), request)
+    fn request_url(&self) -> UrlBuilder {
+        UrlBuilder::new(self.base_url())
     }
 }
 
 impl<'ctx> DeliveryService for DeliveryServiceImpl<'ctx> {
     fn update_user_delivery_address(&self, input: UpdateUserDeliveryAddressFullInput) -> FieldResult<UserDeliveryAddress> {
-        let request_path = format!("{}/addresses/{}", Model::User.to_url(), input.id);
-        let url = self.request_url(&request_path);
+        let url = self.request_url().model(Model::User).path("addresses").segment(input.id).build();
 
         let body: String = serde_json::to_string(&input)?.to_string();
         self.context.request::<UserDeliveryAddress>(Method::Put, url, Some(body)).wait()
     }
+
+    fn get_company(&self, company_id: CompanyId) -> FieldResult<Option<Company>> {
+        let url = self.request_url().model(Model::Company).segment(company_id).build();
+        self.context.request(Method::Get, url, None).wait()
+    }
+
+    fn create_shipment(&self, input: NewShipment) -> FieldResult<Shipment> {
+        let url = self.request_url().path("shipments").build();
+        let body = serde_json::to_string(&input)?;
+        self.context.request(Method::Post, url, Some(body)).wait()
+    }
+
+    fn shipments_by_order_id(&self, order_id: OrderId) -> FieldResult<Vec<Shipment>> {
+        let url = self.request_url().path("shipments/by-order-id").segment(order_id).build();
+        self.context.request(Method::Get, url, None).wait()
+    }
+
+    fn delete_shipment(&self, shipment_id: ShipmentId) -> FieldResult<Option<Shipment>> {
+        let url = self.request_url().path("shipments").segment(shipment_id).build();
+        self.context.request(Method::Delete, url, None).wait()
+    }
 }
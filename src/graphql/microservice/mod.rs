@@ -12,9 +12,18 @@ pub use self::billing::*;
 mod delivery;
 pub use self::delivery::*;
 
+mod carrier;
+pub use self::carrier::*;
+
 mod order;
 pub use self::order::*;
 
+mod recommendations;
+pub use self::recommendations::*;
+
+mod notifications;
+pub use self::notifications::*;
+
 pub mod requests;
 pub use self::requests::*;
 
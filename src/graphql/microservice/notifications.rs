@@ -0,0 +1,40 @@
+use futures::Future;
+use hyper::Method;
+use juniper::FieldResult;
+
+use stq_routes::service::Service;
+
+use graphql::context::Context;
+use graphql::models::*;
+use url::UrlBuilder;
+
+pub trait NotificationsService {
+    /// Notifications service looks up the user and sends the email
+    fn send_saved_search_matches(&self, input: SavedSearchMatches) -> FieldResult<()>;
+}
+
+pub struct NotificationsServiceImpl<'ctx> {
+    context: &'ctx Context,
+}
+
+impl<'ctx> NotificationsServiceImpl<'ctx> {
+    pub fn new(context: &'ctx Context) -> Self {
+        NotificationsServiceImpl { context }
+    }
+
+    fn base_url(&self) -> String {
+        self.context.config.service_url(Service::Notifications)
+    }
+
+    fn request_url(&self) -> UrlBuilder {
+        UrlBuilder::new(self.base_url())
+    }
+}
+
+impl<'ctx> NotificationsService for NotificationsServiceImpl<'ctx> {
+    fn send_saved_search_matches(&self, input: SavedSearchMatches) -> FieldResult<()> {
+        let url = self.request_url().path("users/saved-search-matches").build();
+        let body: String = serde_json::to_string(&input)?;
+        self.context.request(Method::Post, url, Some(body)).wait()
+    }
+}
@@ -1,6 +1,7 @@
 use futures::Future;
 use hyper::Method;
 use juniper::FieldResult;
+use serde_json;
 
 use stq_api::orders::Order;
 use stq_routes::model::Model;
@@ -9,11 +10,34 @@ use stq_types::{OrderId, OrderSlug};
 
 use graphql::context::Context;
 use graphql::models::*;
+use url::UrlBuilder;
 
 pub trait OrdersService {
     fn get_order_by_id(&self, order_id: OrderId) -> FieldResult<Option<GraphQLOrder>>;
 
     fn get_order_by_slug(&self, order_slug: OrderSlug) -> FieldResult<Option<GraphQLOrder>>;
+
+    fn create_dispute(&self, input: NewDispute) -> FieldResult<Dispute>;
+
+    fn get_dispute(&self, dispute_id: DisputeId) -> FieldResult<Option<Dispute>>;
+
+    fn get_dispute_by_order(&self, order_id: OrderId) -> FieldResult<Option<Dispute>>;
+
+    fn search_disputes(&self, skip: i32, count: i32, input: DisputeSearch) -> FieldResult<DisputeSearchResults>;
+
+    fn add_dispute_message(&self, dispute_id: DisputeId, input: NewDisputeMessage) -> FieldResult<Dispute>;
+
+    fn update_dispute_status(&self, dispute_id: DisputeId, input: UpdateDisputeStatus) -> FieldResult<Dispute>;
+
+    fn delete_dispute(&self, dispute_id: DisputeId) -> FieldResult<Option<Dispute>>;
+
+    fn create_return(&self, input: NewOrderReturn) -> FieldResult<OrderReturn>;
+
+    fn get_return(&self, return_id: ReturnId) -> FieldResult<Option<OrderReturn>>;
+
+    fn search_returns(&self, skip: i32, count: i32, input: OrderReturnSearch) -> FieldResult<OrderReturnSearchResults>;
+
+    fn update_return(&self, return_id: ReturnId, input: UpdateOrderReturn) -> FieldResult<OrderReturn>;
 }
 
 pub struct OrdersServiceImpl<'ctx> {
@@ -29,23 +53,83 @@ impl<'ctx> OrdersServiceImpl<'ctx> {
         self.context.config.service_url(Service::Orders)
     }
 
-    fn request_url(&self, request: &str) -> String {
-        format!("{}/{}", self.base_url(), request)
+    fn request_url(&self) -> UrlBuilder {
+        UrlBuilder::new(self.base_url())
     }
 }
 
 impl<'ctx> OrdersService for OrdersServiceImpl<'ctx> {
     fn get_order_by_id(&self, order_id: OrderId) -> FieldResult<Option<GraphQLOrder>> {
-        let request_path = format!("{}/by-id/{}", Model::Order.to_url(), order_id);
-        let url = self.request_url(&request_path);
+        let url = self.request_url().model(Model::Order).path("by-id").segment(order_id).build();
         let order: Option<Order> = self.context.request(Method::Get, url, None).wait()?;
         Ok(order.map(GraphQLOrder))
     }
 
     fn get_order_by_slug(&self, order_slug: OrderSlug) -> FieldResult<Option<GraphQLOrder>> {
-        let request_path = format!("{}/by-slug/{}", Model::Order.to_url(), order_slug);
-        let url = self.request_url(&request_path);
+        let url = self.request_url().model(Model::Order).path("by-slug").segment(order_slug).build();
         let order: Option<Order> = self.context.request(Method::Get, url, None).wait()?;
         Ok(order.map(GraphQLOrder))
     }
+
+    fn create_dispute(&self, input: NewDispute) -> FieldResult<Dispute> {
+        let url = self.request_url().path("disputes").build();
+        let body = serde_json::to_string(&input)?;
+        self.context.request(Method::Post, url, Some(body)).wait()
+    }
+
+    fn get_dispute(&self, dispute_id: DisputeId) -> FieldResult<Option<Dispute>> {
+        let url = self.request_url().path("disputes").segment(dispute_id).build();
+        self.context.request(Method::Get, url, None).wait()
+    }
+
+    fn get_dispute_by_order(&self, order_id: OrderId) -> FieldResult<Option<Dispute>> {
+        let url = self.request_url().path("disputes/by-order-id").segment(order_id).build();
+        self.context.request(Method::Get, url, None).wait()
+    }
+
+    fn search_disputes(&self, skip: i32, count: i32, input: DisputeSearch) -> FieldResult<DisputeSearchResults> {
+        let url = self.request_url().path("disputes/search").query("skip", skip).query("count", count).build();
+        let body = serde_json::to_string(&input)?;
+        self.context.request(Method::Post, url, Some(body)).wait()
+    }
+
+    fn add_dispute_message(&self, dispute_id: DisputeId, input: NewDisputeMessage) -> FieldResult<Dispute> {
+        let url = self.request_url().path("disputes").segment(dispute_id).path("messages").build();
+        let body = serde_json::to_string(&input)?;
+        self.context.request(Method::Post, url, Some(body)).wait()
+    }
+
+    fn update_dispute_status(&self, dispute_id: DisputeId, input: UpdateDisputeStatus) -> FieldResult<Dispute> {
+        let url = self.request_url().path("disputes").segment(dispute_id).path("status").build();
+        let body = serde_json::to_string(&input)?;
+        self.context.request(Method::Put, url, Some(body)).wait()
+    }
+
+    fn delete_dispute(&self, dispute_id: DisputeId) -> FieldResult<Option<Dispute>> {
+        let url = self.request_url().path("disputes").segment(dispute_id).build();
+        self.context.request(Method::Delete, url, None).wait()
+    }
+
+    fn create_return(&self, input: NewOrderReturn) -> FieldResult<OrderReturn> {
+        let url = self.request_url().path("returns").build();
+        let body = serde_json::to_string(&input)?;
+        self.context.request(Method::Post, url, Some(body)).wait()
+    }
+
+    fn get_return(&self, return_id: ReturnId) -> FieldResult<Option<OrderReturn>> {
+        let url = self.request_url().path("returns").segment(return_id).build();
+        self.context.request(Method::Get, url, None).wait()
+    }
+
+    fn search_returns(&self, skip: i32, count: i32, input: OrderReturnSearch) -> FieldResult<OrderReturnSearchResults> {
+        let url = self.request_url().path("returns/search").query("skip", skip).query("count", count).build();
+        let body = serde_json::to_string(&input)?;
+        self.context.request(Method::Post, url, Some(body)).wait()
+    }
+
+    fn update_return(&self, return_id: ReturnId, input: UpdateOrderReturn) -> FieldResult<OrderReturn> {
+        let url = self.request_url().path("returns").segment(return_id).build();
+        let body = serde_json::to_string(&input)?;
+        self.context.request(Method::Put, url, Some(body)).wait()
+    }
 }
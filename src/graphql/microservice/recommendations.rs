@@ -0,0 +1,78 @@
+use std::fs::{self, File};
+use std::io::ErrorKind;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use juniper::FieldResult;
+
+use stq_types::BaseProductId;
+
+use graphql::models::*;
+
+/// Source of "customers also bought" data
+pub trait RecommendationsService {
+    /// Products bought together with any of `base_product_ids`, excluding them, most often bought first
+    fn also_bought(&self, base_product_ids: &[BaseProductId]) -> FieldResult<Vec<BaseProductId>>;
+
+    /// Products bought by the most customers, best sellers first
+    fn best_sellers(&self) -> FieldResult<Vec<BaseProductId>>;
+}
+
+/// Co-purchases file kept in memory between requests, it is read again when the job replaces it
+#[derive(Default)]
+pub struct CoPurchasesCache {
+    loaded: Mutex<Option<(SystemTime, Arc<CoPurchases>)>>,
+}
+
+impl CoPurchasesCache {
+    /// Co-purchases of the file, none until the job has run
+    fn get(&self, path: &str) -> FieldResult<Option<Arc<CoPurchases>>> {
+        let modified = match fs::metadata(path).and_then(|metadata| metadata.modified()) {
+            Ok(modified) => modified,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        // the lock is held while the file is parsed, so concurrent requests do not parse it again
+        let mut loaded = self.loaded.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((loaded_modified, ref co_purchases)) = *loaded {
+            if loaded_modified == modified {
+                return Ok(Some(co_purchases.clone()));
+            }
+        }
+
+        let co_purchases: Arc<CoPurchases> = Arc::new(serde_json::from_reader(File::open(path)?)?);
+        *loaded = Some((modified, co_purchases.clone()));
+        Ok(Some(co_purchases))
+    }
+}
+
+/// Reads co-purchases written by `co_purchases` job, there are no co-purchases until the job has run
+pub struct FileRecommendationsService {
+    path: String,
+    cache: Arc<CoPurchasesCache>,
+}
+
+impl FileRecommendationsService {
+    pub fn new(path: String, cache: Arc<CoPurchasesCache>) -> Self {
+        FileRecommendationsService { path, cache }
+    }
+}
+
+impl RecommendationsService for FileRecommendationsService {
+    fn also_bought(&self, base_product_ids: &[BaseProductId]) -> FieldResult<Vec<BaseProductId>> {
+        Ok(self
+            .cache
+            .get(&self.path)?
+            .map(|co_purchases| co_purchases.also_bought(base_product_ids))
+            .unwrap_or_default())
+    }
+
+    fn best_sellers(&self) -> FieldResult<Vec<BaseProductId>> {
+        Ok(self
+            .cache
+            .get(&self.path)?
+            .map(|co_purchases| co_purchases.best_sellers.clone())
+            .unwrap_or_default())
+    }
+}
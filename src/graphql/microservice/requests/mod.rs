@@ -1,4 +1,5 @@
 use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
 use stq_types::{BaseProductId, OrderId, ProductId, StoreId};
 
 use graphql::models::customer_id::CustomerId;
@@ -46,6 +47,27 @@ pub struct GetProductsRequest {
     pub ids: Vec<ProductId>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct GetStocksRequest {
+    pub product_ids: Vec<ProductId>,
+}
+
+/// Claims saved searches with alerts enabled checked before `checked_before`,
+/// claimed searches are not returned to other claims for `claim_duration_s`
+#[derive(Debug, Serialize)]
+pub struct ClaimSavedSearchesRequest {
+    pub count: i32,
+    pub checked_before: DateTime<Utc>,
+    pub claim_duration_s: u64,
+}
+
+/// Products search limited to the products published after the time
+#[derive(Debug, Serialize)]
+pub struct SearchPublishedAfterRequest {
+    pub search_term: SearchProductInput,
+    pub published_after: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct CalculatePayoutPayload {
     pub store_id: StoreId,
@@ -1,23 +1,31 @@
+use std::cmp;
 use std::str::FromStr;
 
-use futures::Future;
+use futures::future;
+use futures::stream;
+use futures::{Future, Stream};
 use hyper::Method;
-use juniper::FieldResult;
+use juniper::{FieldError, FieldResult};
 use serde_json;
 
 use stq_routes::model::Model;
-use stq_types::{BaseProductId, OrderId};
+use stq_types::{BaseProductId, OrderId, OrderSlug};
 
 use stq_api::orders::Order;
 
 use graphql::context::Context;
 use graphql::models::*;
+use url::UrlBuilder;
 
 pub trait SagaService {
     fn upsert_shipping(&self, base_product_id: BaseProductId, shipping: NewShipping) -> FieldResult<Shipping>;
 
     fn set_order_state(&self, input: UpdateOrderState) -> FieldResult<Option<GraphQLOrder>>;
 
+    /// Sets states of several orders, running at most `gateway.batch_concurrency` requests at once.
+    /// Results are returned in the order of inputs.
+    fn set_order_states(&self, inputs: Vec<UpdateOrderState>) -> Vec<(OrderSlug, FieldResult<Option<GraphQLOrder>>)>;
+
     fn create_orders(&self, input: CreateOrder) -> FieldResult<CreateOrdersOutput>;
 
     fn set_order_payment_state(&self, order_id: OrderId, input: OrderPaymentState) -> FieldResult<()>;
@@ -40,33 +48,50 @@ impl<'ctx> SagaServiceImpl<'ctx> {
         self.context.config.saga_microservice.url.clone()
     }
 
-    fn request_url(&self, request: &str) -> String {
-        format!("{}/{}", self.base_url(), request)
+    fn request_url(&self) -> UrlBuilder {
+        UrlBuilder::new(self.base_url())
+    }
+
+    fn set_order_state_request(&self, input: UpdateOrderState) -> Box<Future<Item = Option<GraphQLOrder>, Error = FieldError> + Send> {
+        let url = self.request_url().model(Model::Order).segment(input.order_slug).path("set_state").build();
+        let body = match serde_json::to_string(&input) {
+            Ok(body) => body,
+            Err(e) => return Box::new(future::err(e.into())),
+        };
+
+        Box::new(
+            self.context
+                .request::<Option<Order>>(Method::Post, url, Some(body))
+                .map(|order| order.map(GraphQLOrder)),
+        )
     }
 }
 
 impl<'ctx> SagaService for SagaServiceImpl<'ctx> {
     fn upsert_shipping(&self, base_product_id: BaseProductId, shipping: NewShipping) -> FieldResult<Shipping> {
-        let request_path = format!("{}/{}/upsert-shipping", Model::BaseProduct.to_url(), base_product_id);
-        let url = self.request_url(&request_path);
+        let url = self.request_url().model(Model::BaseProduct).segment(base_product_id).path("upsert-shipping").build();
 
         let body: String = serde_json::to_string(&shipping)?;
         self.context.request::<Shipping>(Method::Post, url, Some(body)).wait()
     }
 
     fn set_order_state(&self, input: UpdateOrderState) -> FieldResult<Option<GraphQLOrder>> {
-        let request_path = format!("{}/{}/set_state", Model::Order.to_url(), input.order_slug);
-        let url = self.request_url(&request_path);
-        let body = serde_json::to_string(&input)?;
+        self.set_order_state_request(input).wait()
+    }
 
-        self.context
-            .request::<Option<Order>>(Method::Post, url, Some(body))
-            .wait()
-            .map(|order| order.map(GraphQLOrder))
+    fn set_order_states(&self, inputs: Vec<UpdateOrderState>) -> Vec<(OrderSlug, FieldResult<Option<GraphQLOrder>>)> {
+        let concurrency = cmp::max(self.context.config.gateway.batch_concurrency, 1);
+        let requests = inputs.into_iter().map(|input| {
+            let order_slug = input.order_slug;
+            self.set_order_state_request(input)
+                .then(move |result| Ok::<_, ()>((order_slug, result)))
+        });
+
+        stream::iter_ok::<_, ()>(requests).buffered(concurrency).collect().wait().unwrap_or_default()
     }
+
     fn create_orders(&self, input: CreateOrder) -> FieldResult<CreateOrdersOutput> {
-        let request_path = "create_order";
-        let url = self.request_url(&request_path);
+        let url = self.request_url().path("create_order").build();
         let body: String = serde_json::to_string(&input)?.to_string();
         self.context
             .request::<Invoice>(Method::Post, url, Some(body))
@@ -75,8 +100,7 @@ impl<'ctx> SagaService for SagaServiceImpl<'ctx> {
     }
 
     fn buy_now(&self, input: BuyNow) -> FieldResult<CreateOrdersOutput> {
-        let request_path = "buy_now";
-        let url = self.request_url(&request_path);
+        let url = self.request_url().path("buy_now").build();
         let body: String = serde_json::to_string(&input)?.to_string();
         self.context
             .request::<Invoice>(Method::Post, url, Some(body))
@@ -85,8 +109,7 @@ impl<'ctx> SagaService for SagaServiceImpl<'ctx> {
     }
 
     fn set_order_payment_state(&self, order_id: OrderId, input: OrderPaymentState) -> FieldResult<()> {
-        let request_path = format!("{}/{}/set_payment_state", Model::Order.to_url(), order_id);
-        let url = self.request_url(&request_path);
+        let url = self.request_url().model(Model::Order).segment(order_id).path("set_payment_state").build();
         let body = serde_json::to_string(&input)?;
 
         self.context.request::<()>(Method::Post, url, Some(body)).wait()
@@ -95,8 +118,7 @@ impl<'ctx> SagaService for SagaServiceImpl<'ctx> {
     fn update_base_product(&self, input: UpdateBaseProductInput) -> FieldResult<BaseProduct> {
         let identifier = ID::from_str(&*input.id)?;
         let base_product_id = BaseProductId(identifier.raw_id);
-        let request_path = format!("{}/{}/update", Model::BaseProduct.to_url(), base_product_id);
-        let url = self.request_url(&request_path);
+        let url = self.request_url().model(Model::BaseProduct).segment(base_product_id).path("update").build();
 
         let body: String = serde_json::to_string(&input)?;
         self.context.request::<BaseProduct>(Method::Post, url, Some(body)).wait()
@@ -1,14 +1,17 @@
+use chrono::{DateTime, Utc};
+use futures::future;
 use futures::Future;
 use hyper::Method;
-use juniper::FieldResult;
+use juniper::{FieldError, FieldResult};
 
 use stq_routes::model::Model;
 use stq_routes::service::Service;
 use stq_types::{BaseProductId, ProductId, StoreId, StoresRole, UserId};
 
 use graphql::context::Context;
-use graphql::microservice::requests::{GetBaseProductsRequest, GetProductsRequest};
+use graphql::microservice::requests::{ClaimSavedSearchesRequest, GetBaseProductsRequest, GetProductsRequest};
 use graphql::models::*;
+use url::UrlBuilder;
 
 pub trait StoresService {
     fn get_store_by_id(&self, store_id: StoreId) -> FieldResult<Option<Store>>;
@@ -30,6 +33,58 @@ pub trait StoresService {
     fn get_products_by_base_product(&self, base_product_id: BaseProductId) -> FieldResult<Vec<Product>>;
 
     fn get_products(&self, input: GetProductsRequest) -> FieldResult<Vec<Product>>;
+
+    fn wishlist(&self, visitor: Visitor, skip: i32, count: i32) -> FieldResult<WishlistSearchResults>;
+
+    fn add_to_wishlist(&self, visitor: Visitor, input: NewWishlistItem) -> FieldResult<WishlistItem>;
+
+    fn remove_from_wishlist(&self, visitor: Visitor, product_id: ProductId) -> FieldResult<Option<WishlistItem>>;
+
+    fn merge_wishlist(&self, input: MergeWishlist) -> FieldResult<Vec<WishlistItem>>;
+
+    fn create_review(&self, input: NewReview) -> FieldResult<Review>;
+
+    fn get_review(&self, review_id: ReviewId) -> FieldResult<Option<Review>>;
+
+    fn search_reviews(&self, skip: i32, count: i32, input: ReviewSearch) -> FieldResult<ReviewSearchResults>;
+
+    fn update_review(&self, review_id: ReviewId, input: UpdateReview) -> FieldResult<Review>;
+
+    fn followed_stores(&self, user_id: UserId) -> FieldResult<Vec<StoreFollow>>;
+
+    fn follow_store(&self, input: NewStoreFollow) -> FieldResult<StoreFollow>;
+
+    fn unfollow_store(&self, user_id: UserId, store_id: StoreId) -> FieldResult<Option<StoreFollow>>;
+
+    /// News of the stores followed by the user published after `since`
+    fn feed(&self, user_id: UserId, since: DateTime<Utc>, skip: i32, count: i32) -> FieldResult<FeedSearchResults>;
+
+    fn view_history(&self, visitor: Visitor, skip: i32, count: i32) -> FieldResult<ViewHistorySearchResults>;
+
+    /// Views of visitors who turned the history off are ignored.
+    /// The request is not waited for, so it can be run in background
+    fn record_view(&self, visitor: Visitor, input: NewView) -> Box<Future<Item = (), Error = FieldError> + Send>;
+
+    fn clear_view_history(&self, visitor: Visitor) -> FieldResult<()>;
+
+    fn view_history_settings(&self, visitor: Visitor) -> FieldResult<ViewHistorySettings>;
+
+    fn update_view_history_settings(&self, visitor: Visitor, input: ViewHistorySettings) -> FieldResult<ViewHistorySettings>;
+
+    fn search_products_by_code(&self, input: ProductCodeSearch, visibility: Visibility, count: i32) -> FieldResult<Vec<ProductCodeSearchResult>>;
+
+    fn saved_searches(&self, user_id: UserId) -> FieldResult<Vec<SavedSearch>>;
+
+    /// Saved searches with alerts enabled due for a check, claimed by the alerts job of one gateway replica
+    fn claim_saved_searches(&self, input: ClaimSavedSearchesRequest) -> FieldResult<Vec<SavedSearch>>;
+
+    fn get_saved_search(&self, saved_search_id: SavedSearchId) -> FieldResult<Option<SavedSearch>>;
+
+    fn create_saved_search(&self, input: NewSavedSearch) -> FieldResult<SavedSearch>;
+
+    fn update_saved_search(&self, saved_search_id: SavedSearchId, input: UpdateSavedSearch) -> FieldResult<SavedSearch>;
+
+    fn delete_saved_search(&self, saved_search_id: SavedSearchId) -> FieldResult<Option<SavedSearch>>;
 }
 
 pub struct StoresServiceImpl<'ctx> {
@@ -45,73 +100,232 @@ impl<'ctx> StoresServiceImpl<'ctx> {
         self.context.config.service_url(Service::Stores)
     }
 
-    fn request_url(&self, request: &str) -> String {
-        format!("{}/{}", self.base_url(), request)
+    fn request_url(&self) -> UrlBuilder {
+        UrlBuilder::new(self.base_url())
+    }
+
+    fn wishlist_url(&self, visitor: Visitor) -> UrlBuilder {
+        match visitor {
+            Visitor::User(user_id) => self.request_url().path("wishlist/by-user-id").segment(user_id),
+            Visitor::Session(session_id) => self.request_url().path("wishlist/by-session-id").segment(session_id.0),
+        }
+    }
+
+    fn view_history_url(&self, visitor: Visitor) -> UrlBuilder {
+        match visitor {
+            Visitor::User(user_id) => self.request_url().path("view_history/by-user-id").segment(user_id),
+            Visitor::Session(session_id) => self.request_url().path("view_history/by-session-id").segment(session_id.0),
+        }
     }
 }
 
 impl<'ctx> StoresService for StoresServiceImpl<'ctx> {
     fn get_store_by_id(&self, store_id: StoreId) -> FieldResult<Option<Store>> {
-        let request_path = format!("{}/{}", Model::Store.to_url(), store_id);
-        let url = self.request_url(&request_path);
+        let url = self.request_url().model(Model::Store).segment(store_id).build();
         self.context.request(Method::Get, url, None).wait()
     }
 
     fn roles(&self, user_id: UserId) -> FieldResult<Vec<StoresRole>> {
-        let url = format!("{}/roles/by-user-id/{}", self.context.config.stores_microservice.url, user_id);
+        let url = self.request_url().path("roles/by-user-id").segment(user_id).build();
 
         self.context.request::<Vec<StoresRole>>(Method::Get, url, None).wait()
     }
 
     fn add_role_to_user(&self, input: NewStoresRoleInput) -> FieldResult<NewRole<StoresMicroserviceRole>> {
-        let request_path = format!("{}", Model::Role.to_url());
-        let url = self.request_url(&request_path);
+        let url = self.request_url().model(Model::Role).build();
         let body: String = serde_json::to_string(&input)?;
         self.context.request(Method::Post, url, Some(body)).wait()
     }
 
     fn remove_role_from_user(&self, input: RemoveStoresRoleInput) -> FieldResult<NewRole<StoresMicroserviceRole>> {
-        let request_path = format!("{}", Model::Role.to_url());
-        let url = self.request_url(&request_path);
+        let url = self.request_url().model(Model::Role).build();
         let body: String = serde_json::to_string(&input)?;
         self.context.request(Method::Delete, url, Some(body)).wait()
     }
 
     fn get_store_by_user(&self, user_id: UserId) -> FieldResult<Option<Store>> {
-        let request_path = format!("{}/by_user_id/{}", Model::Store.to_url(), user_id);
-        let url = self.request_url(&request_path);
+        let url = self.request_url().model(Model::Store).path("by_user_id").segment(user_id).build();
         self.context.request(Method::Get, url, None).wait()
     }
 
     fn get_currency_exchange_info(&self) -> FieldResult<CurrencyExchangeInfo> {
-        let request_path = "currency_exchange";
-        let url = self.request_url(request_path);
+        let url = self.request_url().path("currency_exchange").build();
         self.context.request(Method::Get, url, None).wait()
     }
 
     fn get_base_products(&self, input: GetBaseProductsRequest) -> FieldResult<Vec<BaseProduct>> {
-        let request_path = format!("{}/search_by_ids", Model::BaseProduct.to_url());
-        let url = self.request_url(&request_path);
+        let url = self.request_url().model(Model::BaseProduct).path("search_by_ids").build();
         let body: String = serde_json::to_string(&input)?;
         self.context.request(Method::Post, url, Some(body)).wait()
     }
 
     fn get_product(&self, product_id: ProductId) -> FieldResult<Option<Product>> {
-        let request_path = format!("{}/{}", Model::Product.to_url(), product_id);
-        let url = self.request_url(&request_path);
+        let url = self.request_url().model(Model::Product).segment(product_id).build();
         self.context.request(Method::Get, url, None).wait()
     }
 
     fn get_products_by_base_product(&self, base_product_id: BaseProductId) -> FieldResult<Vec<Product>> {
-        let request_path = format!("{}/by_base_product/{}", Model::Product.to_url(), base_product_id);
-        let url = self.request_url(&request_path);
+        let url = self.request_url().model(Model::Product).path("by_base_product").segment(base_product_id).build();
         self.context.request(Method::Get, url, None).wait()
     }
 
     fn get_products(&self, input: GetProductsRequest) -> FieldResult<Vec<Product>> {
-        let request_path = format!("{}/search_by_ids", Model::Product.to_url());
-        let url = self.request_url(&request_path);
+        let url = self.request_url().model(Model::Product).path("search_by_ids").build();
+        let body: String = serde_json::to_string(&input)?;
+        self.context.request(Method::Post, url, Some(body)).wait()
+    }
+
+    fn wishlist(&self, visitor: Visitor, skip: i32, count: i32) -> FieldResult<WishlistSearchResults> {
+        let url = self.wishlist_url(visitor).query("skip", skip).query("count", count).build();
+        self.context.request(Method::Get, url, None).wait()
+    }
+
+    fn add_to_wishlist(&self, visitor: Visitor, input: NewWishlistItem) -> FieldResult<WishlistItem> {
+        let url = self.wishlist_url(visitor).build();
+        let body: String = serde_json::to_string(&input)?;
+        self.context.request(Method::Post, url, Some(body)).wait()
+    }
+
+    fn remove_from_wishlist(&self, visitor: Visitor, product_id: ProductId) -> FieldResult<Option<WishlistItem>> {
+        let url = self.wishlist_url(visitor).path("products").segment(product_id).build();
+        self.context.request(Method::Delete, url, None).wait()
+    }
+
+    fn merge_wishlist(&self, input: MergeWishlist) -> FieldResult<Vec<WishlistItem>> {
+        let url = self.request_url().path("wishlist/merge").build();
+        let body: String = serde_json::to_string(&input)?;
+        self.context.request(Method::Post, url, Some(body)).wait()
+    }
+
+    fn create_review(&self, input: NewReview) -> FieldResult<Review> {
+        let url = self.request_url().path("reviews").build();
+        let body: String = serde_json::to_string(&input)?;
+        self.context.request(Method::Post, url, Some(body)).wait()
+    }
+
+    fn get_review(&self, review_id: ReviewId) -> FieldResult<Option<Review>> {
+        let url = self.request_url().path("reviews").segment(review_id).build();
+        self.context.request(Method::Get, url, None).wait()
+    }
+
+    fn search_reviews(&self, skip: i32, count: i32, input: ReviewSearch) -> FieldResult<ReviewSearchResults> {
+        let url = self.request_url().path("reviews/search").query("skip", skip).query("count", count).build();
         let body: String = serde_json::to_string(&input)?;
         self.context.request(Method::Post, url, Some(body)).wait()
     }
+
+    fn update_review(&self, review_id: ReviewId, input: UpdateReview) -> FieldResult<Review> {
+        let url = self.request_url().path("reviews").segment(review_id).build();
+        let body: String = serde_json::to_string(&input)?;
+        self.context.request(Method::Put, url, Some(body)).wait()
+    }
+
+    fn followed_stores(&self, user_id: UserId) -> FieldResult<Vec<StoreFollow>> {
+        let url = self.request_url().path("follows/by-user-id").segment(user_id).build();
+        self.context.request(Method::Get, url, None).wait()
+    }
+
+    fn follow_store(&self, input: NewStoreFollow) -> FieldResult<StoreFollow> {
+        let url = self.request_url().path("follows").build();
+        let body: String = serde_json::to_string(&input)?;
+        self.context.request(Method::Post, url, Some(body)).wait()
+    }
+
+    fn unfollow_store(&self, user_id: UserId, store_id: StoreId) -> FieldResult<Option<StoreFollow>> {
+        let url = self
+            .request_url()
+            .path("follows/by-user-id")
+            .segment(user_id)
+            .path("stores")
+            .segment(store_id)
+            .build();
+        self.context.request(Method::Delete, url, None).wait()
+    }
+
+    fn feed(&self, user_id: UserId, since: DateTime<Utc>, skip: i32, count: i32) -> FieldResult<FeedSearchResults> {
+        let url = self
+            .request_url()
+            .path("follows/by-user-id")
+            .segment(user_id)
+            .path("feed")
+            .query("since", since.to_rfc3339())
+            .query("skip", skip)
+            .query("count", count)
+            .build();
+        self.context.request(Method::Get, url, None).wait()
+    }
+
+    fn view_history(&self, visitor: Visitor, skip: i32, count: i32) -> FieldResult<ViewHistorySearchResults> {
+        let url = self.view_history_url(visitor).query("skip", skip).query("count", count).build();
+        self.context.request(Method::Get, url, None).wait()
+    }
+
+    fn record_view(&self, visitor: Visitor, input: NewView) -> Box<Future<Item = (), Error = FieldError> + Send> {
+        let url = self.view_history_url(visitor).build();
+        match serde_json::to_string(&input) {
+            Ok(body) => self.context.request(Method::Post, url, Some(body)),
+            Err(e) => Box::new(future::err(e.into())),
+        }
+    }
+
+    fn clear_view_history(&self, visitor: Visitor) -> FieldResult<()> {
+        let url = self.view_history_url(visitor).build();
+        self.context.request(Method::Delete, url, None).wait()
+    }
+
+    fn view_history_settings(&self, visitor: Visitor) -> FieldResult<ViewHistorySettings> {
+        let url = self.view_history_url(visitor).path("settings").build();
+        self.context.request(Method::Get, url, None).wait()
+    }
+
+    fn update_view_history_settings(&self, visitor: Visitor, input: ViewHistorySettings) -> FieldResult<ViewHistorySettings> {
+        let url = self.view_history_url(visitor).path("settings").build();
+        let body: String = serde_json::to_string(&input)?;
+        self.context.request(Method::Put, url, Some(body)).wait()
+    }
+
+    fn search_products_by_code(&self, input: ProductCodeSearch, visibility: Visibility, count: i32) -> FieldResult<Vec<ProductCodeSearchResult>> {
+        let url = self
+            .request_url()
+            .model(Model::Product)
+            .path("search_by_code")
+            .query("count", count)
+            .query("visibility", visibility)
+            .build();
+        let body: String = serde_json::to_string(&input)?;
+        self.context.request(Method::Post, url, Some(body)).wait()
+    }
+
+    fn saved_searches(&self, user_id: UserId) -> FieldResult<Vec<SavedSearch>> {
+        let url = self.request_url().path("saved_searches/by-user-id").segment(user_id).build();
+        self.context.request(Method::Get, url, None).wait()
+    }
+
+    fn claim_saved_searches(&self, input: ClaimSavedSearchesRequest) -> FieldResult<Vec<SavedSearch>> {
+        let url = self.request_url().path("saved_searches/alerts/claim").build();
+        let body: String = serde_json::to_string(&input)?;
+        self.context.request(Method::Post, url, Some(body)).wait()
+    }
+
+    fn get_saved_search(&self, saved_search_id: SavedSearchId) -> FieldResult<Option<SavedSearch>> {
+        let url = self.request_url().path("saved_searches").segment(saved_search_id).build();
+        self.context.request(Method::Get, url, None).wait()
+    }
+
+    fn create_saved_search(&self, input: NewSavedSearch) -> FieldResult<SavedSearch> {
+        let url = self.request_url().path("saved_searches").build();
+        let body: String = serde_json::to_string(&input)?;
+        self.context.request(Method::Post, url, Some(body)).wait()
+    }
+
+    fn update_saved_search(&self, saved_search_id: SavedSearchId, input: UpdateSavedSearch) -> FieldResult<SavedSearch> {
+        let url = self.request_url().path("saved_searches").segment(saved_search_id).build();
+        let body: String = serde_json::to_string(&input)?;
+        self.context.request(Method::Put, url, Some(body)).wait()
+    }
+
+    fn delete_saved_search(&self, saved_search_id: SavedSearchId) -> FieldResult<Option<SavedSearch>> {
+        let url = self.request_url().path("saved_searches").segment(saved_search_id).build();
+        self.context.request(Method::Delete, url, None).wait()
+    }
 }
@@ -387,6 +387,15 @@ pub struct CartProductIncrementPayload {
     pub store_id: StoreId,
 }
 
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Merge cart of the session from request header into user cart input object")]
+pub struct MergeCartInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "User country code.")]
+    pub user_country_code: Option<String>,
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct CartMergePayload {
     pub user_from: UserId,
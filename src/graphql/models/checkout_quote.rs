@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use stq_static_resources::Currency;
+use stq_types::{CouponId, ProductId, ProductSellerPrice, Quantity, StoreId, UserId};
+
+use super::*;
+
+#[derive(GraphQLInputObject, Debug, Clone)]
+#[graphql(description = "Checkout quote input object")]
+pub struct CheckoutQuoteInput {
+    #[graphql(description = "Currency that will be paid")]
+    pub currency: Currency,
+    #[graphql(description = "User country code")]
+    pub user_country_code: String,
+    #[graphql(description = "Quote buy now of a single product instead of the cart")]
+    pub buy_now: Option<CheckoutQuoteBuyNowInput>,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone)]
+#[graphql(description = "Buy now checkout quote input object")]
+pub struct CheckoutQuoteBuyNowInput {
+    #[graphql(description = "Product raw id")]
+    pub product_id: i32,
+    #[graphql(description = "Quantity")]
+    pub quantity: i32,
+    #[graphql(description = "Coupon code")]
+    pub coupon_code: Option<String>,
+    #[graphql(description = "Raw shipping id")]
+    pub shipping_id: i32,
+}
+
+#[derive(GraphQLObject, Clone, Debug)]
+#[graphql(description = "Checkout prices frozen until the quote expires")]
+pub struct CheckoutQuote {
+    #[graphql(description = "Signed quote token, pass it to createOrdersV2 or buyNowV2")]
+    pub token: String,
+    #[graphql(description = "Quote expiration time (RFC 3339)")]
+    pub expires_at: String,
+    #[graphql(description = "Currency that will be paid")]
+    pub currency: Currency,
+    #[graphql(description = "Products cost")]
+    pub products_cost: Money,
+    #[graphql(description = "Coupons discount")]
+    pub coupons_discount: Money,
+    #[graphql(description = "Delivery cost")]
+    pub delivery_cost: Money,
+    #[graphql(description = "Total cost")]
+    pub total_cost: Money,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum CheckoutQuoteKind {
+    Cart,
+    BuyNow,
+}
+
+/// Payload of the signed quote token
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CheckoutQuoteClaims {
+    /// Expiration timestamp in seconds
+    pub exp: i64,
+    pub kind: CheckoutQuoteKind,
+    pub user_id: UserId,
+    pub currency: Currency,
+    pub user_country_code: String,
+    pub products: Vec<QuotedProduct>,
+    /// Exchange rates the costs of the quote were converted with
+    pub exchange_rates: CurrencyExchangeData,
+    /// Rounded total cost the customer agreed to pay
+    pub total_cost: String,
+}
+
+impl CheckoutQuoteClaims {
+    pub fn pricing(&self) -> QuotedPricing {
+        QuotedPricing {
+            exchange_rates: self.exchange_rates.clone(),
+            total_cost: self.total_cost.clone(),
+        }
+    }
+}
+
+/// Exchange rates and total cost frozen by the quote, orders created with a quote are charged the quoted total
+#[derive(Serialize, Debug, Clone)]
+pub struct QuotedPricing {
+    pub exchange_rates: CurrencyExchangeData,
+    pub total_cost: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuotedProduct {
+    pub product_id: ProductId,
+    pub store_id: StoreId,
+    pub quantity: Quantity,
+    pub seller_price: ProductSellerPrice,
+    pub coupon: Option<Coupon>,
+    pub package: Option<AvailablePackageForUser>,
+}
+
+/// Seller prices, coupons and delivery packages the cart orders are created with
+#[derive(Debug, Clone)]
+pub struct CartCheckout {
+    pub prices: CartProductWithPriceHash,
+    pub coupons: HashMap<CouponId, Coupon>,
+    pub packages: HashMap<ProductId, AvailablePackageForUser>,
+}
@@ -14,6 +14,17 @@ pub struct Company {
     pub deliveries_from: Vec<Country>,
     pub currency: Currency,
     pub logo: String,
+    #[serde(default)]
+    pub tracking_url_template: Option<String>,
+}
+
+impl Company {
+    /// Tracking page of the shipment, `{tracking_number}` in the template is replaced by the number
+    pub fn tracking_url(&self, tracking_number: &str) -> Option<String> {
+        self.tracking_url_template
+            .as_ref()
+            .map(|template| template.replace("{tracking_number}", tracking_number))
+    }
 }
 
 #[derive(GraphQLInputObject, Serialize, Debug, Clone, PartialEq)]
@@ -34,6 +45,8 @@ pub struct NewCompanyInput {
     pub currency: Currency,
     #[graphql(description = "logo")]
     pub logo: String,
+    #[graphql(description = "Tracking page url with {tracking_number} placeholder")]
+    pub tracking_url_template: Option<String>,
 }
 
 #[derive(GraphQLInputObject, Serialize, Debug, Clone, PartialEq)]
@@ -57,6 +70,8 @@ pub struct UpdateCompanyInput {
     pub currency: Option<Currency>,
     #[graphql(description = "logo")]
     pub logo: Option<String>,
+    #[graphql(description = "Tracking page url with {tracking_number} placeholder")]
+    pub tracking_url_template: Option<String>,
 }
 
 impl UpdateCompanyInput {
@@ -70,6 +85,7 @@ impl UpdateCompanyInput {
             deliveries_from: None,
             currency: None,
             logo: None,
+            tracking_url_template: None,
         } == self.clone()
     }
 }
@@ -1,5 +1,238 @@
-use super::*;
+use std::cmp;
+use std::str::FromStr;
+
+use base64;
 use juniper;
+use juniper::{FieldError, FieldResult};
+use serde_json;
+
+use super::*;
+
+/// Opaque relay cursor. Encodes position of the edge in the result set,
+/// sort key the result set was ordered by and id of the node (if it has one)
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    pub offset: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+}
+
+impl Cursor {
+    pub fn new(offset: i32) -> Self {
+        Self {
+            offset,
+            ..Default::default()
+        }
+    }
+
+    pub fn encode(&self) -> juniper::ID {
+        let json = serde_json::to_string(self).unwrap_or_default();
+        juniper::ID::from(base64::encode_config(&json, base64::URL_SAFE))
+    }
+
+    pub fn decode(cursor: &juniper::ID) -> FieldResult<Self> {
+        // plain offsets were used as cursors before they became opaque
+        if let Ok(offset) = i32::from_str(&*cursor) {
+            return Ok(Cursor::new(offset));
+        }
+
+        base64::decode_config(cursor.as_bytes(), base64::URL_SAFE)
+            .ok()
+            .and_then(|json| serde_json::from_slice::<Cursor>(&json).ok())
+            .ok_or_else(|| {
+                FieldError::new(
+                    "Cursor parsing error",
+                    graphql_value!({ "code": 300, "details": { "Cursor is malformed." }}),
+                )
+            })
+    }
+
+    /// Offset of the record following the edge `after` points to
+    pub fn offset_after(after: Option<juniper::ID>) -> FieldResult<i32> {
+        match after {
+            Some(after) => Cursor::decode(&after).map(|cursor| cursor.offset + 1),
+            None => Ok(0),
+        }
+    }
+
+    fn check_key(&self, key: &Option<String>) -> FieldResult<()> {
+        if self.key.is_some() && self.key != *key {
+            return Err(FieldError::new(
+                "Cursor parsing error",
+                graphql_value!({ "code": 300, "details": { "Cursor was issued for another sort order." }}),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Window of an offset based result set requested with relay `first`/`after`/`last`/`before` arguments
+#[derive(Clone, Debug)]
+pub struct PageRequest {
+    pub offset: i32,
+    pub count: i32,
+    pub key: Option<String>,
+    backward: bool,
+    anchor: Option<i32>,
+    from_end: bool,
+    total_count: Option<i32>,
+}
+
+impl PageRequest {
+    pub fn new(
+        first: Option<i32>,
+        after: Option<juniper::ID>,
+        last: Option<i32>,
+        before: Option<juniper::ID>,
+        key: Option<String>,
+        records_limit: usize,
+    ) -> FieldResult<Self> {
+        let records_limit = records_limit as i32;
+
+        if let Some(before) = before {
+            let cursor = Cursor::decode(&before)?;
+            cursor.check_key(&key)?;
+            let count = cmp::min(cmp::min(last.unwrap_or(records_limit), records_limit), cursor.offset);
+            return Ok(Self {
+                offset: cursor.offset - count,
+                count: cmp::max(count, 0),
+                key,
+                backward: true,
+                anchor: None,
+                from_end: false,
+                total_count: None,
+            });
+        }
+
+        if let (None, None, Some(last)) = (first, &after, last) {
+            // the offset of the last records is resolved with the total count, see `with_total_count`
+            return Ok(Self {
+                offset: 0,
+                count: cmp::max(cmp::min(last, records_limit), 0),
+                key,
+                backward: false,
+                anchor: None,
+                from_end: true,
+                total_count: None,
+            });
+        }
+
+        let count = cmp::max(cmp::min(first.or(last).unwrap_or(records_limit), records_limit), 0);
+        let (offset, anchor) = match after {
+            Some(after) => {
+                let cursor = Cursor::decode(&after)?;
+                cursor.check_key(&key)?;
+                match cursor.id {
+                    // the node of the cursor is fetched as well to find out if the result set has shifted
+                    Some(id) => (cursor.offset, Some(id)),
+                    None => (cursor.offset + 1, None),
+                }
+            }
+            None => (0, None),
+        };
+
+        Ok(Self {
+            offset,
+            count,
+            key,
+            backward: false,
+            anchor,
+            from_end: false,
+            total_count: None,
+        })
+    }
+
+    /// Page of the result set requested with classic `current_page`/`items_count` arguments
+    pub fn from_page_number(current_page: i32, items_count: i32, records_limit: usize) -> Self {
+        let count = cmp::max(cmp::min(items_count, records_limit as i32), 1);
+        Self {
+            offset: count * (cmp::max(current_page, 1) - 1),
+            count,
+            key: None,
+            backward: false,
+            anchor: None,
+            from_end: false,
+            total_count: None,
+        }
+    }
+
+    /// `last` without `before` pages back from the end of the result set,
+    /// so the offset of the page is known only with the total count
+    pub fn with_total_count(mut self, total_count: i32) -> Self {
+        if self.from_end {
+            self.count = cmp::min(self.count, total_count);
+            self.offset = cmp::max(total_count - self.count, 0);
+            self.from_end = false;
+        }
+        self.total_count = Some(total_count);
+        self
+    }
+
+    /// Same as `with_total_count`, the total count is fetched only when the page is counted from the end
+    pub fn resolve_from_end<F>(self, total_count: F) -> FieldResult<Self>
+    where
+        F: FnOnce() -> FieldResult<i32>,
+    {
+        if self.from_end {
+            total_count().map(|total_count| self.with_total_count(total_count))
+        } else {
+            Ok(self)
+        }
+    }
+
+    /// Records count to fetch from microservice, including the one showing that there is a next page
+    pub fn fetch_count(&self) -> i32 {
+        match self.anchor {
+            Some(_) => self.count + 2,
+            None => self.count + 1,
+        }
+    }
+
+    pub fn edges<T, F>(&self, items: Vec<T>, id: F) -> (Vec<Edge<T>>, PageInfo)
+    where
+        F: Fn(&T) -> Option<i32>,
+    {
+        let mut items = items;
+        let mut offset = self.offset;
+
+        if let Some(anchor) = self.anchor {
+            // records inserted or removed before the cursor move its node,
+            // so the page starts right after the node wherever it is now
+            let skip = items.iter().position(|item| id(item) == Some(anchor)).map(|i| i + 1).unwrap_or(1);
+            let skip = cmp::min(skip, items.len());
+            items.drain(..skip);
+            offset += skip as i32;
+        }
+
+        let has_next_page = self.backward || items.len() as i32 > self.count;
+        items.truncate(self.count as usize);
+
+        let edges: Vec<Edge<T>> = items
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let cursor = Cursor {
+                    offset: offset + i as i32,
+                    key: self.key.clone(),
+                    id: id(&item),
+                };
+                Edge::new(cursor.encode(), item)
+            })
+            .collect();
+
+        let page_info = PageInfo {
+            has_next_page,
+            has_previous_page: offset > 0,
+            start_cursor: edges.first().map(|e| e.cursor.clone()),
+            end_cursor: edges.last().map(|e| e.cursor.clone()),
+            total_count: self.total_count,
+        };
+
+        (edges, page_info)
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct Edge<T> {
@@ -15,7 +248,7 @@ impl<T> Edge<T> {
     pub fn create_vec(vec: Vec<T>, offset: i32) -> Vec<Edge<T>> {
         vec.into_iter()
             .enumerate()
-            .map(|(i, item)| Edge::new(juniper::ID::from((i as i32 + offset).to_string()), item))
+            .map(|(i, item)| Edge::new(Cursor::new(i as i32 + offset).encode(), item))
             .collect()
     }
 }
@@ -26,6 +259,8 @@ pub struct PageInfo {
     pub has_previous_page: bool,
     pub start_cursor: Option<juniper::ID>,
     pub end_cursor: Option<juniper::ID>,
+    /// Known when the connection is counted anyway
+    pub total_count: Option<i32>,
 }
 
 #[derive(Clone, Debug)]
@@ -33,6 +268,16 @@ pub struct PageInfoSegments {
     pub current_page: i32,
     pub page_items_count: i32,
     pub total_pages: i32,
+    pub total_count: i32,
+}
+
+#[derive(Clone, Debug)]
+pub struct PageInfoReviews {
+    pub current_page: i32,
+    pub page_items_count: i32,
+    pub total_pages: i32,
+    pub total_count: i32,
+    pub rating_distribution: RatingDistribution,
 }
 
 #[derive(Clone, Debug)]
@@ -51,6 +296,8 @@ pub struct PageInfoProductsSearch {
     pub search_filters: Option<ProductsSearchFilters>,
     pub start_cursor: Option<juniper::ID>,
     pub end_cursor: Option<juniper::ID>,
+    /// Search queries that find products when this one finds nothing
+    pub did_you_mean: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -64,3 +311,97 @@ impl<T, P> Connection<T, P> {
         Self { edges, page_info }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor_offsets(edges: &[Edge<i32>]) -> Vec<i32> {
+        edges.iter().map(|edge| Cursor::decode(&edge.cursor).unwrap().offset).collect()
+    }
+
+    #[test]
+    fn last_without_before_pages_from_the_end() {
+        let page = PageRequest::new(None, None, Some(3), None, None, 50).unwrap().with_total_count(10);
+        assert_eq!(page.offset, 7);
+        assert_eq!(page.count, 3);
+
+        let items = (page.offset..10).collect::<Vec<_>>();
+        let (edges, page_info) = page.edges(items, |_| None);
+        assert_eq!(edges.iter().map(|edge| edge.node).collect::<Vec<_>>(), vec![7, 8, 9]);
+        assert!(!page_info.has_next_page);
+        assert!(page_info.has_previous_page);
+        assert_eq!(page_info.total_count, Some(10));
+    }
+
+    #[test]
+    fn last_without_before_takes_all_of_short_result_set() {
+        let page = PageRequest::new(None, None, Some(5), None, None, 50).unwrap().with_total_count(2);
+        assert_eq!(page.offset, 0);
+        assert_eq!(page.count, 2);
+    }
+
+    #[test]
+    fn total_count_is_fetched_only_from_the_end() {
+        let page = PageRequest::new(Some(3), None, None, None, None, 50)
+            .unwrap()
+            .resolve_from_end(|| panic!("total count is not needed"))
+            .unwrap();
+        assert_eq!(page.offset, 0);
+        assert_eq!(page.count, 3);
+    }
+
+    #[test]
+    fn last_before_cursor_ends_before_it() {
+        let before = Cursor::new(5).encode();
+        let page = PageRequest::new(None, None, Some(2), Some(before), None, 50).unwrap();
+        assert_eq!(page.offset, 3);
+        assert_eq!(page.count, 2);
+
+        let (edges, page_info) = page.edges(vec![3, 4], |_| None);
+        assert_eq!(cursor_offsets(&edges), vec![3, 4]);
+        assert!(page_info.has_next_page);
+        assert!(page_info.has_previous_page);
+    }
+
+    #[test]
+    fn negative_first_is_empty_page() {
+        let page = PageRequest::new(Some(-1), None, None, None, None, 50).unwrap();
+        assert_eq!(page.offset, 0);
+        assert_eq!(page.count, 0);
+
+        let (edges, page_info) = page.edges(vec![0], |_| None);
+        assert!(edges.is_empty());
+        assert!(page_info.has_next_page);
+    }
+
+    #[test]
+    fn first_after_cursor_starts_after_it() {
+        let after = Cursor::new(1).encode();
+        let page = PageRequest::new(Some(2), Some(after), None, None, None, 50).unwrap();
+        assert_eq!(page.offset, 2);
+
+        let (edges, page_info) = page.edges(vec![2, 3, 4], |_| None);
+        assert_eq!(cursor_offsets(&edges), vec![2, 3]);
+        assert!(page_info.has_next_page);
+        assert_eq!(page_info.total_count, None);
+    }
+
+    #[test]
+    fn cursor_of_another_sort_order_is_rejected() {
+        let after = Cursor {
+            offset: 1,
+            key: Some("Price".to_string()),
+            id: None,
+        }
+        .encode();
+        assert!(PageRequest::new(Some(2), Some(after), None, None, Some("Views".to_string()), 50).is_err());
+    }
+
+    #[test]
+    fn page_number_is_converted_to_offset() {
+        let page = PageRequest::from_page_number(3, 10, 50);
+        assert_eq!(page.offset, 20);
+        assert_eq!(page.count, 10);
+    }
+}
@@ -0,0 +1,181 @@
+use std::fmt;
+
+use chrono::prelude::*;
+
+use stq_types::{OrderId, OrderSlug, StoreId, UserId};
+
+use super::*;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DisputeId(pub i32);
+
+impl fmt::Display for DisputeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(GraphQLEnum, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[graphql(name = "DisputeStatus", description = "Dispute status")]
+pub enum DisputeStatus {
+    #[graphql(description = "Opened by the customer, waiting for moderator.")]
+    Open,
+    #[graphql(description = "Moderator waits for the seller response.")]
+    AwaitingSeller,
+    #[graphql(description = "Resolved in favor of the customer, the order is refunded.")]
+    ResolvedRefund,
+    #[graphql(description = "Resolved in favor of the seller, the order is completed.")]
+    ResolvedRejected,
+}
+
+impl DisputeStatus {
+    pub fn is_resolved(self) -> bool {
+        self == DisputeStatus::ResolvedRefund || self == DisputeStatus::ResolvedRejected
+    }
+}
+
+#[derive(GraphQLEnum, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[graphql(name = "DisputeReason", description = "Reason of the dispute")]
+pub enum DisputeReason {
+    NotReceived,
+    NotAsDescribed,
+    Damaged,
+    WrongItem,
+    Other,
+}
+
+#[derive(GraphQLEnum, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[graphql(name = "DisputeParticipant", description = "Author of the dispute message")]
+pub enum DisputeParticipant {
+    Customer,
+    Seller,
+    Moderator,
+}
+
+#[derive(GraphQLEnum, Clone, Copy, Debug, PartialEq, Eq)]
+#[graphql(name = "DisputeResolution", description = "Moderator decision on the dispute")]
+pub enum DisputeResolution {
+    #[graphql(description = "Refund the whole remaining amount to the customer and cancel the order.")]
+    Refund,
+    #[graphql(description = "Complete the order in favor of the seller.")]
+    Reject,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct DisputeMessage {
+    pub id: i32,
+    pub dispute_id: DisputeId,
+    pub author_id: UserId,
+    pub author_role: DisputeParticipant,
+    pub text: String,
+    pub attachments: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Dispute {
+    pub id: DisputeId,
+    pub order_id: OrderId,
+    pub order_slug: OrderSlug,
+    pub customer_id: UserId,
+    pub store_id: StoreId,
+    pub reason: DisputeReason,
+    pub status: DisputeStatus,
+    pub messages: Vec<DisputeMessage>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct DisputeSearchResults {
+    pub total_count: u32,
+    pub disputes: Vec<Dispute>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct DisputeSearch {
+    pub customer_id: Option<UserId>,
+    pub store_id: Option<StoreId>,
+    pub status: Option<DisputeStatus>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NewDisputeMessage {
+    pub author_id: UserId,
+    pub author_role: DisputeParticipant,
+    pub text: String,
+    pub attachments: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NewDispute {
+    pub order_id: OrderId,
+    pub order_slug: OrderSlug,
+    pub customer_id: UserId,
+    pub store_id: StoreId,
+    pub reason: DisputeReason,
+    pub message: NewDisputeMessage,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct UpdateDisputeStatus {
+    pub status: DisputeStatus,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Open dispute input object.")]
+pub struct OpenDisputeInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Slug of order.")]
+    pub order_slug: i32,
+    #[graphql(description = "Reason of the dispute.")]
+    pub reason: DisputeReason,
+    #[graphql(description = "Description of the problem.")]
+    pub text: String,
+    #[graphql(description = "Urls of photos or documents.")]
+    pub attachments: Option<Vec<String>>,
+}
+
+impl From<CreateDisputeInput> for OpenDisputeInput {
+    fn from(input: CreateDisputeInput) -> Self {
+        Self {
+            client_mutation_id: input.client_mutation_id,
+            order_slug: input.order_slug,
+            reason: DisputeReason::Other,
+            text: input.comment.unwrap_or_default(),
+            attachments: None,
+        }
+    }
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Add dispute message input object.")]
+pub struct AddDisputeMessageInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Dispute raw id.")]
+    pub dispute_id: i32,
+    #[graphql(description = "Message text.")]
+    pub text: String,
+    #[graphql(description = "Urls of photos or documents.")]
+    pub attachments: Option<Vec<String>>,
+    #[graphql(description = "Moderator only - ask the seller to respond.")]
+    pub await_seller: Option<bool>,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Resolve dispute input object.")]
+pub struct ResolveDisputeInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Dispute raw id.")]
+    pub dispute_id: i32,
+    #[graphql(description = "Decision.")]
+    pub resolution: DisputeResolution,
+    #[graphql(description = "Explanation for the customer and the seller.")]
+    pub comment: String,
+}
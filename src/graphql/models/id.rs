@@ -7,6 +7,7 @@ use stq_routes::model::Model;
 use stq_routes::service::Service;
 
 use config::Config;
+use url::Url;
 
 pub struct ID {
     pub service: Service,
@@ -52,7 +53,7 @@ impl ID {
         ID { service, model, raw_id }
     }
 
-    pub fn url(self, config: &Config) -> String {
-        format!("{}/{}/{}", config.service_url(self.service), self.model.to_url(), self.raw_id)
+    pub fn url(self, config: &Config) -> Url {
+        config.url(self.service).model(self.model).segment(self.raw_id).build()
     }
 }
@@ -1,6 +1,8 @@
 use std::fmt;
 use std::fmt::Display;
 
+use jsonwebtoken::errors::Error as JwtError;
+use jsonwebtoken::{decode, Algorithm, Validation};
 use juniper::Value;
 
 use graphql::models::{NewUserAdditionalData, NewUserAdditionalDataInput};
@@ -17,6 +19,18 @@ pub struct JWT {
     pub status: UserStatus,
 }
 
+impl JWT {
+    /// Reads payload of the token checking its signature with the public key of users microservice,
+    /// the same way as the tokens of the requests are checked
+    pub fn verified_payload(&self, jwt_public_key: &[u8], leeway: i64) -> Result<JWTPayload, JwtError> {
+        let validation = Validation {
+            leeway,
+            ..Validation::new(Algorithm::RS256)
+        };
+        decode::<JWTPayload>(&self.token, jwt_public_key, &validation).map(|t| t.claims)
+    }
+}
+
 graphql_scalar!(UserStatus {
     description: "DEPRACATED"
 
@@ -52,6 +66,9 @@ pub struct CreateJWTEmailInput {
     pub email: String,
     #[graphql(description = "Password of a user.")]
     pub password: String,
+    #[graphql(description = "Merge cart of the current session into the user's cart after login.")]
+    #[serde(skip_serializing)]
+    pub merge_session_cart: Option<bool>,
 }
 
 #[derive(GraphQLInputObject, Debug, Clone)]
@@ -65,6 +82,8 @@ pub struct CreateJWTProviderInput {
     pub token: String,
     #[graphql(description = "Additional data containing referal, referer, country, utm_marks, etc... .")]
     pub additional_data: Option<NewUserAdditionalDataInput>,
+    #[graphql(description = "Merge cart of the current session into the user's cart after login.")]
+    pub merge_session_cart: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
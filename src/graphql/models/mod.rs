@@ -5,6 +5,7 @@ pub mod billing_info;
 pub mod buy_now;
 pub mod cart;
 pub mod category;
+pub mod checkout_quote;
 pub mod company;
 pub mod company_package;
 pub mod connection;
@@ -13,31 +14,49 @@ pub mod coupon;
 pub mod currency_exchange;
 pub mod custom_attribute;
 pub mod delivery;
+pub mod dispute;
 pub mod email_template;
 pub mod email_verify;
 pub mod fee;
 pub mod id;
 pub mod invoice;
 pub mod jwt;
+pub mod money;
 pub mod moderator_comment;
 pub mod order;
+pub mod order_batch;
 pub mod order_billing;
+pub mod order_export;
+pub mod order_return;
+pub mod order_transition;
 pub mod package;
 pub mod payment_intent;
 pub mod payout;
 pub mod product;
+pub mod product_code;
+pub mod product_import;
+pub mod receipt;
+pub mod recommendation;
+pub mod refund;
 pub mod reset_password;
+pub mod review;
+pub mod saved_search;
 pub mod reset_token;
 pub mod search_product;
+pub mod shipment;
 pub mod stock;
 pub mod store;
+pub mod store_follow;
 pub mod stripe;
 pub mod subscription;
 pub mod user;
 pub mod user_delivery_address;
 pub mod user_role;
+pub mod view_history;
 pub mod visibility;
+pub mod visitor;
 pub mod warehouse;
+pub mod wishlist;
 pub mod wizard_store;
 
 pub use self::address::*;
@@ -47,6 +66,7 @@ pub use self::billing_info::*;
 pub use self::buy_now::*;
 pub use self::cart::*;
 pub use self::category::*;
+pub use self::checkout_quote::*;
 pub use self::company::*;
 pub use self::company_package::*;
 pub use self::connection::*;
@@ -55,31 +75,49 @@ pub use self::coupon::*;
 pub use self::currency_exchange::*;
 pub use self::custom_attribute::*;
 pub use self::delivery::*;
+pub use self::dispute::*;
 pub use self::email_template::*;
 pub use self::email_verify::*;
 pub use self::fee::*;
 pub use self::id::ID;
 pub use self::invoice::*;
 pub use self::jwt::{CreateJWTEmailInput, CreateJWTProviderInput, ProviderOauth, UserStatus, JWT};
+pub use self::money::*;
 pub use self::moderator_comment::*;
 pub use self::order::*;
+pub use self::order_batch::*;
 pub use self::order_billing::*;
+pub use self::order_export::*;
+pub use self::order_return::*;
+pub use self::order_transition::*;
 pub use self::package::*;
 pub use self::payment_intent::*;
 pub use self::payout::*;
 pub use self::product::*;
+pub use self::product_code::*;
+pub use self::product_import::*;
+pub use self::receipt::*;
+pub use self::recommendation::*;
+pub use self::refund::*;
 pub use self::reset_password::*;
+pub use self::review::*;
+pub use self::saved_search::*;
 pub use self::reset_token::*;
 pub use self::search_product::*;
+pub use self::shipment::*;
 pub use self::stock::*;
 pub use self::store::*;
+pub use self::store_follow::*;
 pub use self::stripe::*;
 pub use self::subscription::*;
 pub use self::user::*;
 pub use self::user_delivery_address::*;
 pub use self::user_role::*;
+pub use self::view_history::*;
 pub use self::visibility::*;
+pub use self::visitor::*;
 pub use self::warehouse::*;
+pub use self::wishlist::*;
 pub use self::wizard_store::*;
 
 //Mock object, made to return from graphql when microservices response contains nothing - '()'
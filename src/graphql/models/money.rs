@@ -0,0 +1,221 @@
+use std::fmt;
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use juniper::{FieldError, FieldResult, Value};
+
+use stq_static_resources::{Currency, CurrencyType};
+use stq_types::{ExchangeRate, Quantity};
+
+/// Decimal amount of money in a specific currency.
+/// All cart, buy now and order price math goes through this type instead of `f64`
+/// so that the values shown to the user match what billing charges.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Money {
+    pub amount: BigDecimal,
+    pub currency: Currency,
+}
+
+impl Money {
+    /// Digits after the decimal point kept for fiat currencies
+    pub const FIAT_SCALE: i64 = 2;
+    /// Digits after the decimal point kept for crypto currencies
+    pub const CRYPTO_SCALE: i64 = 8;
+
+    pub fn new(amount: BigDecimal, currency: Currency) -> Self {
+        Self { amount, currency }
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(BigDecimal::from_str("0").unwrap_or_default(), currency)
+    }
+
+    pub fn from_f64(amount: f64, currency: Currency) -> Self {
+        let amount = if amount.is_finite() {
+            BigDecimal::from_str(&amount.to_string()).unwrap_or_default()
+        } else {
+            BigDecimal::default()
+        };
+
+        Self::new(amount, currency)
+    }
+
+    pub fn scale(currency: Currency) -> i64 {
+        match currency.currency_type() {
+            CurrencyType::Fiat => Money::FIAT_SCALE,
+            CurrencyType::Crypto => Money::CRYPTO_SCALE,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.amount == BigDecimal::default()
+    }
+
+    /// Rounds half away from zero to the number of digits used by the currency
+    pub fn round(&self) -> Self {
+        let scale = Money::scale(self.currency);
+        let half = BigDecimal::from_str(&format!("0.{}5", "0".repeat(scale as usize))).unwrap_or_default();
+        let amount = if self.amount >= BigDecimal::default() {
+            self.amount.clone() + half
+        } else {
+            self.amount.clone() - half
+        };
+
+        Self::new(amount.with_scale(scale), self.currency)
+    }
+
+    pub fn times(&self, quantity: Quantity) -> Self {
+        Self::new(self.amount.clone() * decimal_from_i32(quantity.0), self.currency)
+    }
+
+    /// Returns `percent` percents of the amount
+    pub fn percent(&self, percent: i32) -> Self {
+        Self::new(self.amount.clone() * decimal_from_i32(percent) / decimal_from_i32(100), self.currency)
+    }
+
+    /// Returns `part / whole` of the amount, e.g. the price of returned items of the order
+    pub fn share(&self, part: i32, whole: i32) -> FieldResult<Self> {
+        if whole == 0 {
+            let details = format!("Share {} of zero parts of {} amount.", part, self.currency.code());
+            return Err(FieldError::new(
+                "Could not calculate price.",
+                graphql_value!({ "code": 100, "details": { details }}),
+            ));
+        }
+
+        Ok(Self::new(self.amount.clone() * decimal_from_i32(part) / decimal_from_i32(whole), self.currency))
+    }
+
+    /// Applies discount given as a fraction, e.g. `0.15` for 15%
+    pub fn discounted(&self, discount: f64) -> Self {
+        let rest = decimal_from_i32(1) - Money::from_f64(discount, self.currency).amount;
+        Self::new(self.amount.clone() * rest, self.currency)
+    }
+
+    /// Converts amount with exchange rate of `currency` to the current currency
+    pub fn exchange(&self, rate: ExchangeRate, currency: Currency) -> FieldResult<Self> {
+        let rate = Money::from_f64(rate.0, currency).amount;
+        if rate == BigDecimal::default() {
+            let details = format!("Zero exchange rate from {} to {}.", self.currency.code(), currency.code());
+            return Err(FieldError::new(
+                "Could not convert money.",
+                graphql_value!({ "code": 100, "details": { details }}),
+            ));
+        }
+
+        Ok(Self::new(self.amount.clone() / rate, currency))
+    }
+
+    /// Adds two amounts, amounts in different currencies can be added only if one of them is zero
+    pub fn checked_add(self, other: Money) -> FieldResult<Self> {
+        if other.is_zero() {
+            Ok(self)
+        } else if self.is_zero() || self.currency == other.currency {
+            Ok(Self::new(self.amount + other.amount, other.currency))
+        } else {
+            Err(currency_mismatch(self.currency, other.currency))
+        }
+    }
+
+    pub fn checked_sub(self, other: Money) -> FieldResult<Self> {
+        let negative = Money::new(BigDecimal::default() - other.amount, other.currency);
+        self.checked_add(negative)
+    }
+
+    /// Sums amounts, `currency` is used for the result when there is nothing to sum
+    pub fn sum<I>(currency: Currency, amounts: I) -> FieldResult<Self>
+    where
+        I: IntoIterator<Item = Money>,
+    {
+        amounts.into_iter().try_fold(Money::zero(currency), |acc, amount| acc.checked_add(amount))
+    }
+
+    /// Rounded amount as `f64`, for the deprecated float fields of the schema
+    pub fn to_f64(&self) -> f64 {
+        self.round().amount.to_string().parse::<f64>().unwrap_or(0.0)
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.round().amount)
+    }
+}
+
+fn decimal_from_i32(value: i32) -> BigDecimal {
+    BigDecimal::from_str(&value.to_string()).unwrap_or_default()
+}
+
+fn currency_mismatch(left: Currency, right: Currency) -> FieldError {
+    FieldError::new(
+        "Could not calculate price.",
+        graphql_value!({ "code": 100, "details": { format!("Cannot add amounts in {} and {}.", left.code(), right.code()) }}),
+    )
+}
+
+graphql_scalar!(Money {
+    description: "Decimal amount of money rounded according to its currency, serialized as string, e.g. \"10.25\""
+
+    resolve(&self) -> Value {
+        Value::string(&self.to_string())
+    }
+
+    from_input_value(_v: &InputValue) -> Option<Money> {
+        None
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eur(amount: &str) -> Money {
+        Money::new(BigDecimal::from_str(amount).unwrap(), Currency::EUR)
+    }
+
+    #[test]
+    fn rounds_half_away_from_zero() {
+        assert_eq!(eur("1.005").to_string(), "1.01");
+        assert_eq!(eur("1.004").to_string(), "1.00");
+        assert_eq!(eur("-1.005").to_string(), "-1.01");
+        assert_eq!(eur("-1.004").to_string(), "-1.00");
+    }
+
+    #[test]
+    fn rounds_to_currency_scale() {
+        let btc = Money::new(BigDecimal::from_str("0.123456785").unwrap(), Currency::BTC);
+        assert_eq!(btc.to_string(), "0.12345679");
+        assert_eq!(eur("10.25").to_string(), "10.25");
+    }
+
+    #[test]
+    fn adds_amounts_in_same_currency() {
+        assert_eq!(eur("0.1").checked_add(eur("0.2")).unwrap(), eur("0.3"));
+        assert_eq!(eur("1").checked_sub(eur("0.25")).unwrap().to_string(), "0.75");
+    }
+
+    #[test]
+    fn rejects_adding_amounts_in_different_currencies() {
+        let btc = Money::new(BigDecimal::from_str("1").unwrap(), Currency::BTC);
+        assert!(eur("1").checked_add(btc.clone()).is_err());
+        assert!(Money::sum(Currency::EUR, vec![eur("1"), btc.clone()]).is_err());
+        // zero amounts have no meaningful currency
+        assert_eq!(Money::zero(Currency::EUR).checked_add(btc.clone()).unwrap(), btc);
+    }
+
+    #[test]
+    fn takes_share_of_amount() {
+        assert_eq!(eur("10").share(1, 3).unwrap().to_string(), "3.33");
+        assert_eq!(eur("10").share(2, 3).unwrap().to_string(), "6.67");
+        assert_eq!(eur("10").share(3, 3).unwrap().to_string(), "10.00");
+        assert!(eur("10").share(1, 0).is_err());
+    }
+
+    #[test]
+    fn exchanges_with_rate() {
+        let btc = eur("100").exchange(ExchangeRate(4.0), Currency::BTC).unwrap();
+        assert_eq!(btc.currency, Currency::BTC);
+        assert_eq!(btc.to_string(), "25.00000000");
+        assert!(eur("100").exchange(ExchangeRate(0.0), Currency::BTC).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use chrono::prelude::*;
+use juniper;
 use uuid::Uuid;
 
 use stq_api::orders::{Order, OrderDiff};
@@ -56,6 +57,9 @@ pub struct CreateOrderInputV2 {
     pub currency: Currency,
     #[graphql(description = "User country code")]
     pub user_country_code: String,
+    #[graphql(description = "Token from checkoutQuote, orders are created with the quoted prices")]
+    #[serde(skip_serializing)]
+    pub quote_token: Option<String>,
 }
 
 impl CreateOrderInputV2 {
@@ -125,6 +129,8 @@ pub struct CreateOrder {
     pub product_info: HashMap<ProductId, ProductInfo>,
     pub uuid: String,
     pub currency_type: Option<CurrencyType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quoted_pricing: Option<QuotedPricing>,
 }
 
 #[derive(Serialize, Debug, Clone, PartialEq)]
@@ -156,6 +162,9 @@ pub struct OrderStatusDeliveryInput {
     pub track_id: Option<String>,
     #[graphql(description = "Comment.")]
     pub comment: Option<String>,
+    #[graphql(description = "Delivery company raw id, makes shipment events trackable.")]
+    #[serde(skip_serializing)]
+    pub company_id: Option<i32>,
 }
 
 #[derive(Serialize, Debug, Clone, PartialEq)]
@@ -188,7 +197,7 @@ pub struct OrderStatusCanceledInput {
     pub order_slug: i32,
     #[graphql(description = "Comment")]
     pub comment: Option<String>,
-    #[graphql(description = "Committer Role, by default - System.")]
+    #[graphql(description = "Committer Role, by default - the first role of the current user allowing the transition.")]
     pub committer_role: Option<CommitterRole>,
 }
 
@@ -220,7 +229,7 @@ pub struct OrderStatusCompleteInput {
     pub order_slug: i32,
     #[graphql(description = "Comment")]
     pub comment: Option<String>,
-    #[graphql(description = "Committer Role, by default - System.")]
+    #[graphql(description = "Committer Role, by default - the first role of the current user allowing the transition.")]
     pub committer_role: Option<CommitterRole>,
 }
 
@@ -244,7 +253,7 @@ impl From<OrderStatusCompleteInput> for OrderStatusComplete {
 #[derive(Deserialize, Debug, Clone)]
 pub struct OrderHistoryItem(pub OrderDiff);
 
-#[derive(GraphQLInputObject, Serialize, Clone, Debug, Default)]
+#[derive(GraphQLInputObject, Serialize, Deserialize, Clone, Debug, Default)]
 #[graphql(description = "Search order option input object")]
 pub struct SearchOrderOptionInput {
     #[graphql(description = "Slug")]
@@ -275,9 +284,14 @@ pub struct SearchOrder {
 #[derive(Clone, Debug)]
 pub struct PageInfoOrdersSearch {
     pub total_pages: i32,
+    pub total_count: i32,
     pub current_page: i32,
     pub page_items_count: i32,
     pub search_term_options: SearchOrderOption,
+    pub has_next_page: bool,
+    pub has_previous_page: bool,
+    pub start_cursor: Option<juniper::ID>,
+    pub end_cursor: Option<juniper::ID>,
 }
 
 #[derive(GraphQLObject, Serialize, Clone, Debug, Default)]
@@ -370,6 +384,9 @@ pub struct BuyNowInputV2 {
     pub coupon_code: Option<String>,
     #[graphql(description = "Select delivery package shipping id")]
     pub shipping_id: i32,
+    #[graphql(description = "Token from checkoutQuote, order is created with the quoted prices")]
+    #[serde(skip_serializing)]
+    pub quote_token: Option<String>,
 }
 
 impl BuyNowInputV2 {
@@ -399,6 +416,8 @@ pub struct BuyNow {
     pub delivery_info: Option<DeliveryInfo>, // TODO: drop Option<T>
     pub product_info: ProductInfo,
     pub uuid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quoted_pricing: Option<QuotedPricing>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -420,6 +439,8 @@ pub struct OrderConfirmedInput {
 pub struct UpdateOrderState {
     pub order_slug: OrderSlug,
     pub state: OrderState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_id: Option<String>,
     pub comment: Option<String>,
     pub committer_role: CommitterRole,
 }
@@ -429,23 +450,13 @@ impl From<OrderConfirmedInput> for UpdateOrderState {
         Self {
             order_slug: OrderSlug(order.order_slug),
             state: OrderState::InProcessing,
+            track_id: None,
             comment: order.comment,
             committer_role: CommitterRole::Seller,
         }
     }
 }
 
-impl From<CreateDisputeInput> for UpdateOrderState {
-    fn from(order: CreateDisputeInput) -> Self {
-        Self {
-            order_slug: OrderSlug(order.order_slug),
-            state: OrderState::Dispute,
-            comment: order.comment,
-            committer_role: CommitterRole::Customer,
-        }
-    }
-}
-
 #[derive(GraphQLInputObject, Serialize, Debug, Clone, PartialEq)]
 #[graphql(description = "Confirmation by the financier that the money is transferred to the seller input object.")]
 pub struct PaidToSellerOrderStateInput {
@@ -0,0 +1,62 @@
+use stq_static_resources::CommitterRole;
+use stq_types::OrderSlug;
+
+use super::*;
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Order to be sent with its track id.")]
+pub struct OrderDeliveryItemInput {
+    #[graphql(description = "Slug of order.")]
+    pub order_slug: i32,
+    #[graphql(description = "Track id.")]
+    pub track_id: Option<String>,
+    #[graphql(description = "Delivery company raw id, makes shipment events trackable.")]
+    pub company_id: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Order Status Delivery batch input.")]
+pub struct OrderStatusDeliveryBatchInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Orders with track ids.")]
+    pub orders: Vec<OrderDeliveryItemInput>,
+    #[graphql(description = "Comment, the same for all orders.")]
+    pub comment: Option<String>,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Order Status batch input.")]
+pub struct OrderStatusBatchInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Slugs of orders.")]
+    pub order_slugs: Vec<i32>,
+    #[graphql(description = "Comment, the same for all orders.")]
+    pub comment: Option<String>,
+    #[graphql(description = "Committer Role, by default - the first role of the current user allowing the transition.")]
+    pub committer_role: Option<CommitterRole>,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Orders confirm for seller input.")]
+pub struct OrdersConfirmedInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Slugs of orders.")]
+    pub order_slugs: Vec<i32>,
+    #[graphql(description = "Comment, the same for all orders.")]
+    pub comment: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct OrderStateBatchItem {
+    pub order_slug: OrderSlug,
+    pub order: Option<GraphQLOrder>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct OrderStateBatchOutput {
+    pub items: Vec<OrderStateBatchItem>,
+}
@@ -36,6 +36,8 @@ pub struct OrderBilling {
     pub store_id: StoreId,
     pub state: PaymentState,
     pub stripe_fee: Option<f64>,
+    #[serde(default)]
+    pub refunded_amount: f64,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -0,0 +1,61 @@
+use stq_types::StoreId;
+
+use super::jwt::JWTPayload;
+use super::*;
+
+#[derive(GraphQLEnum, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[graphql(name = "OrdersExportFormat", description = "Orders export file format")]
+pub enum OrdersExportFormat {
+    #[graphql(description = "Comma separated values.")]
+    Csv,
+    #[graphql(description = "Excel workbook.")]
+    Xlsx,
+}
+
+impl OrdersExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match *self {
+            OrdersExportFormat::Csv => "csv",
+            OrdersExportFormat::Xlsx => "xlsx",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match *self {
+            OrdersExportFormat::Csv => "text/csv; charset=utf-8",
+            OrdersExportFormat::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+}
+
+#[derive(GraphQLInputObject, Clone, Debug)]
+#[graphql(description = "Export store orders input object")]
+pub struct ExportStoreOrdersInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Store raw id")]
+    pub store_id: i32,
+    #[graphql(description = "File format")]
+    pub format: OrdersExportFormat,
+    #[graphql(description = "Search options pattern, the same as in Store.orders")]
+    pub search_term_options: SearchOrderOptionInput,
+}
+
+#[derive(GraphQLObject, Clone, Debug)]
+#[graphql(description = "Link to download exported orders")]
+pub struct OrdersExport {
+    #[graphql(description = "Path of the gateway to download the file from, the orders are exported at the time of download")]
+    pub url: String,
+    #[graphql(description = "The link can not be used after this time")]
+    pub expires_at: String,
+}
+
+/// Export request signed into the download link, orders are searched again with the user of the link
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OrdersExportClaims {
+    pub exp: i64,
+    pub user: JWTPayload,
+    pub store_id: StoreId,
+    pub format: OrdersExportFormat,
+    pub search_term_options: SearchOrderOptionInput,
+}
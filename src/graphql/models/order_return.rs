@@ -0,0 +1,178 @@
+use std::fmt;
+
+use chrono::prelude::*;
+
+use stq_types::{OrderId, OrderSlug, Quantity, StoreId, UserId};
+
+use super::*;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReturnId(pub i32);
+
+impl fmt::Display for ReturnId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(GraphQLEnum, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[graphql(name = "ReturnStatus", description = "Return request status")]
+pub enum ReturnStatus {
+    #[graphql(description = "Opened by the customer, waiting for the seller decision.")]
+    Requested,
+    #[graphql(description = "Accepted by the seller, the customer can ship the items back.")]
+    Accepted,
+    #[graphql(description = "Rejected by the seller.")]
+    Rejected,
+    #[graphql(description = "Items are shipped back to the seller.")]
+    Shipped,
+    #[graphql(description = "Items passed the inspection, the customer is refunded.")]
+    Refunded,
+    #[graphql(description = "Items did not pass the inspection.")]
+    InspectionFailed,
+}
+
+#[derive(GraphQLEnum, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[graphql(name = "ReturnReason", description = "Reason of the return")]
+pub enum ReturnReason {
+    Defective,
+    NotAsDescribed,
+    WrongItem,
+    WrongSize,
+    NoLongerNeeded,
+    Other,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OrderReturnItem {
+    pub order_id: OrderId,
+    pub order_slug: OrderSlug,
+    pub quantity: Quantity,
+    #[serde(default)]
+    pub refund_id: Option<RefundId>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct OrderReturn {
+    pub id: ReturnId,
+    pub customer_id: UserId,
+    pub store_id: StoreId,
+    pub items: Vec<OrderReturnItem>,
+    pub reason: ReturnReason,
+    pub status: ReturnStatus,
+    pub comment: Option<String>,
+    pub instructions: Option<String>,
+    pub seller_comment: Option<String>,
+    pub track_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct OrderReturnSearchResults {
+    pub total_count: u32,
+    pub returns: Vec<OrderReturn>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct OrderReturnSearch {
+    pub customer_id: Option<UserId>,
+    pub store_id: Option<StoreId>,
+    pub order_id: Option<OrderId>,
+    /// Returns including any of the orders
+    pub order_ids: Option<Vec<OrderId>>,
+    pub status: Option<ReturnStatus>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NewOrderReturn {
+    pub customer_id: UserId,
+    pub store_id: StoreId,
+    pub items: Vec<OrderReturnItem>,
+    pub reason: ReturnReason,
+    pub comment: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct UpdateOrderReturn {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<ReturnStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seller_comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<OrderReturnItem>>,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Returned order item input object.")]
+pub struct ReturnItemInput {
+    #[graphql(description = "Slug of order.")]
+    pub order_slug: i32,
+    #[graphql(description = "Quantity to return, not greater than ordered.")]
+    pub quantity: i32,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Open return input object.")]
+pub struct OpenReturnInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Returned items, all orders must be from the same store.")]
+    pub items: Vec<ReturnItemInput>,
+    #[graphql(description = "Reason of the return.")]
+    pub reason: ReturnReason,
+    #[graphql(description = "Description of the problem.")]
+    pub comment: Option<String>,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Accept return input object.")]
+pub struct AcceptReturnInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Return raw id.")]
+    pub return_id: i32,
+    #[graphql(description = "Return shipping instructions: address, packing, carrier.")]
+    pub instructions: String,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Reject return input object.")]
+pub struct RejectReturnInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Return raw id.")]
+    pub return_id: i32,
+    #[graphql(description = "Explanation for the customer.")]
+    pub comment: String,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Set return track id input object.")]
+pub struct SetReturnTrackIdInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Return raw id.")]
+    pub return_id: i32,
+    #[graphql(description = "Track id of the return shipment.")]
+    pub track_id: String,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Inspect returned items input object.")]
+pub struct InspectReturnInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Return raw id.")]
+    pub return_id: i32,
+    #[graphql(description = "Whether the items passed the inspection, the customer is refunded if so.")]
+    pub passed: bool,
+    #[graphql(description = "Inspection result for the customer.")]
+    pub comment: Option<String>,
+}
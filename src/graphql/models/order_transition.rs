@@ -0,0 +1,133 @@
+use stq_static_resources::{CommitterRole, OrderState};
+
+#[derive(GraphQLEnum, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[graphql(name = "OrderActor", description = "Participant changing order state")]
+pub enum OrderActor {
+    #[graphql(description = "Customer of the order.")]
+    Customer,
+    #[graphql(description = "Owner of the store.")]
+    Seller,
+    #[graphql(description = "Platform itself, e.g. payment processing.")]
+    System,
+    #[graphql(description = "Platform moderator, resolving disputes.")]
+    Moderator,
+}
+
+impl OrderActor {
+    /// Role sent to saga, moderators commit transitions on behalf of the platform
+    pub fn committer_role(self) -> CommitterRole {
+        match self {
+            OrderActor::Customer => CommitterRole::Customer,
+            OrderActor::Seller => CommitterRole::Seller,
+            OrderActor::System | OrderActor::Moderator => CommitterRole::System,
+        }
+    }
+
+    /// States the actor can move the order to from the given state
+    pub fn allowed_transitions(self, from: &OrderState) -> &'static [OrderState] {
+        match (self, from) {
+            (OrderActor::Customer, &OrderState::New) | (OrderActor::Customer, &OrderState::PaymentAwaited) => &[OrderState::Cancelled],
+            (OrderActor::Customer, &OrderState::Sent)
+            | (OrderActor::Customer, &OrderState::Delivered)
+            | (OrderActor::Customer, &OrderState::Received) => &[OrderState::Complete, OrderState::Dispute],
+
+            (OrderActor::Seller, &OrderState::Paid) => &[OrderState::InProcessing, OrderState::Cancelled],
+            (OrderActor::Seller, &OrderState::InProcessing) => &[OrderState::Sent, OrderState::Cancelled],
+
+            (OrderActor::System, &OrderState::New) => &[OrderState::PaymentAwaited, OrderState::Cancelled],
+            (OrderActor::System, &OrderState::PaymentAwaited) => &[
+                OrderState::TransactionPending,
+                OrderState::Paid,
+                OrderState::AmountExpired,
+                OrderState::Cancelled,
+            ],
+            (OrderActor::System, &OrderState::TransactionPending) => &[OrderState::Paid, OrderState::AmountExpired],
+            (OrderActor::System, &OrderState::AmountExpired) => &[OrderState::PaymentAwaited, OrderState::Cancelled],
+            (OrderActor::System, &OrderState::Paid) | (OrderActor::System, &OrderState::InProcessing) => &[OrderState::Cancelled],
+            (OrderActor::System, &OrderState::Sent) => &[OrderState::Delivered, OrderState::Complete],
+            (OrderActor::System, &OrderState::Delivered) => &[OrderState::Received, OrderState::Complete],
+            (OrderActor::System, &OrderState::Received) => &[OrderState::Complete],
+
+            (OrderActor::Moderator, &OrderState::Dispute) => &[OrderState::Complete, OrderState::Cancelled],
+
+            _ => &[],
+        }
+    }
+}
+
+#[derive(GraphQLObject, Clone, Debug, PartialEq)]
+#[graphql(description = "Order state transition available for the current user")]
+pub struct OrderStateTransition {
+    #[graphql(description = "State the order can be moved to")]
+    pub state: OrderState,
+    #[graphql(description = "Participant committing the transition")]
+    pub actor: OrderActor,
+    #[graphql(description = "Committer role to pass to the state mutation")]
+    pub committer_role: CommitterRole,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Superusers act both as the platform and as moderators
+    fn superuser_transitions(from: &OrderState) -> Vec<OrderState> {
+        OrderActor::System
+            .allowed_transitions(from)
+            .iter()
+            .chain(OrderActor::Moderator.allowed_transitions(from))
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn customer_transitions() {
+        let customer = OrderActor::Customer;
+        assert_eq!(customer.allowed_transitions(&OrderState::New), &[OrderState::Cancelled]);
+        assert_eq!(customer.allowed_transitions(&OrderState::PaymentAwaited), &[OrderState::Cancelled]);
+        assert_eq!(customer.allowed_transitions(&OrderState::Sent), &[OrderState::Complete, OrderState::Dispute]);
+        assert_eq!(customer.allowed_transitions(&OrderState::Received), &[OrderState::Complete, OrderState::Dispute]);
+
+        assert!(customer.allowed_transitions(&OrderState::Paid).is_empty());
+        assert!(customer.allowed_transitions(&OrderState::InProcessing).is_empty());
+        assert!(customer.allowed_transitions(&OrderState::Dispute).is_empty());
+        assert!(customer.allowed_transitions(&OrderState::Complete).is_empty());
+        assert!(customer.allowed_transitions(&OrderState::Cancelled).is_empty());
+    }
+
+    #[test]
+    fn store_manager_transitions() {
+        let seller = OrderActor::Seller;
+        assert_eq!(seller.allowed_transitions(&OrderState::Paid), &[OrderState::InProcessing, OrderState::Cancelled]);
+        assert_eq!(seller.allowed_transitions(&OrderState::InProcessing), &[OrderState::Sent, OrderState::Cancelled]);
+
+        assert!(seller.allowed_transitions(&OrderState::New).is_empty());
+        assert!(seller.allowed_transitions(&OrderState::PaymentAwaited).is_empty());
+        assert!(seller.allowed_transitions(&OrderState::Sent).is_empty());
+        assert!(seller.allowed_transitions(&OrderState::Dispute).is_empty());
+        assert!(seller.allowed_transitions(&OrderState::Complete).is_empty());
+        assert!(seller.allowed_transitions(&OrderState::Cancelled).is_empty());
+    }
+
+    #[test]
+    fn superuser_transitions_include_platform_and_moderation() {
+        assert_eq!(
+            superuser_transitions(&OrderState::PaymentAwaited),
+            vec![OrderState::TransactionPending, OrderState::Paid, OrderState::AmountExpired, OrderState::Cancelled]
+        );
+        assert_eq!(superuser_transitions(&OrderState::Sent), vec![OrderState::Delivered, OrderState::Complete]);
+        assert_eq!(superuser_transitions(&OrderState::Dispute), vec![OrderState::Complete, OrderState::Cancelled]);
+
+        assert!(!superuser_transitions(&OrderState::Paid).contains(&OrderState::Sent));
+        assert!(superuser_transitions(&OrderState::Complete).is_empty());
+        assert!(superuser_transitions(&OrderState::Cancelled).is_empty());
+    }
+
+    #[test]
+    fn committer_roles() {
+        assert_eq!(OrderActor::Customer.committer_role(), CommitterRole::Customer);
+        assert_eq!(OrderActor::Seller.committer_role(), CommitterRole::Seller);
+        assert_eq!(OrderActor::System.committer_role(), CommitterRole::System);
+        assert_eq!(OrderActor::Moderator.committer_role(), CommitterRole::System);
+    }
+}
@@ -0,0 +1,35 @@
+use stq_types::AttributeId;
+
+use super::{Product, Store};
+
+/// Products with vendor code or barcode starting with one of the codes
+#[derive(Serialize, Debug, Clone)]
+pub struct ProductCodeSearch {
+    pub codes: Vec<String>,
+    /// Custom attributes holding EAN/UPC barcodes
+    pub barcode_attribute_ids: Vec<AttributeId>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ProductCodeSearchResult {
+    pub product: Product,
+    /// Value of the barcode attribute of the product if it has one
+    pub barcode: Option<String>,
+}
+
+#[derive(GraphQLEnum, Debug, Clone, Copy, PartialEq)]
+#[graphql(name = "CodeMatchKind", description = "What the code was matched with")]
+pub enum CodeMatchKind {
+    VendorCode,
+    Barcode,
+    StoreSlug,
+}
+
+#[derive(Debug, Clone)]
+pub struct CodeMatch {
+    pub kind: CodeMatchKind,
+    /// The code is the whole vendor code, barcode or slug, not just its beginning
+    pub exact: bool,
+    pub product: Option<Product>,
+    pub store: Option<Store>,
+}
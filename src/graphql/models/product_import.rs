@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use stq_static_resources::Currency;
+
+#[derive(GraphQLEnum, Clone, Copy, Debug, PartialEq)]
+#[graphql(name = "ProductsImportFormat", description = "Products import file format")]
+pub enum ProductsImportFormat {
+    #[graphql(description = "Comma separated values with header row.")]
+    Csv,
+    #[graphql(description = "JSON array of rows.")]
+    Json,
+}
+
+#[derive(GraphQLInputObject, Clone, Debug)]
+#[graphql(description = "Import products input object")]
+pub struct ImportProductsInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Store raw id")]
+    pub store_id: i32,
+    #[graphql(description = "Sale currency of imported products")]
+    pub currency: Currency,
+    #[graphql(description = "File format")]
+    pub format: ProductsImportFormat,
+    #[graphql(description = "File content as text. \
+                             Every row is a product variant, rows with the same `base_product` key are variants of one base product. \
+                             Columns: base_product, name, short_description, long_description, category_id, slug, \
+                             length_cm, width_cm, height_cm, weight_g, vendor_code, price, discount, cashback, \
+                             photo_main, additional_photos (separated with `|`), pre_order, pre_order_days, \
+                             warehouse_id, quantity and `attr:<attribute raw id>` for attribute values. \
+                             JSON rows have the same fields, attribute values are passed in `attributes` object.")]
+    pub content: String,
+    #[graphql(description = "Only validate rows, nothing is created")]
+    pub dry_run: Option<bool>,
+}
+
+/// Single row of the import file
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ImportProductRow {
+    pub base_product: String,
+    pub name: String,
+    pub short_description: Option<String>,
+    pub long_description: Option<String>,
+    pub category_id: Option<i32>,
+    pub slug: Option<String>,
+    pub length_cm: Option<i32>,
+    pub width_cm: Option<i32>,
+    pub height_cm: Option<i32>,
+    pub weight_g: Option<i32>,
+    pub vendor_code: String,
+    pub price: Option<f64>,
+    pub discount: Option<f64>,
+    pub cashback: Option<f64>,
+    pub photo_main: Option<String>,
+    pub additional_photos: Option<Vec<String>>,
+    pub pre_order: Option<bool>,
+    pub pre_order_days: Option<i32>,
+    pub warehouse_id: Option<String>,
+    pub quantity: Option<i32>,
+    pub attributes: HashMap<i32, String>,
+}
+
+#[derive(GraphQLObject, Clone, Debug)]
+#[graphql(description = "Import result of a single row")]
+pub struct ImportProductRowResult {
+    #[graphql(description = "Row number, starting with 1 for the first row after the header")]
+    pub row: i32,
+    #[graphql(description = "Base product key of the row")]
+    pub base_product: String,
+    #[graphql(description = "Vendor code of the row")]
+    pub vendor_code: String,
+    #[graphql(description = "Raw id of created product variant")]
+    pub product_id: Option<i32>,
+    #[graphql(description = "Validation or creation errors, the row is imported if empty")]
+    pub errors: Vec<String>,
+}
+
+#[derive(GraphQLObject, Clone, Debug)]
+#[graphql(description = "Products import report")]
+pub struct ImportProductsOutput {
+    #[graphql(description = "Whether the import was only validated")]
+    pub dry_run: bool,
+    #[graphql(description = "Number of rows imported, or valid for dry run")]
+    pub imported_count: i32,
+    #[graphql(description = "Number of rows with errors")]
+    pub failed_count: i32,
+    #[graphql(description = "Results of every row")]
+    pub rows: Vec<ImportProductRowResult>,
+}
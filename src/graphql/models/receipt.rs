@@ -0,0 +1,109 @@
+use base64::decode;
+use juniper::{FieldError, FieldResult};
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiptLineKind {
+    Title,
+    Heading,
+    Text,
+    /// Label on the left and amount on the right
+    Row,
+    /// Row written in bold, e.g. the total
+    Total,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ReceiptLine {
+    pub kind: ReceiptLineKind,
+    pub text: String,
+    pub value: Option<String>,
+}
+
+/// Content of the order receipt, billing renders it to PDF with the fonts covering all languages of the texts
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct ReceiptDocument {
+    pub lines: Vec<ReceiptLine>,
+}
+
+impl ReceiptDocument {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(&mut self, text: &str) -> &mut Self {
+        self.push(ReceiptLineKind::Title, text, None)
+    }
+
+    pub fn heading(&mut self, text: &str) -> &mut Self {
+        self.push(ReceiptLineKind::Heading, text, None)
+    }
+
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        self.push(ReceiptLineKind::Text, text, None)
+    }
+
+    pub fn row(&mut self, label: &str, value: &str) -> &mut Self {
+        self.push(ReceiptLineKind::Row, label, Some(value))
+    }
+
+    pub fn total(&mut self, label: &str, value: &str) -> &mut Self {
+        self.push(ReceiptLineKind::Total, label, Some(value))
+    }
+
+    fn push(&mut self, kind: ReceiptLineKind, text: &str, value: Option<&str>) -> &mut Self {
+        self.lines.push(ReceiptLine {
+            kind,
+            text: text.to_string(),
+            value: value.map(|value| value.to_string()),
+        });
+        self
+    }
+}
+
+/// PDF file rendered by billing, the content is base64 encoded
+#[derive(Deserialize, Clone, Debug)]
+pub struct RenderedReceipt {
+    pub content: String,
+}
+
+impl RenderedReceipt {
+    pub fn bytes(&self) -> FieldResult<Vec<u8>> {
+        decode(&self.content).map_err(|err| {
+            FieldError::new(
+                "Could not render receipt.",
+                graphql_value!({ "code": 100, "details": { format!("Receipt content is not base64 encoded: {}", err) }}),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use super::*;
+
+    #[test]
+    fn document_lines() {
+        let mut document = ReceiptDocument::new();
+        document.title("Receipt").row("Delivery", "5.00 EUR").total("Total", "15.00 EUR");
+        assert_eq!(
+            serde_json::to_value(&document).unwrap(),
+            json!({ "lines": [
+                { "kind": "title", "text": "Receipt", "value": null },
+                { "kind": "row", "text": "Delivery", "value": "5.00 EUR" },
+                { "kind": "total", "text": "Total", "value": "15.00 EUR" }
+            ]})
+        );
+    }
+
+    #[test]
+    fn rendered_receipt_bytes() {
+        let receipt = RenderedReceipt {
+            content: "JVBERi0xLjQ=".to_string(),
+        };
+        assert_eq!(receipt.bytes().unwrap(), b"%PDF-1.4".to_vec());
+        assert!(RenderedReceipt { content: "%PDF".to_string() }.bytes().is_err());
+    }
+}
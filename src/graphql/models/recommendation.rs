@@ -0,0 +1,108 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::prelude::*;
+
+use stq_types::{BaseProductId, UserId};
+
+/// Base product bought by the customer, input of co-purchases computation
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Purchase {
+    pub customer_id: UserId,
+    pub base_product_id: BaseProductId,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CoPurchase {
+    pub base_product_id: BaseProductId,
+    /// Number of customers who bought both products
+    pub customers_count: u32,
+}
+
+/// Products bought together by the same customers, computed offline from orders history
+/// and kept in a json file read by the gateway
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CoPurchases {
+    pub generated_at: Option<DateTime<Utc>>,
+    /// Co-purchases of every base product (keyed by base product id), most often bought first
+    pub products: HashMap<String, Vec<CoPurchase>>,
+    /// Base products bought by the most customers, best sellers first
+    #[serde(default)]
+    pub best_sellers: Vec<BaseProductId>,
+}
+
+impl CoPurchases {
+    /// Counts customers who bought every pair of base products, keeps `limit` co-purchases per product
+    /// and `best_sellers_limit` best sellers
+    pub fn compute<I>(purchases: I, limit: usize, best_sellers_limit: usize) -> Self
+    where
+        I: IntoIterator<Item = Purchase>,
+    {
+        let mut by_customer: HashMap<UserId, HashSet<BaseProductId>> = HashMap::new();
+        for purchase in purchases {
+            by_customer
+                .entry(purchase.customer_id)
+                .or_insert_with(HashSet::new)
+                .insert(purchase.base_product_id);
+        }
+
+        let mut counts: HashMap<BaseProductId, HashMap<BaseProductId, u32>> = HashMap::new();
+        for base_products in by_customer.values() {
+            for a in base_products {
+                for b in base_products.iter().filter(|b| *b != a) {
+                    *counts.entry(*a).or_insert_with(HashMap::new).entry(*b).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let products = counts
+            .into_iter()
+            .map(|(base_product_id, co_counts)| {
+                let mut co_purchases = co_counts
+                    .into_iter()
+                    .map(|(base_product_id, customers_count)| CoPurchase {
+                        base_product_id,
+                        customers_count,
+                    })
+                    .collect::<Vec<_>>();
+                co_purchases.sort_by(|a, b| {
+                    b.customers_count
+                        .cmp(&a.customers_count)
+                        .then_with(|| a.base_product_id.0.cmp(&b.base_product_id.0))
+                });
+                co_purchases.truncate(limit);
+                (base_product_id.to_string(), co_purchases)
+            })
+            .collect();
+
+        let mut sales: HashMap<BaseProductId, u32> = HashMap::new();
+        for base_product_id in by_customer.values().flatten() {
+            *sales.entry(*base_product_id).or_insert(0) += 1;
+        }
+        let mut best_sellers = sales.into_iter().collect::<Vec<_>>();
+        best_sellers.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| (a.0).0.cmp(&(b.0).0)));
+        best_sellers.truncate(best_sellers_limit);
+
+        CoPurchases {
+            generated_at: Some(Utc::now()),
+            products,
+            best_sellers: best_sellers.into_iter().map(|(base_product_id, _)| base_product_id).collect(),
+        }
+    }
+
+    /// Products bought together with any of `base_product_ids`, excluding them, most often bought first
+    pub fn also_bought(&self, base_product_ids: &[BaseProductId]) -> Vec<BaseProductId> {
+        let mut counts: HashMap<BaseProductId, u32> = HashMap::new();
+        for base_product_id in base_product_ids {
+            for co_purchase in self.products.get(&base_product_id.to_string()).into_iter().flatten() {
+                *counts.entry(co_purchase.base_product_id).or_insert(0) += co_purchase.customers_count;
+            }
+        }
+
+        let mut also_bought = counts
+            .into_iter()
+            .filter(|(base_product_id, _)| !base_product_ids.contains(base_product_id))
+            .collect::<Vec<_>>();
+        also_bought.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| (a.0).0.cmp(&(b.0).0)));
+        also_bought.into_iter().map(|(base_product_id, _)| base_product_id).collect()
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt;
+
+use chrono::prelude::*;
+
+use stq_static_resources::Currency;
+use stq_types::OrderId;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RefundId(pub i32);
+
+impl fmt::Display for RefundId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(GraphQLEnum, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[graphql(name = "RefundMethod", description = "Way the money is returned to the buyer")]
+pub enum RefundMethod {
+    #[graphql(description = "Refund of the Stripe payment intent charge.")]
+    Stripe,
+    #[graphql(description = "Transfer back to the wallet the crypto invoice was paid from.")]
+    Crypto,
+}
+
+#[derive(GraphQLEnum, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[graphql(name = "RefundStatus", description = "Refund status")]
+pub enum RefundStatus {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Refund {
+    pub id: RefundId,
+    pub order_id: OrderId,
+    pub amount: f64,
+    pub currency: Currency,
+    pub method: RefundMethod,
+    pub status: RefundStatus,
+    pub reason: String,
+    pub fee_amount: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Billing chooses Stripe or crypto refund by the way the order was paid.
+/// Partial refund is made if amount is given, billing recalculates the fee proportionally.
+#[derive(Serialize, Debug, Clone)]
+pub struct NewRefund {
+    pub amount: Option<f64>,
+    pub reason: String,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Refund order input object.")]
+pub struct RefundOrderInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Slug of order.")]
+    pub order_slug: i32,
+    #[graphql(description = "Amount to refund in the seller currency, the whole remaining amount if not given.")]
+    pub amount: Option<f64>,
+    #[graphql(description = "Reason of the refund.")]
+    pub reason: String,
+}
@@ -0,0 +1,125 @@
+use std::fmt;
+
+use chrono::prelude::*;
+
+use stq_types::{BaseProductId, OrderId, ProductId, StoreId, UserId};
+
+pub const MIN_RATING: i32 = 1;
+pub const MAX_RATING: i32 = 5;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReviewId(pub i32);
+
+impl fmt::Display for ReviewId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Review of the bought product, stores microservice recalculates
+/// `BaseProduct.rating` and `Store.rating` from visible reviews
+#[derive(Deserialize, Debug, Clone)]
+pub struct Review {
+    pub id: ReviewId,
+    pub user_id: UserId,
+    pub order_id: OrderId,
+    pub store_id: StoreId,
+    pub base_product_id: BaseProductId,
+    pub product_id: ProductId,
+    pub rating: i32,
+    pub text: String,
+    pub reply: Option<String>,
+    pub replied_at: Option<DateTime<Utc>>,
+    pub is_hidden: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(GraphQLObject, Deserialize, Debug, Clone)]
+#[graphql(description = "Number of reviews with the rating")]
+pub struct RatingCount {
+    #[graphql(description = "Rating from 1 to 5")]
+    pub rating: i32,
+    #[graphql(description = "Reviews count")]
+    pub count: i32,
+}
+
+#[derive(GraphQLObject, Deserialize, Debug, Clone, Default)]
+#[graphql(description = "Aggregated rating of the reviews")]
+pub struct RatingDistribution {
+    #[graphql(description = "Average rating, 0 if there are no reviews")]
+    pub average: f64,
+    #[graphql(description = "Reviews count by rating, from 5 to 1")]
+    pub counts: Vec<RatingCount>,
+}
+
+/// Distribution is aggregated over all found reviews, not only over the requested page
+#[derive(Deserialize, Debug, Clone)]
+pub struct ReviewSearchResults {
+    pub total_count: u32,
+    pub reviews: Vec<Review>,
+    pub rating_distribution: RatingDistribution,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ReviewSearch {
+    pub user_id: Option<UserId>,
+    pub order_id: Option<OrderId>,
+    pub store_id: Option<StoreId>,
+    pub base_product_id: Option<BaseProductId>,
+    pub include_hidden: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NewReview {
+    pub user_id: UserId,
+    pub order_id: OrderId,
+    pub store_id: StoreId,
+    pub base_product_id: BaseProductId,
+    pub product_id: ProductId,
+    pub rating: i32,
+    pub text: String,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct UpdateReview {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_hidden: Option<bool>,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Create review input object.")]
+pub struct CreateReviewInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Slug of the complete order with the product.")]
+    pub order_slug: i32,
+    #[graphql(description = "Rating from 1 to 5.")]
+    pub rating: i32,
+    #[graphql(description = "Review text.")]
+    pub text: String,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Reply to review input object.")]
+pub struct ReplyToReviewInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Review raw id.")]
+    pub review_id: i32,
+    #[graphql(description = "Reply of the seller.")]
+    pub reply: String,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Hide or show review input object.")]
+pub struct SetReviewHiddenInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Review raw id.")]
+    pub review_id: i32,
+    #[graphql(description = "Whether the review is hidden from buyers.")]
+    pub hidden: bool,
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+
+use chrono::prelude::*;
+
+use stq_types::{BaseProductId, UserId};
+
+use super::SearchProductInput;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SavedSearchId(pub i32);
+
+impl fmt::Display for SavedSearchId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Products search repeated by the buyer, products found by the search and published
+/// after `checked_at` are new matches. `checked_at` of a new saved search is its creation time
+#[derive(Deserialize, Debug, Clone)]
+pub struct SavedSearch {
+    pub id: SavedSearchId,
+    pub user_id: UserId,
+    pub name: String,
+    pub search_term: SearchProductInput,
+    pub alerts_enabled: bool,
+    pub checked_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NewSavedSearch {
+    pub user_id: UserId,
+    pub name: String,
+    pub search_term: SearchProductInput,
+    pub alerts_enabled: bool,
+}
+
+/// Saving the check time releases the claim of the saved search
+#[derive(Serialize, Debug, Clone)]
+pub struct UpdateSavedSearch {
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Notification about base products found by the saved search since its previous check
+#[derive(Serialize, Debug, Clone)]
+pub struct SavedSearchMatches {
+    pub user_id: UserId,
+    pub saved_search_id: SavedSearchId,
+    pub saved_search_name: String,
+    pub base_product_ids: Vec<BaseProductId>,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone)]
+#[graphql(description = "Save search input object.")]
+pub struct SaveSearchInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Name of the search shown to the user.")]
+    pub name: String,
+    #[graphql(description = "Search pattern with filters.")]
+    pub search_term: SearchProductInput,
+    #[graphql(description = "Notify about new products found by the search, true by default.")]
+    pub alerts_enabled: Option<bool>,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone)]
+#[graphql(description = "Delete saved search input object.")]
+pub struct DeleteSavedSearchInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Saved search id.")]
+    pub saved_search_id: i32,
+}
@@ -1,8 +1,11 @@
 use super::*;
 
-use stq_static_resources::{Currency, ModerationStatus};
+use stq_static_resources::{Currency, Language, ModerationStatus};
+use stq_types::CategoryId;
 
-#[derive(GraphQLInputObject, Serialize, Clone, Debug, Default)]
+use text;
+
+#[derive(GraphQLInputObject, Serialize, Deserialize, Clone, Debug, Default)]
 pub struct ProductsSearchOptionsInput {
     #[graphql(description = "Attribute filters.")]
     pub attr_filters: Option<Vec<AttributeFilterInput>>,
@@ -18,9 +21,11 @@ pub struct ProductsSearchOptionsInput {
     pub sort_by: Option<ProductsSorting>,
     #[graphql(description = "Status.")]
     pub status: Option<ModerationStatus>,
+    #[graphql(description = "Distance of the store of the product from the point.")]
+    pub near: Option<GeoDistanceInput>,
 }
 
-#[derive(GraphQLInputObject, Serialize, Clone, Debug, Default)]
+#[derive(GraphQLInputObject, Serialize, Deserialize, Clone, Debug, Default)]
 #[graphql(description = "Search product input object")]
 pub struct SearchProductInput {
     #[graphql(description = "Name part of the product.")]
@@ -72,3 +77,163 @@ pub enum ProductsSorting {
     PriceDesc,
     Discount,
 }
+
+/// Facets of the found products, counts of every facet are computed with all the filters
+/// except the filters of the facet itself, so that other values of the facet stay selectable
+#[derive(Deserialize, Clone, Debug)]
+pub struct ProductsFacets {
+    pub total_count: i32,
+    pub attributes: Vec<AttributeFacet>,
+    pub price_histogram: Vec<PriceBucket>,
+    pub categories: Vec<CategoryFacet>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct AttributeFacet {
+    pub id: i32,
+    /// Values of attributes filtered by equality, most common first
+    pub values: Vec<FacetValue>,
+    /// Bounds of attributes filtered by range
+    pub range: Option<RangeFilter>,
+    #[serde(skip)]
+    pub attribute: Option<Attribute>,
+}
+
+#[derive(GraphQLObject, Deserialize, Clone, Debug)]
+#[graphql(description = "Attribute value with products count")]
+pub struct FacetValue {
+    #[graphql(description = "Value")]
+    pub value: String,
+    #[graphql(description = "Found products count")]
+    pub count: i32,
+    #[graphql(description = "Value is in the current filters")]
+    #[serde(default)]
+    pub selected: bool,
+}
+
+#[derive(GraphQLObject, Deserialize, Clone, Debug)]
+#[graphql(description = "Price histogram bucket, min value is inclusive and max value is exclusive")]
+pub struct PriceBucket {
+    #[graphql(description = "Min value")]
+    pub min_value: f64,
+    #[graphql(description = "Max value")]
+    pub max_value: f64,
+    #[graphql(description = "Found products count")]
+    pub count: i32,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct CategoryFacet {
+    pub category_id: CategoryId,
+    pub count: i32,
+    #[serde(default)]
+    pub selected: bool,
+    #[serde(skip)]
+    pub category: Option<Category>,
+}
+
+impl ProductsFacets {
+    /// Marks facets matching the filters of the search
+    pub fn mark_selected(&mut self, search_term: &SearchProductInput) {
+        let options = match search_term.options {
+            Some(ref options) => options,
+            None => return,
+        };
+
+        let attr_filters = options.attr_filters.clone().unwrap_or_default();
+        for attribute in &mut self.attributes {
+            let selected_values = attr_filters
+                .iter()
+                .filter(|filter| filter.id == attribute.id)
+                .filter_map(|filter| filter.equal.as_ref())
+                .flat_map(|equal| equal.values.iter())
+                .collect::<Vec<_>>();
+            for value in &mut attribute.values {
+                value.selected = selected_values.contains(&&value.value);
+            }
+        }
+
+        for category in &mut self.categories {
+            category.selected = options.category_id == Some(category.category_id.0);
+        }
+    }
+
+    /// Sets attributes and categories of the facets from the full lists fetched once per search
+    pub fn fill_details(&mut self, attributes: &[Attribute], categories_tree: Option<&Category>) {
+        for facet in &mut self.attributes {
+            facet.attribute = attributes.iter().find(|attribute| attribute.id.0 == facet.id).cloned();
+        }
+
+        for facet in &mut self.categories {
+            facet.category = categories_tree.and_then(|tree| find_category(tree, facet.category_id)).cloned();
+        }
+    }
+}
+
+fn find_category(category: &Category, category_id: CategoryId) -> Option<&Category> {
+    if category.id == category_id {
+        return Some(category);
+    }
+    category.children.iter().filter_map(|child| find_category(child, category_id)).next()
+}
+
+/// Words that mean the same in the language, read from `search.synonyms_path`
+#[derive(Deserialize, Clone, Debug)]
+pub struct SynonymGroup {
+    pub language: Language,
+    pub words: Vec<String>,
+}
+
+/// Synonym groups of all languages, read once at startup
+#[derive(Clone, Debug, Default)]
+pub struct Synonyms {
+    groups: Vec<SynonymGroup>,
+}
+
+impl Synonyms {
+    pub fn new(groups: Vec<SynonymGroup>) -> Self {
+        let groups = groups
+            .into_iter()
+            .map(|group| SynonymGroup {
+                language: group.language,
+                words: group.words.iter().map(|word| word.to_lowercase()).collect(),
+            })
+            .collect();
+        Self { groups }
+    }
+
+    /// Other words of the groups containing the lowercase word, Cyrillic words have only Russian synonyms
+    pub fn of_word(&self, word: &str) -> Vec<&str> {
+        let cyrillic = text::is_cyrillic(word);
+        self.groups
+            .iter()
+            .filter(|group| match group.language {
+                Language::Ru => cyrillic,
+                _ => !cyrillic,
+            })
+            .filter(|group| group.words.iter().any(|group_word| group_word == word))
+            .flat_map(|group| group.words.iter())
+            .filter(|synonym| *synonym != word)
+            .map(|synonym| synonym.as_str())
+            .collect()
+    }
+
+    /// The query followed by the synonyms of its words, so that products named with a synonym are found too
+    pub fn expand(&self, query: &str) -> String {
+        let words = text::words(query);
+        let mut synonyms: Vec<&str> = vec![];
+        for word in &words {
+            for synonym in self.of_word(word) {
+                if !words.iter().any(|word| word == synonym) && !synonyms.contains(&synonym) {
+                    synonyms.push(synonym);
+                }
+            }
+        }
+
+        if synonyms.is_empty() {
+            query.to_string()
+        } else {
+            format!("{} {}", query, synonyms.join(" "))
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::fmt;
+
+use chrono::prelude::*;
+
+use stq_types::{CompanyId, OrderId};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShipmentId(pub i32);
+
+impl fmt::Display for ShipmentId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Shipment {
+    pub id: ShipmentId,
+    pub order_id: OrderId,
+    pub company_id: Option<CompanyId>,
+    pub tracking_number: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NewShipment {
+    pub order_id: OrderId,
+    pub company_id: Option<CompanyId>,
+    pub tracking_number: String,
+}
+
+#[derive(GraphQLEnum, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[graphql(name = "TrackingStatus", description = "Status of the shipment reported by the carrier")]
+pub enum TrackingStatus {
+    InfoReceived,
+    InTransit,
+    OutForDelivery,
+    Delivered,
+    Exception,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TrackingEvent {
+    pub status: TrackingStatus,
+    pub description: String,
+    pub location: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
@@ -181,6 +181,8 @@ pub struct StoresSearchOptionsInput {
     pub category_id: Option<i32>,
     #[graphql(description = "Country.")]
     pub country: Option<String>,
+    #[graphql(description = "Distance of the store from the point.")]
+    pub near: Option<GeoDistanceInput>,
 }
 
 #[derive(GraphQLInputObject, Serialize, Clone, Debug)]
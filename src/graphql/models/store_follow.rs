@@ -0,0 +1,69 @@
+use chrono::prelude::*;
+
+use stq_types::{StoreId, UserId};
+
+use super::{BaseProduct, Coupon};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct StoreFollow {
+    pub user_id: UserId,
+    pub store_id: StoreId,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NewStoreFollow {
+    pub user_id: UserId,
+    pub store_id: StoreId,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Follow store input object.")]
+pub struct FollowStoreInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Store raw id.")]
+    pub store_id: i32,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Unfollow store input object.")]
+pub struct UnfollowStoreInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Store raw id.")]
+    pub store_id: i32,
+}
+
+/// News of the followed store, either a published base product or an active coupon
+#[derive(Deserialize, Debug, Clone)]
+pub struct FeedItem {
+    pub store_id: StoreId,
+    pub base_product: Option<BaseProduct>,
+    pub coupon: Option<Coupon>,
+    /// Time the base product was published by moderator, or the coupon was created
+    pub published_at: DateTime<Utc>,
+}
+
+impl FeedItem {
+    pub fn kind(&self) -> FeedItemKind {
+        match self.base_product {
+            Some(_) => FeedItemKind::NewBaseProduct,
+            None => FeedItemKind::NewCoupon,
+        }
+    }
+}
+
+/// News of the followed stores, newest first
+#[derive(Deserialize, Debug, Clone)]
+pub struct FeedSearchResults {
+    pub total_count: u32,
+    pub items: Vec<FeedItem>,
+}
+
+#[derive(GraphQLEnum, Clone, Copy, Debug, PartialEq, Eq)]
+#[graphql(name = "FeedItemKind", description = "Kind of the store news")]
+pub enum FeedItemKind {
+    NewBaseProduct,
+    NewCoupon,
+}
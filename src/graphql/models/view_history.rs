@@ -0,0 +1,44 @@
+use chrono::prelude::*;
+
+use stq_types::BaseProductId;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ViewedBaseProduct {
+    pub base_product_id: BaseProductId,
+    pub viewed_at: DateTime<Utc>,
+}
+
+/// Latest view of every base product, newest first
+#[derive(Deserialize, Debug, Clone)]
+pub struct ViewHistorySearchResults {
+    pub total_count: u32,
+    pub items: Vec<ViewedBaseProduct>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NewView {
+    pub base_product_id: BaseProductId,
+}
+
+#[derive(GraphQLObject, Serialize, Deserialize, Debug, Clone)]
+#[graphql(description = "Recently viewed products settings of the user or the session")]
+pub struct ViewHistorySettings {
+    #[graphql(description = "Views are recorded, history is cleared when turned off")]
+    pub enabled: bool,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Clear recently viewed products input object.")]
+pub struct ClearViewHistoryInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Turn recording of viewed products on or off input object.")]
+pub struct SetViewHistoryEnabledInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Whether viewed products are recorded.")]
+    pub enabled: bool,
+}
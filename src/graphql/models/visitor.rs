@@ -0,0 +1,20 @@
+use stq_types::{SessionId, UserId};
+
+/// Identity of the visitor: logged in user or anonymous session
+#[derive(Clone, Copy, Debug)]
+pub enum Visitor {
+    User(UserId),
+    Session(SessionId),
+}
+
+impl From<UserId> for Visitor {
+    fn from(user_id: UserId) -> Self {
+        Visitor::User(user_id)
+    }
+}
+
+impl From<SessionId> for Visitor {
+    fn from(session_id: SessionId) -> Self {
+        Visitor::Session(session_id)
+    }
+}
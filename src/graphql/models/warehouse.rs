@@ -34,6 +34,30 @@ pub struct GeoPointInput {
     pub y: f64,
 }
 
+#[derive(GraphQLInputObject, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[graphql(description = "Distance from the point input object, distance of a store is the distance to its nearest warehouse")]
+pub struct GeoDistanceInput {
+    #[graphql(description = "Latitude of the point in degrees.")]
+    pub latitude: f64,
+    #[graphql(description = "Longitude of the point in degrees.")]
+    pub longitude: f64,
+    #[graphql(description = "Only results within the distance in kilometers.")]
+    pub within_km: Option<f64>,
+    #[graphql(description = "Nearest results first.")]
+    pub sort_by_distance: Option<bool>,
+}
+
+impl GeoDistanceInput {
+    /// Geo points have longitude as `x` and latitude as `y`
+    pub fn point(&self) -> Point<f64> {
+        Point::new(self.longitude, self.latitude)
+    }
+
+    pub fn sort_by_distance(&self) -> bool {
+        self.sort_by_distance.unwrap_or(false)
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct GraphQLWarehouse(pub Warehouse);
 
@@ -0,0 +1,61 @@
+use chrono::prelude::*;
+
+use stq_types::{ProductId, SessionId, UserId};
+
+use super::Product;
+
+/// Saved product with the product state at the moment it was saved,
+/// used to tell the customer about price drops and restocks
+#[derive(Deserialize, Debug, Clone)]
+pub struct WishlistItem {
+    pub product_id: ProductId,
+    pub discount_at_add: Option<f64>,
+    pub in_stock_at_add: bool,
+    pub added_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NewWishlistItem {
+    pub product_id: ProductId,
+    pub discount_at_add: Option<f64>,
+    pub in_stock_at_add: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct WishlistSearchResults {
+    pub total_count: u32,
+    pub items: Vec<WishlistItem>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct MergeWishlist {
+    pub from_session_id: SessionId,
+    pub to_user_id: UserId,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Add to wishlist input object.")]
+pub struct AddToWishlistInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Product raw id.")]
+    pub product_id: i32,
+}
+
+#[derive(GraphQLInputObject, Debug, Clone, PartialEq)]
+#[graphql(description = "Remove from wishlist input object.")]
+pub struct RemoveFromWishlistInput {
+    #[graphql(description = "Client mutation id.")]
+    pub client_mutation_id: String,
+    #[graphql(description = "Product raw id.")]
+    pub product_id: i32,
+}
+
+/// Wishlist item with the current product, product is none if it was deleted
+#[derive(Debug, Clone)]
+pub struct WishlistEntry {
+    pub item: WishlistItem,
+    pub product: Option<Product>,
+    /// Product is in stock in any warehouse now
+    pub in_stock: bool,
+}
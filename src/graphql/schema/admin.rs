@@ -14,8 +14,7 @@ use stq_routes::service::Service;
 
 use graphql::context::Context;
 use graphql::models::*;
-
-const MIN_ID: i32 = 0;
+use graphql::schema::dispute as dispute_module;
 
 graphql_object!(Admin: Context as "Admin" |&self| {
     description: "Admin's profile."
@@ -32,52 +31,36 @@ graphql_object!(Admin: Context as "Admin" |&self| {
 
     field deprecated "use usersSearchPages" users_search(&executor,
         first = None : Option<i32> as "First edges",
-        after = None : Option<GraphqlID>  as "Base64 Id of a user",
+        after = None : Option<GraphqlID>  as "Cursor of the edge to start after",
+        last = None : Option<i32> as "Last edges",
+        before = None : Option<GraphqlID>  as "Cursor of the edge to end before",
         search_term : SearchUserInput as "Search pattern"
         )
             -> FieldResult<Option<Connection<User, PageInfo>>> as "Searching for users using relay connection." {
         let context = executor.context();
 
-        let raw_id = match after {
-            Some(val) => ID::from_str(&*val)?.raw_id + 1,
-            None => MIN_ID
-        };
+        let page = PageRequest::new(first, after, last, before, None, context.config.gateway.records_limit)?;
 
-        let records_limit = context.config.gateway.records_limit;
-        let first = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
+        let body = serde_json::to_string(&search_term)?;
 
-        let url = format!("{}/{}/search?offset={}&count={}",
-            context.config.service_url(Service::Users),
-            Model::User.to_url(),
-            raw_id,
-            first + 1);
+        let search = |offset: i32, count: i32| {
+            let url = context
+                .config
+                .url(Service::Users)
+                .model(Model::User)
+                .path("search")
+                .query("offset", offset)
+                .query("count", count)
+                .build();
+
+            context.request::<UserSearchResults>(Method::Post, url, Some(body.clone())).wait()
+        };
 
-        let body = serde_json::to_string(&search_term)?;
+        let page = page.resolve_from_end(|| search(0, 0).map(|results| results.total_count as i32))?;
+        let UserSearchResults { total_count, users } = search(page.offset, page.fetch_count())?;
 
-        context.request::<UserSearchResults>(Method::Post, url, Some(body))
-            .map (|UserSearchResults { users, .. }| {
-                let mut user_edges: Vec<Edge<User>> = users
-                    .into_iter()
-                    .map(|user| Edge::new(
-                        juniper::ID::from(ID::new(Service::Users, Model::User, user.id.0).to_string()),
-                        user.clone()
-                    )).collect();
-                let has_next_page = user_edges.len() as i32 == first + 1;
-                if has_next_page {
-                    user_edges.pop();
-                };
-                let has_previous_page = true;
-                let start_cursor =  user_edges.get(0).map(|e| e.cursor.clone());
-                let end_cursor = user_edges.iter().last().map(|e| e.cursor.clone());
-                let page_info = PageInfo {
-                    has_next_page,
-                    has_previous_page,
-                    start_cursor,
-                    end_cursor};
-                Connection::new(user_edges, page_info)
-            })
-            .wait()
-            .map(Some)
+        let (user_edges, page_info) = page.with_total_count(total_count as i32).edges(users, |user| Some(user.id.0));
+        Ok(Some(Connection::new(user_edges, page_info)))
     }
 
     field users_search_pages(&executor,
@@ -95,12 +78,14 @@ graphql_object!(Admin: Context as "Admin" |&self| {
 
         let skip = items_count * (current_page - 1);
 
-        let url = format!(
-            "{}/{}/search?skip={}&count={}",
-            context.config.service_url(Service::Users),
-            Model::User.to_url(),
-            skip, items_count,
-        );
+        let url = context
+            .config
+            .url(Service::Users)
+            .model(Model::User)
+            .path("search")
+            .query("skip", skip)
+            .query("count", items_count)
+            .build();
 
         let body = serde_json::to_string(&search_term)?;
 
@@ -116,6 +101,7 @@ graphql_object!(Admin: Context as "Admin" |&self| {
                     current_page,
                     page_items_count: items_count,
                     total_pages,
+                    total_count: total_count as i32,
                 };
                 Connection::new(user_edges, page_info)
             })
@@ -125,17 +111,22 @@ graphql_object!(Admin: Context as "Admin" |&self| {
 
     field deprecated "use storesSearchPages" stores_search(&executor,
         first = None : Option<i32> as "First edges",
-        after = None : Option<GraphqlID>  as "Base64 Id of a store",
+        after = None : Option<GraphqlID>  as "Cursor of the edge to start after",
+        last = None : Option<i32> as "Last edges",
+        before = None : Option<GraphqlID>  as "Cursor of the edge to end before",
         search_term : SearchModeratorStoreInput as "Search pattern"
         )
             -> FieldResult<Option<Connection<Store, PageInfo>>> as "Searching stores by moderator using relay connection." {
         let context = executor.context();
 
         let store_manager_ids = if let Some(ref store_manager_email) = search_term.store_manager_email {
-            let url = format!("{}/{}/search/by_email?email={}",
-                context.config.service_url(Service::Users),
-                Model::User.to_url(),
-                store_manager_email);
+            let url = context
+                .config
+                .url(Service::Users)
+                .model(Model::User)
+                .path("search/by_email")
+                .query("email", &store_manager_email)
+                .build();
 
             let users_ids = context.request::<Vec<User>>(Method::Get, url, None)
                 .wait()?
@@ -150,44 +141,26 @@ graphql_object!(Admin: Context as "Admin" |&self| {
 
         let body = serde_json::to_string(&term)?;
 
-        let raw_id = match after {
-            Some(val) => ID::from_str(&*val)?.raw_id,
-            None => MIN_ID
-        };
+        let page = PageRequest::new(first, after, last, before, None, context.config.gateway.records_limit)?;
 
-        let records_limit = context.config.gateway.records_limit;
-        let first = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
+        let search = |offset: i32, count: i32| {
+            let url = context
+                .config
+                .url(Service::Stores)
+                .model(Model::Store)
+                .path("moderator_search")
+                .query("offset", offset)
+                .query("count", count)
+                .build();
 
-        let url = format!("{}/{}/moderator_search?offset={}&count={}",
-            context.config.service_url(Service::Stores),
-            Model::Store.to_url(),
-            raw_id,
-            first);
+            context.request::<StoreSearchResults>(Method::Post, url, Some(body.clone())).wait()
+        };
 
-        context.request::<StoreSearchResults>(Method::Post, url, Some(body))
-            .map(|StoreSearchResults { stores, .. }| {
-                let mut store_edges: Vec<Edge<Store>> = stores
-                    .into_iter()
-                    .map(|store| Edge::new(
-                        juniper::ID::from(ID::new(Service::Stores, Model::Store, store.id.0).to_string()),
-                        store.clone()
-                    )).collect();
-                let has_next_page = store_edges.len() as i32 == first + 1;
-                if has_next_page {
-                    store_edges.pop();
-                };
-                let has_previous_page = true;
-                let start_cursor =  store_edges.get(0).map(|e| e.cursor.clone());
-                let end_cursor = store_edges.iter().last().map(|e| e.cursor.clone());
-                let page_info = PageInfo {
-                    has_next_page,
-                    has_previous_page,
-                    start_cursor,
-                    end_cursor};
-                Connection::new(store_edges, page_info)
-            })
-            .wait()
-            .map(Some)
+        let page = page.resolve_from_end(|| search(0, 0).map(|results| results.total_count as i32))?;
+        let StoreSearchResults { total_count, stores } = search(page.offset, page.fetch_count())?;
+
+        let (store_edges, page_info) = page.with_total_count(total_count as i32).edges(stores, |store| Some(store.id.0));
+        Ok(Some(Connection::new(store_edges, page_info)))
     }
 
     field stores_search_pages(&executor,
@@ -199,10 +172,13 @@ graphql_object!(Admin: Context as "Admin" |&self| {
         let context = executor.context();
 
         let store_manager_ids = if let Some(ref store_manager_email) = search_term.store_manager_email {
-            let url = format!("{}/{}/search/by_email?email={}",
-                context.config.service_url(Service::Users),
-                Model::User.to_url(),
-                store_manager_email);
+            let url = context
+                .config
+                .url(Service::Users)
+                .model(Model::User)
+                .path("search/by_email")
+                .query("email", &store_manager_email)
+                .build();
 
             let users_ids = context.request::<Vec<User>>(Method::Get, url, None)
                 .wait()?
@@ -224,11 +200,14 @@ graphql_object!(Admin: Context as "Admin" |&self| {
 
         let body = serde_json::to_string(&term)?;
 
-        let url = format!("{}/{}/moderator_search?skip={}&count={}",
-            context.config.service_url(Service::Stores),
-            Model::Store.to_url(),
-            skip, items_count,
-        );
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::Store)
+            .path("moderator_search")
+            .query("skip", skip)
+            .query("count", items_count)
+            .build();
 
         context.request::<StoreSearchResults>(Method::Post, url, Some(body))
             .map(|StoreSearchResults { stores, total_count }| {
@@ -243,6 +222,7 @@ graphql_object!(Admin: Context as "Admin" |&self| {
                     current_page,
                     page_items_count: items_count,
                     total_pages,
+                    total_count: total_count as i32,
                 };
                 Connection::new(store_edges, page_info)
             })
@@ -252,10 +232,13 @@ graphql_object!(Admin: Context as "Admin" |&self| {
 
     field deprecated "use baseProductsSearchPages" base_products_search(&executor,
         first = None : Option<i32> as "First edges",
-        after = None : Option<GraphqlID>  as "Base64 Id of a base_product",
+        after = None : Option<GraphqlID>  as "Cursor of the edge to start after",
+        last = None : Option<i32> as "Last edges",
+        before = None : Option<GraphqlID>  as "Cursor of the edge to end before",
         search_term : SearchModeratorBaseProductInput as "Search pattern"
     ) -> FieldResult<Option<Connection<BaseProduct, PageInfo>>> as "Searching base_products by moderator using relay connection." {
-        base_products_search(executor.context(), first, after, search_term)
+        let page = PageRequest::new(first, after, last, before, None, executor.context().config.gateway.records_limit)?;
+        base_products_search(executor.context(), page, search_term)
     }
 
     field base_products_search_pages(&executor,
@@ -265,61 +248,51 @@ graphql_object!(Admin: Context as "Admin" |&self| {
     ) -> FieldResult<Option<Connection<BaseProduct, PageInfoSegments>>> as "Searching base_products by moderator using relay connection." {
         base_products_search_pages(executor.context(), current_page, items_count, search_term)
     }
+
+    field disputes(&executor,
+        current_page : i32 as "Current page",
+        items_count : i32 as "Items count",
+        status : Option<DisputeStatus> as "Dispute status")
+            -> FieldResult<Connection<Dispute, PageInfoSegments>> as "All disputes for moderators." {
+        let context = executor.context();
+        let search = DisputeSearch {
+            status,
+            ..Default::default()
+        };
+
+        dispute_module::find_disputes(context, current_page, items_count, search)
+    }
 });
 
 pub fn base_products_search(
     context: &Context,
-    first: Option<i32>,
-    after: Option<GraphqlID>,
+    page: PageRequest,
     search_term: SearchModeratorBaseProductInput,
 ) -> FieldResult<Option<Connection<BaseProduct, PageInfo>>> {
     let body = serde_json::to_string(&search_term)?;
 
-    let raw_id = match after {
-        Some(val) => ID::from_str(&*val)?.raw_id,
-        None => MIN_ID,
+    let search = |offset: i32, count: i32| {
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::BaseProduct)
+            .path("moderator_search")
+            .query("offset", offset)
+            .query("count", count)
+            .build();
+
+        context
+            .request::<BaseProductSearchResults>(Method::Post, url, Some(body.clone()))
+            .wait()
     };
 
-    let records_limit = context.config.gateway.records_limit;
-    let first = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
-
-    let url = format!(
-        "{}/{}/moderator_search?offset={}&count={}",
-        context.config.service_url(Service::Stores),
-        Model::BaseProduct.to_url(),
-        raw_id,
-        first
-    );
+    let page = page.resolve_from_end(|| search(0, 0).map(|results| results.total_count as i32))?;
+    let BaseProductSearchResults { total_count, base_products } = search(page.offset, page.fetch_count())?;
 
-    context
-        .request::<BaseProductSearchResults>(Method::Post, url, Some(body))
-        .map(|BaseProductSearchResults { base_products, .. }| {
-            let mut base_product_edges: Vec<Edge<BaseProduct>> = base_products
-                .into_iter()
-                .map(|base_product| {
-                    Edge::new(
-                        juniper::ID::from(ID::new(Service::Stores, Model::BaseProduct, base_product.id.0).to_string()),
-                        base_product.clone(),
-                    )
-                })
-                .collect();
-            let has_next_page = base_product_edges.len() as i32 == first + 1;
-            if has_next_page {
-                base_product_edges.pop();
-            };
-            let has_previous_page = true;
-            let start_cursor = base_product_edges.get(0).map(|e| e.cursor.clone());
-            let end_cursor = base_product_edges.iter().last().map(|e| e.cursor.clone());
-            let page_info = PageInfo {
-                has_next_page,
-                has_previous_page,
-                start_cursor,
-                end_cursor,
-            };
-            Connection::new(base_product_edges, page_info)
-        })
-        .wait()
-        .map(Some)
+    let (base_product_edges, page_info) = page
+        .with_total_count(total_count as i32)
+        .edges(base_products, |base_product| Some(base_product.id.0));
+    Ok(Some(Connection::new(base_product_edges, page_info)))
 }
 
 pub fn base_products_search_pages(
@@ -335,13 +308,14 @@ pub fn base_products_search_pages(
 
     let skip = items_count * (current_page - 1);
 
-    let url = format!(
-        "{}/{}/moderator_search?skip={}&count={}",
-        context.config.service_url(Service::Stores),
-        Model::BaseProduct.to_url(),
-        skip,
-        items_count,
-    );
+    let url = context
+        .config
+        .url(Service::Stores)
+        .model(Model::BaseProduct)
+        .path("moderator_search")
+        .query("skip", skip)
+        .query("count", items_count)
+        .build();
 
     let body = serde_json::to_string(&search_term)?;
 
@@ -366,6 +340,7 @@ pub fn base_products_search_pages(
                     current_page,
                     page_items_count: items_count,
                     total_pages,
+                    total_count: total_count as i32,
                 };
                 Connection::new(base_product_edges, page_info)
             },
@@ -144,26 +144,14 @@ pub struct TranslatedValue {
     pub translations: Vec<Translation>,
 }
 
-fn get_attribute_values(context: &Context, attribute_id: AttributeId) -> FieldResult<Option<Vec<AttributeValue>>> {
-    let url = format!(
-        "{}/{}/{}/{}",
-        context.config.service_url(Service::Stores),
-        Model::Attribute.to_url(),
-        attribute_id,
-        Model::AttributeValue.to_url(),
-    );
+pub fn get_attribute_values(context: &Context, attribute_id: AttributeId) -> FieldResult<Option<Vec<AttributeValue>>> {
+    let url = context.config.url(Service::Stores).model(Model::Attribute).segment(attribute_id).model(Model::AttributeValue).build();
 
     context.request::<Option<Vec<AttributeValue>>>(Method::Get, url, None).wait()
 }
 
 fn get_attribute_value(context: &Context, attribute_value_id: AttributeValueId) -> FieldResult<AttributeValue> {
-    let url = format!(
-        "{}/{}/{}/{}",
-        context.config.service_url(Service::Stores),
-        Model::Attribute.to_url(),
-        Model::AttributeValue.to_url(),
-        attribute_value_id
-    );
+    let url = context.config.url(Service::Stores).model(Model::Attribute).model(Model::AttributeValue).segment(attribute_value_id).build();
 
     context.request::<AttributeValue>(Method::Get, url, None).wait()
 }
@@ -198,12 +186,7 @@ fn get_attribute_meta_field(
 }
 
 fn try_get_attribute(context: &Context, attribute_id: AttributeId) -> FieldResult<Option<Attribute>> {
-    let url = format!(
-        "{}/{}/{}",
-        context.config.service_url(Service::Stores),
-        Model::Attribute.to_url(),
-        attribute_id.0
-    );
+    let url = context.config.url(Service::Stores).model(Model::Attribute).segment(attribute_id.0).build();
 
     context.request::<Option<Attribute>>(Method::Get, url, None).wait()
 }
@@ -90,12 +90,7 @@ graphql_object!(AvailablePackageForUser: Context as "AvailablePackageForUser" |&
         }
         .unwrap_or(self.currency);
 
-        let exch_rate = if let Some(exch_rate) =
-            context
-                .get_stores_microservice()
-                .get_currency_exchange_info()?
-                .data
-                .get(&self.currency) {
+        let exch_rate = if let Some(exch_rate) = context.exchange_rates()?.get(&self.currency) {
             exch_rate.get(&user_currency).map(|rate| rate.0).unwrap_or(1.0)
         } else {
             1.0
@@ -123,12 +118,13 @@ graphql_object!(AvailableShippingForUser: Context as "AvailableShippingForUser"
 });
 
 pub fn get_available_package_for_user_by_id_v1(context: &Context, shipping_id: ShippingId) -> FieldResult<AvailablePackageForUser> {
-    let url = format!(
-        "{}/{}/by_shipping_id/{}",
-        context.config.service_url(Service::Delivery),
-        Model::AvailablePackageForUser.to_url(),
-        shipping_id,
-    );
+    let url = context
+        .config
+        .url(Service::Delivery)
+        .model(Model::AvailablePackageForUser)
+        .path("by_shipping_id")
+        .segment(shipping_id)
+        .build();
 
     context
         .request::<Option<AvailablePackageForUser>>(Method::Get, url, None)
@@ -149,16 +145,18 @@ pub fn try_get_available_package_for_user_by_id(
     volume: u32,
     weight: u32,
 ) -> FieldResult<Option<AvailablePackageForUser>> {
-    let url = format!(
-        "{}/v2/{}/by_shipping_id/{}?delivery_from={}&delivery_to={}&volume={}&weight={}",
-        context.config.service_url(Service::Delivery),
-        Model::AvailablePackageForUser.to_url(),
-        shipping_id,
-        delivery_from,
-        delivery_to,
-        volume,
-        weight,
-    );
+    let url = context
+        .config
+        .url(Service::Delivery)
+        .path("v2")
+        .model(Model::AvailablePackageForUser)
+        .path("by_shipping_id")
+        .segment(shipping_id)
+        .query("delivery_from", &delivery_from)
+        .query("delivery_to", &delivery_to)
+        .query("volume", volume)
+        .query("weight", weight)
+        .build();
 
     context.request::<Option<AvailablePackageForUser>>(Method::Get, url, None).wait()
 }
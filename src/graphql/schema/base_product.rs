@@ -20,6 +20,8 @@ use super::*;
 use errors::into_graphql;
 use graphql::context::Context;
 use graphql::models::*;
+use graphql::schema::recommendation as recommendation_module;
+use graphql::schema::review as review_module;
 
 graphql_object!(BaseProduct: Context as "BaseProduct" |&self| {
     description: "Base Product's info."
@@ -66,6 +68,25 @@ graphql_object!(BaseProduct: Context as "BaseProduct" |&self| {
         &self.rating
     }
 
+    field reviews(&executor,
+        current_page : i32 as "Current page",
+        items_count : i32 as "Items count")
+            -> FieldResult<Connection<Review, PageInfoReviews>> as "Reviews of the product variants." {
+        let context = executor.context();
+        let search = ReviewSearch {
+            base_product_id: Some(self.id),
+            ..Default::default()
+        };
+
+        review_module::find_reviews(context, current_page, items_count, search)
+    }
+
+    field related_products(&executor, first = None : Option<i32> as "Max number of products") -> FieldResult<Vec<BaseProduct>> as "Products bought by customers who bought this product." {
+        let context = executor.context();
+
+        recommendation_module::recommend(context, &[self.clone()], first)
+    }
+
     field status() -> &ModerationStatus as "Moderation Status" {
         &self.status
     }
@@ -111,12 +132,7 @@ graphql_object!(BaseProduct: Context as "BaseProduct" |&self| {
     field moderator_comment(&executor) -> FieldResult<Option<ModeratorProductComments>> as "Fetches moderator comment by id." {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}/{}",
-            &context.config.service_url(Service::Stores),
-            Model::ModeratorProductComment.to_url(),
-            self.id.to_string()
-        );
+        let url = context.config.url(Service::Stores).model(Model::ModeratorProductComment).segment(self.id).build();
 
         context.request::<Option<ModeratorProductComments>>(Method::Get, url, None)
             .wait()
@@ -129,13 +145,7 @@ graphql_object!(BaseProduct: Context as "BaseProduct" |&self| {
         let context = executor.context();
         let visibility = visibility.unwrap_or_default();
 
-        let url = format!(
-            "{}/{}/{}?visibility={}",
-            &context.config.service_url(Service::Stores),
-            Model::Store.to_url(),
-            self.store_id.to_string(),
-            visibility
-        );
+        let url = context.config.url(Service::Stores).model(Model::Store).segment(self.store_id).query("visibility", &visibility).build();
 
         context.request::<Option<Store>>(Method::Get, url, None)
             .wait()
@@ -143,10 +153,7 @@ graphql_object!(BaseProduct: Context as "BaseProduct" |&self| {
 
     field category(&executor) -> FieldResult<Option<Category>> as "Category" {
         let context = executor.context();
-        let url = format!("{}/{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::Category.to_url(),
-            self.category_id.0);
+        let url = context.config.url(Service::Stores).model(Model::Category).segment(self.category_id.0).build();
 
         context.request::<Option<Category>>(Method::Get, url, None)
             .wait()
@@ -157,10 +164,7 @@ graphql_object!(BaseProduct: Context as "BaseProduct" |&self| {
         if let Some(ref variants) = self.variants {
             Ok(Some(Variants::new(variants.clone())))
         } else {
-            let url = format!("{}/{}/by_base_product/{}",
-                context.config.service_url(Service::Stores),
-                Model::Product.to_url(),
-                self.id);
+            let url = context.config.url(Service::Stores).model(Model::Product).path("by_base_product").segment(self.id).build();
 
             context.request::<Vec<Product>>(Method::Get, url, None)
                 .wait()
@@ -189,11 +193,7 @@ graphql_object!(BaseProduct: Context as "BaseProduct" |&self| {
             -> FieldResult<Option<Connection<Product, PageInfo>>> as "Fetches products using relay connection." {
         let context = executor.context();
 
-        let offset = after
-            .and_then(|id|{
-                i32::from_str(&id).map(|i| i + 1).ok()
-            })
-            .unwrap_or_default();
+        let offset = Cursor::offset_after(after)?;
 
         let records_limit = context.config.gateway.records_limit;
         let first = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
@@ -202,48 +202,63 @@ graphql_object!(BaseProduct: Context as "BaseProduct" |&self| {
             let mut product_edges: Vec<Edge<Product>> = variants.clone()
                 .into_iter()
                 .skip(offset as usize)
-                .take(first as usize)
-                .map(|product| Edge::new(
-                            juniper::ID::from(ID::new(Service::Stores, Model::Product, product.id.0).to_string()),
-                            product.clone()
-                        ))
+                .take(first as usize + 1)
+                .enumerate()
+                .map(|(i, product)| {
+                    let cursor = Cursor {
+                        offset: offset + i as i32,
+                        key: None,
+                        id: Some(product.id.0),
+                    };
+                    Edge::new(cursor.encode(), product)
+                })
                 .collect();
             let has_next_page = product_edges.len() as i32 > first;
-            let has_previous_page = true;
+            if has_next_page {
+                product_edges.pop();
+            };
+            let has_previous_page = offset > 0;
             let start_cursor =  product_edges.get(0).map(|e| e.cursor.clone());
             let end_cursor = product_edges.iter().last().map(|e| e.cursor.clone());
             let page_info = PageInfo {
                 has_next_page,
                 has_previous_page,
                 start_cursor,
-                end_cursor};
+                end_cursor,
+                total_count: None};
             Ok(Some(Connection::new(product_edges, page_info)))
         } else {
-            let url = format!("{}/{}/by_base_product/{}",
-                context.config.service_url(Service::Stores),
-                Model::Product.to_url(),
-                self.id);
+            let url = context.config.url(Service::Stores).model(Model::Product).path("by_base_product").segment(self.id).build();
 
             context.request::<Vec<Product>>(Method::Get, url, None)
             .map (|products| {
                 let mut product_edges: Vec<Edge<Product>> = products
                     .into_iter()
                     .skip(offset as usize)
-                    .take(first as usize)
-                    .map(|product| Edge::new(
-                                juniper::ID::from(ID::new(Service::Stores, Model::Product, product.id.0).to_string()),
-                                product.clone()
-                            ))
+                    .take(first as usize + 1)
+                    .enumerate()
+                    .map(|(i, product)| {
+                        let cursor = Cursor {
+                            offset: offset + i as i32,
+                            key: None,
+                            id: Some(product.id.0),
+                        };
+                        Edge::new(cursor.encode(), product)
+                    })
                     .collect();
                 let has_next_page = product_edges.len() as i32 > first;
-                let has_previous_page = true;
+                if has_next_page {
+                    product_edges.pop();
+                };
+                let has_previous_page = offset > 0;
                 let start_cursor =  product_edges.get(0).map(|e| e.cursor.clone());
                 let end_cursor = product_edges.iter().last().map(|e| e.cursor.clone());
                 let page_info = PageInfo {
                     has_next_page,
                     has_previous_page,
                     start_cursor,
-                    end_cursor};
+                    end_cursor,
+                    total_count: None};
                 Connection::new(product_edges, page_info)
             })
             .wait()
@@ -261,12 +276,7 @@ graphql_object!(BaseProduct: Context as "BaseProduct" |&self| {
 
     field custom_attributes(&executor) -> FieldResult<Vec<CustomAttribute>> as "Custom attributes" {
         let context = executor.context();
-        let url = format!("{}/{}/{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::BaseProduct.to_url(),
-            self.id,
-            Model::CustomAttribute.to_url(),
-            );
+        let url = context.config.url(Service::Stores).model(Model::BaseProduct).segment(self.id).model(Model::CustomAttribute).build();
 
         context.request::<Vec<CustomAttribute>>(Method::Get, url, None)
             .map(From::from)
@@ -285,13 +295,14 @@ graphql_object!(BaseProduct: Context as "BaseProduct" |&self| {
 
         if let Some(warehouse) = warehouses.into_iter().nth(0) {
             if let Some(country_code) = warehouse.country_code {
-                let url = format!(
-                    "{}/available_packages?country={}&weight={}&size={}",
-                    context.config.service_url(Service::Delivery),
-                    country_code.to_string(),
-                    weight_g,
-                    volume_cubic_cm,
-                );
+                let url = context
+                    .config
+                    .url(Service::Delivery)
+                    .path("available_packages")
+                    .query("country", &country_code)
+                    .query("weight", weight_g)
+                    .query("size", volume_cubic_cm)
+                    .build();
 
                 context.request::<Vec<AvailablePackages>>(Method::Get, url, None)
                     .map(From::from)
@@ -312,11 +323,7 @@ graphql_object!(BaseProduct: Context as "BaseProduct" |&self| {
 
     field shipping(&executor) -> FieldResult<ShippingOutput> as "Shipping" {
         let context = executor.context();
-        let url = format!("{}/{}/{}",
-            context.config.service_url(Service::Delivery),
-            Model::Product.to_url(),
-            self.id.0
-        );
+        let url = context.config.url(Service::Delivery).model(Model::Product).segment(self.id.0).build();
 
         context.request::<Shipping>(Method::Get, url, None)
             .map(From::from)
@@ -325,11 +332,7 @@ graphql_object!(BaseProduct: Context as "BaseProduct" |&self| {
 
     field is_shipping_available(&executor)-> FieldResult<bool> as "Is shipping available" {
         let context = executor.context();
-        let url = format!("{}/{}/{}",
-            context.config.service_url(Service::Delivery),
-            Model::Product.to_url(),
-            self.id,
-        );
+        let url = context.config.url(Service::Delivery).model(Model::Product).segment(self.id).build();
 
         let available_shipping = context.request::<Shipping>(Method::Get, url, None)
             .wait()?;
@@ -405,35 +408,25 @@ graphql_object!(Connection<BaseProduct, PageInfoProductsSearch>: Context as "Bas
 });
 
 pub fn try_get_base_product(context: &Context, base_product_id: BaseProductId, visibility: Visibility) -> FieldResult<Option<BaseProduct>> {
-    let url = format!(
-        "{}/{}/{}?visibility={}",
-        &context.config.service_url(Service::Stores),
-        Model::BaseProduct.to_url(),
-        base_product_id.to_string(),
-        visibility,
-    );
+    let url = context
+        .config
+        .url(Service::Stores)
+        .model(Model::BaseProduct)
+        .segment(base_product_id)
+        .query("visibility", &visibility)
+        .build();
 
     context.request::<Option<BaseProduct>>(Method::Get, url, None).wait()
 }
 
 pub fn try_get_base_product_without_filters(context: &Context, base_product_id: BaseProductId) -> FieldResult<Option<BaseProduct>> {
-    let url = format!(
-        "{}/{}/{}/without_filters",
-        context.config.service_url(Service::Stores),
-        Model::BaseProduct.to_url(),
-        base_product_id,
-    );
+    let url = context.config.url(Service::Stores).model(Model::BaseProduct).segment(base_product_id).path("without_filters").build();
 
     context.request::<Option<BaseProduct>>(Method::Get, url, None).wait()
 }
 
 pub fn try_get_base_product_by_product(context: &Context, product_id: ProductId) -> FieldResult<Option<BaseProduct>> {
-    let url = format!(
-        "{}/{}/by_product/{}",
-        context.config.service_url(Service::Stores),
-        Model::BaseProduct.to_url(),
-        product_id
-    );
+    let url = context.config.url(Service::Stores).model(Model::BaseProduct).path("by_product").segment(product_id).build();
 
     context.request::<Option<BaseProduct>>(Method::Get, url, None).wait()
 }
@@ -473,12 +466,7 @@ pub fn run_send_to_moderation_base_product(context: &Context, base_product_id: B
 }
 
 fn send_to_moderation(context: &Context, base_product_id: BaseProductId) -> FieldResult<BaseProduct> {
-    let url = format!(
-        "{}/{}/{}/moderation",
-        context.config.saga_microservice.url.clone(),
-        Model::BaseProduct.to_url(),
-        base_product_id
-    );
+    let url = context.config.saga_url().model(Model::BaseProduct).segment(base_product_id).path("moderation").build();
 
     let _ = context.request::<()>(Method::Post, url, None).wait()?;
 
@@ -505,11 +493,7 @@ pub fn run_moderation_status_base_product(context: &Context, input: BaseProductM
 }
 
 fn send_to_moderate(context: &Context, payload: BaseProductModerate) -> FieldResult<BaseProduct> {
-    let url = format!(
-        "{}/{}/moderate",
-        context.config.saga_microservice.url.clone(),
-        Model::BaseProduct.to_url()
-    );
+    let url = context.config.saga_url().model(Model::BaseProduct).path("moderate").build();
 
     let body: String = serde_json::to_string(&payload)?.to_string();
     let _ = context.request::<()>(Method::Post, url, Some(body)).wait()?;
@@ -525,22 +509,13 @@ pub fn run_draft_base_products_mutation(context: &Context, ids: Vec<i32>) -> Fie
 }
 
 fn send_to_draft_base_product(context: &Context, base_product_id: BaseProductId) -> FieldResult<BaseProduct> {
-    let url = format!(
-        "{}/{}/{}/draft",
-        context.config.service_url(Service::Stores),
-        Model::BaseProduct.to_url(),
-        base_product_id
-    );
+    let url = context.config.url(Service::Stores).model(Model::BaseProduct).segment(base_product_id).path("draft").build();
 
     context.request::<BaseProduct>(Method::Post, url, None).wait()
 }
 
 fn validate_change_moderation_status(context: &Context, payload: &BaseProductModerate) -> FieldResult<bool> {
-    let url = format!(
-        "{}/{}/validate_change_moderation_status",
-        context.config.service_url(Service::Stores),
-        Model::BaseProduct.to_url()
-    );
+    let url = context.config.url(Service::Stores).model(Model::BaseProduct).path("validate_change_moderation_status").build();
 
     let body: String = serde_json::to_string(&payload)?.to_string();
 
@@ -582,6 +557,22 @@ pub fn get_base_product_shipping_details(
     })
 }
 
+pub fn run_create_base_product_with_variants(context: &Context, input: NewBaseProductWithVariantsInput) -> FieldResult<BaseProduct> {
+    let url = context.config.saga_url().model(Model::BaseProduct).path("create_with_variants").build();
+    let mut input = input;
+    input.variants = input
+        .variants
+        .into_iter()
+        .map(|mut variant| {
+            variant.product = variant.product.fill_uuid(variant.client_mutation_id.clone());
+            variant
+        })
+        .collect();
+    let body: String = serde_json::to_string(&input.fill_uuid())?.to_string();
+
+    context.request::<BaseProduct>(Method::Post, url, Some(body)).wait()
+}
+
 pub fn run_update_base_product(context: &Context, input: UpdateBaseProductInput) -> FieldResult<BaseProduct> {
     let identifier = ID::from_str(&*input.id)?;
     let base_product_id = BaseProductId(identifier.raw_id);
@@ -606,12 +597,7 @@ pub fn run_update_base_product(context: &Context, input: UpdateBaseProductInput)
 }
 
 pub fn validate_update_base_product(context: &Context, base_product_id: BaseProductId) -> FieldResult<bool> {
-    let url = format!(
-        "{}/{}/{}/validate_update",
-        context.config.service_url(Service::Stores),
-        Model::BaseProduct.to_url(),
-        base_product_id
-    );
+    let url = context.config.url(Service::Stores).model(Model::BaseProduct).segment(base_product_id).path("validate_update").build();
 
     context.request::<bool>(Method::Get, url, None).wait()
 }
@@ -8,6 +8,7 @@ use stq_types::*;
 use graphql::context::Context;
 use graphql::models::*;
 use graphql::schema::available_packages::*;
+use graphql::schema::checkout_quote;
 use graphql::schema::coupon;
 use graphql::schema::order;
 use graphql::schema::product as product_module;
@@ -25,18 +26,35 @@ graphql_object!(BuyNowCheckout: Context as "BuyNowCheckout" |&self| {
         &self.coupon
     }
 
-    field coupons_discounts() -> f64 as "Coupons discounts" {
+    field deprecated "use couponsDiscountsV2" coupons_discounts() -> f64 as "Coupons discounts" {
+        calculate_coupon_discount(&self).to_f64()
+    }
+
+    field coupons_discounts_v2() -> Money as "Coupons discounts" {
         calculate_coupon_discount(&self)
     }
 
-    field total_cost(&executor) -> FieldResult<f64> as "Total cost" {
+    field deprecated "use totalCostV2" total_cost(&executor) -> FieldResult<f64> as "Total cost" {
+        let context = executor.context();
+        calculate_total_cost(context, &self).map(|money| money.to_f64())
+    }
+
+    field total_cost_v2(&executor) -> FieldResult<Money> as "Total cost" {
         let context = executor.context();
-        Ok(calculate_cost(&self) + calculate_delivery_cost(context, &self.package, self.quantity, self.product.currency)?)
+        calculate_total_cost(context, &self)
     }
 
-    field total_cost_without_discounts(&executor) -> FieldResult<f64> as "Total without cost" {
+    field deprecated "use totalCostWithoutDiscountsV2" total_cost_without_discounts(&executor) -> FieldResult<f64> as "Total without cost" {
         let context = executor.context();
-        Ok(calculate_cost_without_discounts(&self) + calculate_delivery_cost(context, &self.package, self.quantity, self.product.currency)?)
+        calculate_cost_without_discounts(&self)
+            .checked_add(calculate_delivery_cost(context, &self.package, self.quantity, &self.product)?)
+            .map(|money| money.to_f64())
+    }
+
+    field total_cost_without_discounts_v2(&executor) -> FieldResult<Money> as "Total without cost" {
+        let context = executor.context();
+        calculate_cost_without_discounts(&self)
+            .checked_add(calculate_delivery_cost(context, &self.package, self.quantity, &self.product)?)
     }
 
     field total_count() -> &i32 as "Total products count" {
@@ -47,17 +65,34 @@ graphql_object!(BuyNowCheckout: Context as "BuyNowCheckout" |&self| {
         &self.product.customer_price.price.0
     }
 
-    field subtotal() -> f64 as "Subtotal with discounts" {
+    field currency() -> &Currency as "Currency of the price" {
+        &self.product.customer_price.currency
+    }
+
+    field deprecated "use subtotalV2" subtotal() -> FieldResult<f64> as "Subtotal with discounts" {
+        calculate_cost(&self).map(|money| money.to_f64())
+    }
+
+    field subtotal_v2() -> FieldResult<Money> as "Subtotal with discounts" {
         calculate_cost(&self)
     }
 
-    field subtotal_without_discounts() -> f64 as "Subtotal without discounts" {
+    field deprecated "use subtotalWithoutDiscountsV2" subtotal_without_discounts() -> f64 as "Subtotal without discounts" {
+        calculate_cost_without_discounts(&self).to_f64()
+    }
+
+    field subtotal_without_discounts_v2() -> Money as "Subtotal without discounts" {
         calculate_cost_without_discounts(&self)
     }
 
-    field delivery_cost(&executor) -> FieldResult<f64> as "Delivery cost" {
+    field deprecated "use deliveryCostV2" delivery_cost(&executor) -> FieldResult<f64> as "Delivery cost" {
+        let context = executor.context();
+        calculate_delivery_cost(context, &self.package, self.quantity, &self.product).map(|money| money.to_f64())
+    }
+
+    field delivery_cost_v2(&executor) -> FieldResult<Money> as "Delivery cost" {
         let context = executor.context();
-        calculate_delivery_cost(context, &self.package, self.quantity, self.product.currency)
+        calculate_delivery_cost(context, &self.package, self.quantity, &self.product)
     }
 
     field package() -> &Option<AvailablePackageForUser> as "Select delivery package" {
@@ -65,56 +100,66 @@ graphql_object!(BuyNowCheckout: Context as "BuyNowCheckout" |&self| {
     }
 });
 
-fn calculate_cost(buy_now: &BuyNowCheckout) -> f64 {
+pub fn calculate_total_cost(context: &Context, buy_now: &BuyNowCheckout) -> FieldResult<Money> {
+    calculate_cost(buy_now)?.checked_add(calculate_delivery_cost(context, &buy_now.package, buy_now.quantity, &buy_now.product)?)
+}
+
+pub fn calculate_cost(buy_now: &BuyNowCheckout) -> FieldResult<Money> {
+    let price = Money::from_f64(buy_now.product.customer_price.price.0, buy_now.product.customer_price.currency);
+
     if buy_now.quantity.0 <= 0 {
-        return 0f64;
+        return Ok(Money::zero(price.currency));
     }
 
     if let Some(discount) = buy_now.product.discount.filter(|discount| *discount > ZERO_DISCOUNT) {
-        let calc_cost = (buy_now.product.customer_price.price.0 * (f64::from(buy_now.quantity.0))) * (1.0f64 - discount);
+        let calc_cost = price.times(buy_now.quantity).discounted(discount);
 
-        return calc_cost;
+        return Ok(calc_cost.round());
     } else {
         if buy_now.coupon.is_some() {
             // set discount only 1 product
-            let product_cost_with_coupon_discount = buy_now.product.customer_price.price.0 - calculate_coupon_discount(buy_now);
-            let calc_cost =
-                product_cost_with_coupon_discount + (buy_now.product.customer_price.price.0 * (f64::from(buy_now.quantity.0 - 1)));
+            let product_cost_with_coupon_discount = price.clone().checked_sub(calculate_coupon_discount(buy_now))?;
+            let calc_cost = product_cost_with_coupon_discount.checked_add(price.times(Quantity(buy_now.quantity.0 - 1)))?;
 
-            return calc_cost;
+            return Ok(calc_cost.round());
         }
     }
 
-    buy_now.product.customer_price.price.0 * f64::from(buy_now.quantity.0)
+    Ok(price.times(buy_now.quantity).round())
 }
 
-fn calculate_cost_without_discounts(buy_now: &BuyNowCheckout) -> f64 {
+fn calculate_cost_without_discounts(buy_now: &BuyNowCheckout) -> Money {
+    let price = Money::from_f64(buy_now.product.customer_price.price.0, buy_now.product.customer_price.currency);
+
     if buy_now.quantity.0 <= 0 {
-        return 0f64;
+        return Money::zero(price.currency);
     }
 
-    buy_now.product.customer_price.price.0 * f64::from(buy_now.quantity.0)
+    price.times(buy_now.quantity).round()
 }
 
-fn calculate_coupon_discount(buy_now: &BuyNowCheckout) -> f64 {
+pub fn calculate_coupon_discount(buy_now: &BuyNowCheckout) -> Money {
+    let price = Money::from_f64(buy_now.product.customer_price.price.0, buy_now.product.customer_price.currency);
+
     if let Some(coupon) = buy_now.coupon.as_ref() {
         // set discount only 1 product
-        let discount = (buy_now.product.customer_price.price.0 / 100f64) * f64::from(coupon.percent);
-
-        return discount;
+        return price.percent(coupon.percent).round();
     }
 
-    0.0f64
+    Money::zero(price.currency)
 }
 
-fn calculate_delivery_cost(
+pub fn calculate_delivery_cost(
     context: &Context,
     package: &Option<AvailablePackageForUser>,
     quantity: Quantity,
-    currency: Currency,
-) -> FieldResult<f64> {
+    product: &Product,
+) -> FieldResult<Money> {
+    let currency = product.currency;
+    let customer_currency = product.customer_price.currency;
+
     if quantity.0 <= 0 {
-        return Ok(0f64);
+        return Ok(Money::zero(customer_currency));
     };
 
     if let Some(package) = package {
@@ -124,16 +169,20 @@ fn calculate_delivery_cost(
         }
         .unwrap_or(currency);
 
-        let exch_rate = if let Some(exch_rate) = context.get_stores_microservice().get_currency_exchange_info()?.data.get(&currency) {
-            exch_rate.get(&user_currency).map(|rate| rate.0).unwrap_or(1.0)
+        let exch_rate = if let Some(exch_rate) = context.exchange_rates()?.get(&currency) {
+            exch_rate.get(&user_currency).cloned().unwrap_or(ExchangeRate(1.0))
         } else {
-            1.0
+            ExchangeRate(1.0)
         };
 
-        return Ok(package.price.0 / exch_rate * quantity.0 as f64);
+        let delivery_cost = Money::from_f64(package.price.0, package.currency)
+            .exchange(exch_rate, customer_currency)?
+            .times(quantity);
+
+        return Ok(delivery_cost.round());
     }
 
-    Ok(0.0f64)
+    Ok(Money::zero(customer_currency))
 }
 
 pub fn run_buy_now_mutation(context: &Context, input: BuyNowInputV2) -> FieldResult<CreateOrdersOutput> {
@@ -149,47 +198,50 @@ pub fn run_buy_now_mutation(context: &Context, input: BuyNowInputV2) -> FieldRes
         )
     })?;
 
-    let product_price = product_module::get_seller_price(context, ProductId(input.product_id))?;
-    let store_id = store::get_store_id_by_product(context, ProductId(input.product_id))?;
     let product = product_module::get_product(context, ProductId(input.product_id))?;
 
-    let (shipping_details, package) = get_available_package_for_user_with_price(
-        context,
-        product.base_product_id,
-        ShippingId(input.shipping_id),
-        input.user_country_code.as_str(),
-        "Buy Now failed.",
-    )?;
-
-    let coupon = match input.coupon_code {
-        Some(code) => {
-            let coupon = validate_coupon(context, CouponCode(code), ProductId(input.product_id), store_id)?;
-            Some(coupon)
+    let (quoted_product, quoted_pricing) = match input.quote_token.take() {
+        Some(quote_token) => {
+            let quote = checkout_quote::decode_quote(context, &quote_token, CheckoutQuoteKind::BuyNow, user.user_id, input.currency)?;
+            let quoted_product = checkout_quote::validate_buy_now_quote(&quote, &input)?;
+            context.freeze_exchange_rates(quote.exchange_rates.clone());
+            (quoted_product, Some(quote.pricing()))
         }
-        None => None,
+        None => (
+            prepare_buy_now_checkout(
+                context,
+                &product,
+                input.quantity.into(),
+                input.user_country_code.as_str(),
+                input.coupon_code.clone(),
+                ShippingId(input.shipping_id),
+            )?,
+            None,
+        ),
     };
 
     let customer = get_user_by_id(context, user.user_id)?;
-    let delivery_info = get_delivery_info(package);
+    let delivery_info = quoted_product.package.map(get_delivery_info);
     let product_info = ProductInfo::from(product.clone());
 
     let buy_now = BuyNow {
         product_id: input.product_id.into(),
-        store_id: shipping_details.store_id,
+        store_id: quoted_product.store_id,
         customer_id: user.user_id,
         address: input.address_full.into(),
         receiver_name: input.receiver_name,
         receiver_phone: input.receiver_phone,
         receiver_email: customer.email,
-        price: product_price,
+        price: quoted_product.seller_price,
         quantity: input.quantity.into(),
         currency: input.currency,
         pre_order: product.pre_order,
         pre_order_days: product.pre_order_days,
-        coupon,
-        delivery_info: Some(delivery_info),
+        coupon: quoted_product.coupon,
+        delivery_info,
         product_info,
         uuid: input.uuid,
+        quoted_pricing,
     };
 
     if buy_now.currency.currency_type() == CurrencyType::Fiat {
@@ -200,6 +252,39 @@ pub fn run_buy_now_mutation(context: &Context, input: BuyNowInputV2) -> FieldRes
     saga.buy_now(buy_now)
 }
 
+/// Collects current seller price, coupon and delivery package for buy now
+pub fn prepare_buy_now_checkout(
+    context: &Context,
+    product: &Product,
+    quantity: Quantity,
+    user_country_code: &str,
+    coupon_code: Option<String>,
+    shipping_id: ShippingId,
+) -> FieldResult<QuotedProduct> {
+    let seller_price = product_module::get_seller_price(context, product.id)?;
+    let store_id = store::get_store_id_by_product(context, product.id)?;
+
+    let (shipping_details, package) =
+        get_available_package_for_user_with_price(context, product.base_product_id, shipping_id, user_country_code, "Buy Now failed.")?;
+
+    let coupon = match coupon_code {
+        Some(code) => {
+            let coupon = validate_coupon(context, CouponCode(code), product.id, store_id)?;
+            Some(coupon)
+        }
+        None => None,
+    };
+
+    Ok(QuotedProduct {
+        product_id: product.id,
+        store_id: shipping_details.store_id,
+        quantity,
+        seller_price,
+        coupon,
+        package: Some(package),
+    })
+}
+
 /// DEPRECATED
 pub fn run_buy_now_mutation_v1(context: &Context, input: BuyNowInput) -> FieldResult<CreateOrdersOutput> {
     let mut input = input.fill_uuid();
@@ -256,6 +341,7 @@ pub fn run_buy_now_mutation_v1(context: &Context, input: BuyNowInput) -> FieldRe
         delivery_info: Some(delivery_info),
         product_info,
         uuid: input.uuid,
+        quoted_pricing: None,
     };
 
     if buy_now.currency.currency_type() == CurrencyType::Fiat {
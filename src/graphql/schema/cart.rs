@@ -1,8 +1,5 @@
 //! File containing Cart object of graphql schema
 
-use std::cmp;
-use std::str::FromStr;
-
 use juniper::ID as GraphqlID;
 use juniper::{FieldError, FieldResult};
 
@@ -11,24 +8,26 @@ use hyper::Method;
 
 use stq_routes::model::Model;
 use stq_routes::service::Service;
-use stq_types::{CartCustomer, CartItem, DeliveryMethodId, ProductId, Quantity, ShippingId, UserId};
+use stq_types::{CartCustomer, CartItem, DeliveryMethodId, ProductId, Quantity, SessionId, ShippingId, UserId};
 
 use stq_api::orders::{CartClient, UserCountryCodeUpdater};
 use stq_api::types::ApiFutureExt;
 
-use stq_static_resources::CurrencyType;
+use stq_static_resources::{Currency, CurrencyType};
 
 use super::*;
 use graphql::context::Context;
 use graphql::models::*;
 use graphql::schema::cart_store::{
-    calculate_coupons_discount, calculate_products_delivery_cost, calculate_products_price, calculate_products_price_without_discounts,
+    add_converted, calculate_coupons_discount, calculate_products_delivery_cost, calculate_products_price,
+    calculate_products_price_without_discounts, customer_currency,
 };
 
 use errors::into_graphql;
 use graphql::schema::available_packages;
 use graphql::schema::base_product as base_product_module;
 use graphql::schema::product as product_module;
+use graphql::schema::recommendation as recommendation_module;
 
 graphql_object!(Cart: Context as "Cart" |&self| {
     description: "Users cart"
@@ -56,71 +55,97 @@ graphql_object!(Cart: Context as "Cart" |&self| {
 
     field stores(&executor,
         first = None : Option<i32> as "First edges",
-        after = None : Option<GraphqlID>  as "Offset")
-            -> Connection<CartStore, PageInfo> as "Fetches stores using relay connection." {
+        after = None : Option<GraphqlID>  as "Cursor of the edge to start after",
+        last = None : Option<i32> as "Last edges",
+        before = None : Option<GraphqlID>  as "Cursor of the edge to end before")
+            -> FieldResult<Connection<CartStore, PageInfo>> as "Fetches stores using relay connection." {
         let context = executor.context();
 
-        let offset = after
-            .and_then(|id|{
-                i32::from_str(&id).map(|i| i + 1).ok()
-            })
-            .unwrap_or_default();
-
-        let records_limit = context.config.gateway.records_limit;
-        let count = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
+        let page = PageRequest::new(first, after, last, before, None, context.config.gateway.records_limit)?
+            .with_total_count(self.inner.len() as i32);
 
-        let mut cart_stores: Vec<CartStore> = self.inner.clone()
+        let cart_stores: Vec<CartStore> = self.inner.clone()
             .into_iter()
-            .skip(offset as usize)
-            .take(count as usize)
+            .skip(page.offset as usize)
+            .take(page.fetch_count() as usize)
             .collect();
-        let mut store_edges = Edge::create_vec(cart_stores, offset);
-        let has_next_page = store_edges.len() as i32 > count;
-        let has_previous_page = true;
-        let start_cursor =  store_edges.get(0).map(|e| e.cursor.clone());
-        let end_cursor = store_edges.iter().last().map(|e| e.cursor.clone());
-        let page_info = PageInfo {
-            has_next_page,
-            has_previous_page,
-            start_cursor,
-            end_cursor};
-        Connection::new(store_edges, page_info)
+        let (store_edges, page_info) = page.edges(cart_stores, |cart_store| Some(cart_store.id.0));
+        Ok(Connection::new(store_edges, page_info))
+    }
+
+    field deprecated "use productsCostV2" products_cost(&executor) -> FieldResult<f64> as "Products cost" {
+        let context = executor.context();
+
+        calculate_cart_price(context, &self.inner).map(|money| money.to_f64())
     }
 
-    field products_cost(&executor) -> FieldResult<f64> as "Products cost" {
+    field products_cost_v2(&executor) -> FieldResult<Money> as "Products cost" {
         let context = executor.context();
 
         calculate_cart_price(context, &self.inner)
     }
 
-    field products_cost_without_discounts(&executor) -> f64 as "Products without cost" {
+    field deprecated "use productsCostWithoutDiscountsV2" products_cost_without_discounts(&executor) -> FieldResult<f64> as "Products without cost" {
+        let context = executor.context();
+
+        calculate_cart_price_without_discounts(context, &self.inner).map(|money| money.to_f64())
+    }
+
+    field products_cost_without_discounts_v2(&executor) -> FieldResult<Money> as "Products without cost" {
+        let context = executor.context();
+
+        calculate_cart_price_without_discounts(context, &self.inner)
+    }
+
+    field deprecated "use couponsDiscountsV2" coupons_discounts(&executor) -> FieldResult<f64> as "Coupons discounts" {
         let context = executor.context();
 
-        calculate_cart_price_without_discounts(&self.inner)
+        calculate_cart_coupons_discount(context, &self.inner).map(|money| money.to_f64())
     }
 
-    field coupons_discounts(&executor) -> FieldResult<f64> as "Coupons discounts" {
+    field coupons_discounts_v2(&executor) -> FieldResult<Money> as "Coupons discounts" {
         let context = executor.context();
 
         calculate_cart_coupons_discount(context, &self.inner)
     }
 
-    field delivery_cost(&executor) -> FieldResult<f64> as "Delivery cost" {
+    field deprecated "use deliveryCostV2" delivery_cost(&executor) -> FieldResult<f64> as "Delivery cost" {
+        let context = executor.context();
+
+        calculate_cart_delivery_cost(context, &self.inner).map(|money| money.to_f64())
+    }
+
+    field delivery_cost_v2(&executor) -> FieldResult<Money> as "Delivery cost" {
         let context = executor.context();
 
         calculate_cart_delivery_cost(context, &self.inner)
     }
 
-    field total_cost(&executor) -> FieldResult<f64> as "Total cost" {
+    field deprecated "use totalCostV2" total_cost(&executor) -> FieldResult<f64> as "Total cost" {
+        let context = executor.context();
+
+        calculate_cart_total_cost(context, &self.inner).map(|money| money.to_f64())
+    }
+
+    field total_cost_v2(&executor) -> FieldResult<Money> as "Total cost" {
+        let context = executor.context();
+
+        calculate_cart_total_cost(context, &self.inner)
+    }
+
+    field deprecated "use totalCostWithoutDiscountsV2" total_cost_without_discounts(&executor) -> FieldResult<f64> as "Total without cost" {
         let context = executor.context();
 
-        Ok(calculate_cart_price(context, &self.inner)? + calculate_cart_delivery_cost(context, &self.inner)?)
+        calculate_cart_price_without_discounts(context, &self.inner)?
+            .checked_add(calculate_cart_delivery_cost(context, &self.inner)?)
+            .map(|money| money.to_f64())
     }
 
-    field total_cost_without_discounts(&executor) -> FieldResult<f64> as "Total without cost" {
+    field total_cost_without_discounts_v2(&executor) -> FieldResult<Money> as "Total without cost" {
         let context = executor.context();
 
-        Ok(calculate_cart_price_without_discounts(&self.inner) + calculate_cart_delivery_cost(context, &self.inner)?)
+        calculate_cart_price_without_discounts(context, &self.inner)?
+            .checked_add(calculate_cart_delivery_cost(context, &self.inner)?)
     }
 
     field total_count() -> i32 as "Total products count" {
@@ -135,6 +160,21 @@ graphql_object!(Cart: Context as "Cart" |&self| {
             acc + store_products_cost
         })
     }
+
+    field recommendations(&executor, first = None : Option<i32> as "Max number of products") -> FieldResult<Vec<BaseProduct>> as "Products bought by customers who bought the cart products." {
+        let context = executor.context();
+
+        let mut base_product_ids = vec![];
+        for product in self.inner.iter().flat_map(|store| store.products.iter()) {
+            if !base_product_ids.contains(&product.base_product_id) {
+                base_product_ids.push(product.base_product_id);
+            }
+        }
+        let base_products = recommendation_module::base_products_by_ids(context, base_product_ids)?;
+
+        recommendation_module::recommend(context, &base_products, first)
+    }
+
     field fiat(&executor) -> FieldResult<Cart> as "Fiat cart" {
         let context = executor.context();
         get_cart(context, Some(CurrencyType::Fiat))
@@ -159,44 +199,44 @@ graphql_object!(CartProductStore: Context as "CartProductStore" |&self| {
 
 });
 
-pub fn calculate_cart_price(context: &Context, stores: &[CartStore]) -> FieldResult<f64> {
-    let cost = stores.iter().try_fold(0.0, |acc, store| {
+pub fn calculate_cart_price(context: &Context, stores: &[CartStore]) -> FieldResult<Money> {
+    stores.iter().try_fold(Money::zero(cart_currency(context, stores)), |acc, store| {
         let store_products_cost = calculate_products_price(context, &store.products)?;
 
-        Ok(acc + store_products_cost)
-    });
-
-    cost
+        add_converted(context, acc, store_products_cost)
+    })
 }
 
-pub fn calculate_cart_price_without_discounts(stores: &[CartStore]) -> f64 {
-    let cost = stores.iter().fold(0.0, |acc, store| {
-        let store_products_cost = calculate_products_price_without_discounts(&store.products);
-
-        acc + store_products_cost
-    });
+pub fn calculate_cart_price_without_discounts(context: &Context, stores: &[CartStore]) -> FieldResult<Money> {
+    stores.iter().try_fold(Money::zero(cart_currency(context, stores)), |acc, store| {
+        let store_products_cost = calculate_products_price_without_discounts(context, &store.products)?;
 
-    cost
+        add_converted(context, acc, store_products_cost)
+    })
 }
 
-pub fn calculate_cart_coupons_discount(context: &Context, stores: &[CartStore]) -> FieldResult<f64> {
-    let cost = stores.iter().try_fold(0.0, |acc, store| {
+pub fn calculate_cart_coupons_discount(context: &Context, stores: &[CartStore]) -> FieldResult<Money> {
+    stores.iter().try_fold(Money::zero(cart_currency(context, stores)), |acc, store| {
         let store_coupons_discount = calculate_coupons_discount(context, &store.products)?;
 
-        Ok(acc + store_coupons_discount)
-    });
-
-    cost
+        add_converted(context, acc, store_coupons_discount)
+    })
 }
 
-pub fn calculate_cart_delivery_cost(context: &Context, stores: &[CartStore]) -> FieldResult<f64> {
-    let cost = stores.iter().try_fold(0.0, |acc, store| {
+pub fn calculate_cart_delivery_cost(context: &Context, stores: &[CartStore]) -> FieldResult<Money> {
+    stores.iter().try_fold(Money::zero(cart_currency(context, stores)), |acc, store| {
         let store_products_cost = calculate_products_delivery_cost(context, &store.products)?;
 
-        Ok(acc + store_products_cost)
-    });
+        add_converted(context, acc, store_products_cost)
+    })
+}
+
+pub fn calculate_cart_total_cost(context: &Context, stores: &[CartStore]) -> FieldResult<Money> {
+    calculate_cart_price(context, stores)?.checked_add(calculate_cart_delivery_cost(context, stores)?)
+}
 
-    cost
+fn cart_currency(context: &Context, stores: &[CartStore]) -> Currency {
+    customer_currency(context, stores.iter().flat_map(|store| store.products.iter()))
 }
 
 pub fn run_set_delivery_method_in_cart(context: &Context, input: SetDeliveryMethodInCartInputV2) -> FieldResult<Cart> {
@@ -498,8 +538,57 @@ pub fn run_add_in_cart(context: &Context, input: AddInCartInputV2) -> FieldResul
     })
 }
 
+pub fn run_merge_cart(context: &Context, input: MergeCartInput) -> FieldResult<Cart> {
+    let user = context.user.clone().ok_or_else(|| {
+        FieldError::new(
+            "Could not merge cart for unauthorized user.",
+            graphql_value!({ "code": 100, "details": { "No user id in request header." }}),
+        )
+    })?;
+
+    // only the cart of the session making the request can be merged, otherwise anyone could empty a stranger's cart
+    let session_id = context.session_id.ok_or_else(|| {
+        FieldError::new(
+            "Could not merge cart.",
+            graphql_value!({ "code": 100, "details": { "No session id in request header." }}),
+        )
+    })?;
+
+    let products = merge_session_cart(context, session_id, user.user_id)?;
+
+    convert_products_to_cart(context, &products, input.user_country_code)
+}
+
+/// Merges cart of the session into the cart of the user who has just logged in,
+/// the user is taken from the token after its signature is checked.
+/// Errors are logged because they should not prevent the login
+pub fn merge_session_cart_on_login(context: &Context, jwt: &JWT) {
+    let session_id = match context.session_id {
+        Some(session_id) => session_id,
+        None => return,
+    };
+
+    let result = context
+        .verify_jwt(jwt)
+        .and_then(|payload| merge_session_cart(context, session_id, payload.user_id));
+    if let Err(e) = result {
+        warn!("Could not merge session {} cart into user cart: {:?}", session_id.0, e);
+    }
+}
+
+/// Moves products from the session cart to the user cart in one call of orders microservice,
+/// which resolves the conflicts of the products present in both carts and clears the session cart
+pub fn merge_session_cart(context: &Context, session_id: SessionId, user_id: UserId) -> FieldResult<Vec<CartItem>> {
+    context
+        .get_rest_api_client_as(Service::Orders, Some(user_id))
+        .merge(session_id.into(), user_id.into(), None)
+        .sync()
+        .map_err(into_graphql)
+        .map(|products| products.into_iter().collect())
+}
+
 pub fn convert_products_to_cart(context: &Context, products: &[CartItem], user_country_code: Option<String>) -> FieldResult<Cart> {
-    let url = format!("{}/{}/cart", context.config.service_url(Service::Stores), Model::Store.to_url());
+    let url = context.config.url(Service::Stores).model(Model::Store).path("cart").build();
     let body = serde_json::to_string(&products)?;
 
     context
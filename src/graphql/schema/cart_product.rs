@@ -11,7 +11,7 @@ use stq_routes::service::Service;
 use stq_static_resources::currency_type::CurrencyType;
 use stq_static_resources::Currency;
 use stq_static_resources::Translation;
-use stq_types::{BaseProductId, CartItem, DeliveryMethodId, ExchangeRate, ProductId};
+use stq_types::{BaseProductId, CartItem, DeliveryMethodId, ExchangeRate, ProductId, Quantity};
 
 use super::*;
 use graphql::context::Context;
@@ -45,16 +45,35 @@ graphql_object!(CartProduct: Context as "CartProduct" |&self| {
         &self.customer_price.0
     }
 
-    field subtotal(&executor) -> FieldResult<f64> as "Subtotal with discounts" {
+    field currency() -> &Currency as "Currency of the price" {
+        &self.customer_currency
+    }
+
+    field deprecated "use subtotalV2" subtotal(&executor) -> FieldResult<f64> as "Subtotal with discounts" {
+        let context = executor.context();
+        calculate_product_price(context, &self).map(|money| money.to_f64())
+    }
+
+    field subtotal_v2(&executor) -> FieldResult<Money> as "Subtotal with discounts" {
         let context = executor.context();
         calculate_product_price(context, &self)
     }
 
-    field subtotal_without_discounts() -> f64 as "Subtotal without discounts" {
-        self.customer_price.0 * f64::from(self.quantity.0)
+    field deprecated "use subtotalWithoutDiscountsV2" subtotal_without_discounts() -> f64 as "Subtotal without discounts" {
+        calculate_product_price_without_discounts(&self).to_f64()
+    }
+
+    field subtotal_without_discounts_v2() -> Money as "Subtotal without discounts" {
+        calculate_product_price_without_discounts(&self)
+    }
+
+    field deprecated "use deliveryCostV2" delivery_cost(&executor) -> FieldResult<f64> as "Delivery cost" {
+        let context = executor.context();
+
+        calculate_delivery_cost(context, &self).map(|money| money.to_f64())
     }
 
-    field delivery_cost(&executor) -> FieldResult<f64> as "Delivery cost" {
+    field delivery_cost_v2(&executor) -> FieldResult<Money> as "Delivery cost" {
         let context = executor.context();
 
         calculate_delivery_cost(context, &self)
@@ -76,11 +95,7 @@ graphql_object!(CartProduct: Context as "CartProduct" |&self| {
         let context = executor.context();
         match self.company_package_id {
             Some(company_package_id) => {
-                let url = format!("{}/{}/{}",
-                    context.config.service_url(Service::Delivery),
-                    Model::CompanyPackage.to_url(),
-                    company_package_id,
-                );
+                let url = context.config.url(Service::Delivery).model(Model::CompanyPackage).segment(company_package_id).build();
 
                 context.request::<Option<CompaniesPackages>>(Method::Get, url, None).wait()
             },
@@ -91,7 +106,6 @@ graphql_object!(CartProduct: Context as "CartProduct" |&self| {
     field select_package(&executor) -> FieldResult<Option<AvailablePackageForUser>> as "Select package" {
         let context = executor.context();
 
-
         Ok(match self.delivery_method_id {
             Some(delivery_method_id) => match self.user_country_code.clone() {
                 Some(user_country_code) => Some(get_select_package(context, self.base_product_id, user_country_code, delivery_method_id)?),
@@ -134,7 +148,13 @@ graphql_object!(CartProduct: Context as "CartProduct" |&self| {
         }
     }
 
-    field coupon_discount(&executor) -> FieldResult<f64> as "Coupon discount" {
+    field deprecated "use couponDiscountV2" coupon_discount(&executor) -> FieldResult<f64> as "Coupon discount" {
+        let context = executor.context();
+
+        calculate_coupon_discount(context, &self).map(|money| money.to_f64())
+    }
+
+    field coupon_discount_v2(&executor) -> FieldResult<Money> as "Coupon discount" {
         let context = executor.context();
 
         calculate_coupon_discount(context, &self)
@@ -146,13 +166,13 @@ graphql_object!(CartProduct: Context as "CartProduct" |&self| {
         let context = executor.context();
         let visibility = visibility.unwrap_or_default();
 
-        let url = format!(
-            "{}/{}/{}?visibility={}",
-            &context.config.service_url(Service::Stores),
-            Model::BaseProduct.to_url(),
-            self.base_product_id.to_string(),
-            visibility,
-        );
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::BaseProduct)
+            .segment(self.base_product_id)
+            .query("visibility", &visibility)
+            .build();
 
         context.request::<Option<BaseProduct>>(Method::Get, url, None)
             .wait()
@@ -164,10 +184,7 @@ graphql_object!(CartProduct: Context as "CartProduct" |&self| {
 
     field attributes(&executor) -> FieldResult<Option<Vec<ProdAttrValue>>> as "Variants" {
         let context = executor.context();
-        let url = format!("{}/{}/{}/attributes",
-            context.config.service_url(Service::Stores),
-            Model::Product.to_url(),
-            self.id);
+        let url = context.config.url(Service::Stores).model(Model::Product).segment(self.id).path("attributes").build();
 
         context.request::<Vec<ProdAttrValue>>(Method::Get, url, None)
             .wait()
@@ -176,70 +193,87 @@ graphql_object!(CartProduct: Context as "CartProduct" |&self| {
     }
 });
 
-pub fn calculate_product_price(context: &Context, cart_product: &CartProduct) -> FieldResult<f64> {
+pub fn calculate_product_price(context: &Context, cart_product: &CartProduct) -> FieldResult<Money> {
+    let price = Money::from_f64(cart_product.customer_price.0, cart_product.customer_currency);
+
     if cart_product.quantity.0 <= 0 {
-        return Ok(0f64);
+        return Ok(Money::zero(cart_product.customer_currency));
     }
 
     if let Some(discount) = cart_product.discount.filter(|discount| *discount > ZERO_DISCOUNT) {
-        let calc_price = (cart_product.customer_price.0 * (f64::from(cart_product.quantity.0))) * (1.0f64 - discount);
+        let calc_price = price.times(cart_product.quantity).discounted(discount);
 
-        return Ok(calc_price);
+        return Ok(calc_price.round());
     } else {
         if cart_product.coupon_id.is_some() {
             // set discount only 1 product
-            let product_price_with_coupon_discount = cart_product.customer_price.0 - calculate_coupon_discount(context, cart_product)?;
-            let calc_price =
-                product_price_with_coupon_discount + (cart_product.customer_price.0 * (f64::from(cart_product.quantity.0 - 1)));
+            let product_price_with_coupon_discount = price.clone().checked_sub(calculate_coupon_discount(context, cart_product)?)?;
+            let calc_price = product_price_with_coupon_discount.checked_add(price.times(Quantity(cart_product.quantity.0 - 1)))?;
 
-            return Ok(calc_price);
+            return Ok(calc_price.round());
         }
     }
 
-    Ok(cart_product.customer_price.0 * f64::from(cart_product.quantity.0))
+    Ok(price.times(cart_product.quantity).round())
 }
 
-pub fn calculate_product_price_without_discounts(cart_product: &CartProduct) -> f64 {
+pub fn calculate_product_price_without_discounts(cart_product: &CartProduct) -> Money {
     if cart_product.quantity.0 <= 0 {
-        return 0f64;
+        return Money::zero(cart_product.customer_currency);
     }
 
-    cart_product.customer_price.0 * f64::from(cart_product.quantity.0)
+    Money::from_f64(cart_product.customer_price.0, cart_product.customer_currency)
+        .times(cart_product.quantity)
+        .round()
 }
 
-pub fn calculate_coupon_discount(context: &Context, cart_product: &CartProduct) -> FieldResult<f64> {
+pub fn calculate_coupon_discount(context: &Context, cart_product: &CartProduct) -> FieldResult<Money> {
     if let Some(coupon_id) = cart_product.coupon_id {
         if let Some(coupon) = try_get_coupon(context, coupon_id)? {
             // set discount only 1 product
-            let discount = (cart_product.customer_price.0 / 100f64) * f64::from(coupon.percent);
+            let discount = Money::from_f64(cart_product.customer_price.0, cart_product.customer_currency).percent(coupon.percent);
 
-            return Ok(discount);
+            return Ok(discount.round());
         }
     }
 
-    Ok(0.0f64)
+    Ok(Money::zero(cart_product.customer_currency))
 }
 
 pub fn get_cart_product_base_product(context: &Context, product: &CartProduct) -> FieldResult<Option<BaseProduct>> {
-    let url = format!(
-        "{}/{}/{}?visibility={}",
-        &context.config.service_url(Service::Stores),
-        Model::BaseProduct.to_url(),
-        product.base_product_id.to_string(),
-        Visibility::Published,
-    );
+    let url = context
+        .config
+        .url(Service::Stores)
+        .model(Model::BaseProduct)
+        .segment(product.base_product_id)
+        .query("visibility", Visibility::Published)
+        .build();
 
     context.request::<Option<BaseProduct>>(Method::Get, url, None).wait()
 }
 
 pub fn get_currency_exchange_rates(context: &Context, currency: Currency) -> FieldResult<ExchangeRates> {
-    Ok(context
-        .get_stores_microservice()
-        .get_currency_exchange_info()?
-        .data
+    Ok(context.exchange_rates()?.get(&currency).cloned().unwrap_or_default())
+}
+
+/// Converts money to `currency` with the exchange rates of its own currency
+pub fn convert_money(context: &Context, money: Money, currency: Currency) -> FieldResult<Money> {
+    if money.currency == currency {
+        return Ok(money);
+    }
+    if money.is_zero() {
+        return Ok(Money::zero(currency));
+    }
+
+    let exchange_rate = get_currency_exchange_rates(context, money.currency)?
         .get(&currency)
         .cloned()
-        .unwrap_or_default())
+        .ok_or_else(|| {
+            let details = format!("No exchange rate from {} to {}.", money.currency.code(), currency.code());
+            FieldError::new("Could not convert money.", graphql_value!({ "code": 100, "details": { details }}))
+        })?;
+
+    money.exchange(exchange_rate, currency).map(|money| money.round())
 }
 
 pub fn get_exchange_rate(context: &Context, product: &CartProduct) -> FieldResult<ExchangeRate> {
@@ -252,7 +286,7 @@ pub fn get_exchange_rate(context: &Context, product: &CartProduct) -> FieldResul
     Ok(currency_map.get(&user_currency).cloned().unwrap_or(ExchangeRate(1.0)))
 }
 
-pub fn calculate_delivery_cost(context: &Context, product: &CartProduct) -> FieldResult<f64> {
+pub fn calculate_delivery_cost(context: &Context, product: &CartProduct) -> FieldResult<Money> {
     calculate_delivery_cost_with_exchange_rate(context, product, get_exchange_rate(context, product)?)
 }
 
@@ -260,15 +294,19 @@ pub fn calculate_delivery_cost_with_exchange_rate(
     context: &Context,
     product: &CartProduct,
     exchange_rate: ExchangeRate,
-) -> FieldResult<f64> {
+) -> FieldResult<Money> {
     match product.delivery_method_id {
-        None => Ok(0.0f64),
+        None => Ok(Money::zero(product.customer_currency)),
         Some(delivery_method_id) => {
             let package = match product.user_country_code.clone() {
                 None => get_select_package_v1(context, delivery_method_id)?,
                 Some(user_country_code) => get_select_package(context, product.base_product_id, user_country_code, delivery_method_id)?,
             };
-            Ok(package.price.0 / exchange_rate.0 * f64::from(product.quantity.0))
+            let delivery_cost = Money::from_f64(package.price.0, package.currency)
+                .exchange(exchange_rate, product.customer_currency)?
+                .times(product.quantity);
+
+            Ok(delivery_cost.round())
         }
     }
 }
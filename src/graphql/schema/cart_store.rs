@@ -6,13 +6,15 @@ use juniper::ID as GraphqlID;
 
 use stq_routes::model::Model;
 use stq_routes::service::Service;
-use stq_static_resources::Translation;
+use stq_static_resources::{Currency, CurrencyType, Translation};
 
 use super::*;
+use config::FIAT_SELLER_CURRENCY;
 use graphql::context::Context;
 use graphql::models::*;
 use graphql::schema::cart_product::{
-    calculate_coupon_discount, calculate_delivery_cost_with_exchange_rate, calculate_product_price, get_exchange_rate,
+    calculate_coupon_discount, calculate_delivery_cost_with_exchange_rate, calculate_product_price,
+    calculate_product_price_without_discounts, convert_money, get_exchange_rate,
 };
 use graphql::schema::coupon::get_coupon;
 
@@ -52,14 +54,28 @@ graphql_object!(CartStore: Context as "CartStore" |&self| {
         &self.cover
     }
 
-    field products_cost(&executor) -> FieldResult<f64> as "Products cost" {
+    field deprecated "use productsCostV2" products_cost(&executor) -> FieldResult<f64> as "Products cost" {
+        let context = executor.context();
+
+        calculate_products_price(context, &self.products).map(|money| money.to_f64())
+    }
+
+    field products_cost_v2(&executor) -> FieldResult<Money> as "Products cost" {
         let context = executor.context();
 
         calculate_products_price(context, &self.products)
     }
 
-    field products_cost_without_discounts() -> f64 as "Products without cost" {
-        calculate_products_price_without_discounts(&self.products)
+    field deprecated "use productsCostWithoutDiscountsV2" products_cost_without_discounts(&executor) -> FieldResult<f64> as "Products without cost" {
+        let context = executor.context();
+
+        calculate_products_price_without_discounts(context, &self.products).map(|money| money.to_f64())
+    }
+
+    field products_cost_without_discounts_v2(&executor) -> FieldResult<Money> as "Products without cost" {
+        let context = executor.context();
+
+        calculate_products_price_without_discounts(context, &self.products)
     }
 
     field coupons(&executor) -> FieldResult<Vec<Coupon>> as "Coupons added user" {
@@ -75,28 +91,55 @@ graphql_object!(CartStore: Context as "CartStore" |&self| {
         })
     }
 
-    field coupons_discount(&executor) -> FieldResult<f64> as "Coupons discount" {
+    field deprecated "use couponsDiscountV2" coupons_discount(&executor) -> FieldResult<f64> as "Coupons discount" {
+        let context = executor.context();
+
+        calculate_coupons_discount(context, &self.products).map(|money| money.to_f64())
+    }
+
+    field coupons_discount_v2(&executor) -> FieldResult<Money> as "Coupons discount" {
         let context = executor.context();
 
         calculate_coupons_discount(context, &self.products)
     }
 
-    field delivery_cost(&executor) -> FieldResult<f64> as "Delivery cost" {
+    field deprecated "use deliveryCostV2" delivery_cost(&executor) -> FieldResult<f64> as "Delivery cost" {
+        let context = executor.context();
+
+        calculate_products_delivery_cost(context, &self.products).map(|money| money.to_f64())
+    }
+
+    field delivery_cost_v2(&executor) -> FieldResult<Money> as "Delivery cost" {
         let context = executor.context();
 
         calculate_products_delivery_cost(context, &self.products)
     }
 
-    field total_cost(&executor) -> FieldResult<f64> as "Total cost" {
+    field deprecated "use totalCostV2" total_cost(&executor) -> FieldResult<f64> as "Total cost" {
+        let context = executor.context();
+
+        calculate_products_total_cost(context, &self.products).map(|money| money.to_f64())
+    }
+
+    field total_cost_v2(&executor) -> FieldResult<Money> as "Total cost" {
         let context = executor.context();
 
-        Ok(calculate_products_price(context, &self.products)? + calculate_products_delivery_cost(context, &self.products)?)
+        calculate_products_total_cost(context, &self.products)
     }
 
-    field total_cost_without_discounts(&executor) -> FieldResult<f64> as "Total without cost" {
+    field deprecated "use totalCostWithoutDiscountsV2" total_cost_without_discounts(&executor) -> FieldResult<f64> as "Total without cost" {
         let context = executor.context();
 
-        Ok(calculate_products_price_without_discounts(&self.products) + calculate_products_delivery_cost(context, &self.products)?)
+        calculate_products_price_without_discounts(context, &self.products)?
+            .checked_add(calculate_products_delivery_cost(context, &self.products)?)
+            .map(|money| money.to_f64())
+    }
+
+    field total_cost_without_discounts_v2(&executor) -> FieldResult<Money> as "Total without cost" {
+        let context = executor.context();
+
+        calculate_products_price_without_discounts(context, &self.products)?
+            .checked_add(calculate_products_delivery_cost(context, &self.products)?)
     }
 
     field total_count() -> i32 as "Total products count" {
@@ -139,43 +182,66 @@ graphql_object!(Edge<CartStore>: Context as "CartStoresEdge" |&self| {
     }
 });
 
-pub fn calculate_products_price(context: &Context, products: &[CartProduct]) -> FieldResult<f64> {
-    products.iter().try_fold(0.0, |acc, x| {
-        if x.selected {
-            Ok(acc + calculate_product_price(context, &x)?)
-        } else {
-            Ok(acc)
-        }
-    })
+pub fn calculate_products_price(context: &Context, products: &[CartProduct]) -> FieldResult<Money> {
+    products.iter().filter(|x| x.selected).try_fold(
+        Money::zero(customer_currency(context, products)),
+        |acc, x| add_converted(context, acc, calculate_product_price(context, &x)?),
+    )
 }
 
-pub fn calculate_products_price_without_discounts(products: &[CartProduct]) -> f64 {
-    products.iter().fold(0.0, |acc, x| {
-        if x.selected {
-            acc + x.customer_price.0 * f64::from(x.quantity.0)
-        } else {
-            acc
-        }
-    })
+pub fn calculate_products_price_without_discounts(context: &Context, products: &[CartProduct]) -> FieldResult<Money> {
+    products.iter().filter(|x| x.selected).try_fold(
+        Money::zero(customer_currency(context, products)),
+        |acc, x| add_converted(context, acc, calculate_product_price_without_discounts(&x)),
+    )
 }
 
-pub fn calculate_coupons_discount(context: &Context, products: &[CartProduct]) -> FieldResult<f64> {
-    products.iter().try_fold(0.0, |acc, x| {
-        if x.selected {
-            Ok(acc + calculate_coupon_discount(context, &x)?)
-        } else {
-            Ok(acc)
-        }
+pub fn calculate_coupons_discount(context: &Context, products: &[CartProduct]) -> FieldResult<Money> {
+    products.iter().filter(|x| x.selected).try_fold(
+        Money::zero(customer_currency(context, products)),
+        |acc, x| add_converted(context, acc, calculate_coupon_discount(context, &x)?),
+    )
+}
+
+pub fn calculate_products_delivery_cost(context: &Context, products: &[CartProduct]) -> FieldResult<Money> {
+    products.iter().filter(|x| x.selected).try_fold(Money::zero(customer_currency(context, products)), |acc, x| {
+        let exchange_rate = get_exchange_rate(context, x)?;
+        add_converted(context, acc, calculate_delivery_cost_with_exchange_rate(context, &x, exchange_rate)?)
     })
 }
 
-pub fn calculate_products_delivery_cost(context: &Context, products: &[CartProduct]) -> FieldResult<f64> {
-    products.iter().try_fold(0.0, |acc, x| {
-        if x.selected {
-            let exchange_rate = get_exchange_rate(context, x)?;
-            Ok(acc + calculate_delivery_cost_with_exchange_rate(context, &x, exchange_rate)?)
-        } else {
-            Ok(acc)
+pub fn calculate_products_total_cost(context: &Context, products: &[CartProduct]) -> FieldResult<Money> {
+    calculate_products_price(context, products)?.checked_add(calculate_products_delivery_cost(context, products)?)
+}
+
+/// Adds the amount converted to the currency of the sum
+pub fn add_converted(context: &Context, sum: Money, amount: Money) -> FieldResult<Money> {
+    let currency = sum.currency;
+    sum.checked_add(convert_money(context, amount, currency)?)
+}
+
+/// Currency of the sums of the products: the currency of the selected products if they have the same one,
+/// the fiat currency when fiat and crypto products are selected together.
+/// Without selected products it is the currency the products are shown in, the requested fiat currency
+/// of the customer or the currency of the sellers for an empty cart
+pub fn customer_currency<'a, I>(context: &Context, products: I) -> Currency
+where
+    I: IntoIterator<Item = &'a CartProduct>,
+{
+    let products = products.into_iter().collect::<Vec<_>>();
+    let mut selected: Vec<Currency> = vec![];
+    for product in products.iter().filter(|product| product.selected) {
+        if !selected.contains(&product.customer_currency) {
+            selected.push(product.customer_currency);
         }
-    })
+    }
+
+    selected
+        .iter()
+        .find(|currency| currency.currency_type() == CurrencyType::Fiat)
+        .or_else(|| selected.first())
+        .cloned()
+        .or_else(|| products.first().map(|product| product.customer_currency))
+        .or(context.fiat_currency)
+        .unwrap_or(FIAT_SELLER_CURRENCY)
 }
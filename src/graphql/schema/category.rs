@@ -43,10 +43,7 @@ graphql_object!(Category: Context as "Category" |&self| {
             Some(parent_id) => {
                 // TODO: use `try_get_category`
                 let context = executor.context();
-                let url = format!("{}/{}/{}",
-                context.config.service_url(Service::Stores),
-                Model::Category.to_url(),
-                parent_id.0);
+                let url = context.config.url(Service::Stores).model(Model::Category).segment(parent_id.0).build();
 
                 context.request::<Option<Category>>(Method::Get, url, None)
                     .wait()
@@ -160,11 +157,7 @@ graphql_object!(CategoryWithProducts: Context as "CategoryWithProducts" |&self|
 });
 
 pub fn run_replace_category(context: &Context, payload: CategoryReplaceInput) -> FieldResult<Vec<BaseProduct>> {
-    let url = format!(
-        "{}/{}/replace_category",
-        context.config.service_url(Service::Stores),
-        Model::BaseProduct.to_url(),
-    );
+    let url = context.config.url(Service::Stores).model(Model::BaseProduct).path("replace_category").build();
 
     let body: String = serde_json::to_string(&payload)?.to_string();
 
@@ -172,11 +165,7 @@ pub fn run_replace_category(context: &Context, payload: CategoryReplaceInput) ->
 }
 
 pub fn categories_with_products(context: &Context) -> FieldResult<Option<CategoryWithProducts>> {
-    let url = format!(
-        "{}/{}/with_products",
-        context.config.service_url(Service::Stores),
-        Model::Category.to_url()
-    );
+    let url = context.config.url(Service::Stores).model(Model::Category).path("with_products").build();
 
     context
         .request::<Category>(Method::Get, url, None)
@@ -186,12 +175,7 @@ pub fn categories_with_products(context: &Context) -> FieldResult<Option<Categor
 }
 
 pub fn try_get_category(context: &Context, category_id: CategoryId) -> FieldResult<Option<Category>> {
-    let url = format!(
-        "{}/{}/{}",
-        context.config.service_url(Service::Stores),
-        Model::Category.to_url(),
-        category_id
-    );
+    let url = context.config.url(Service::Stores).model(Model::Category).segment(category_id).build();
 
     context.request::<Option<Category>>(Method::Get, url, None).wait()
 }
@@ -0,0 +1,295 @@
+//! File containing checkout quote functions of graphql schema
+use std::collections::HashSet;
+
+use chrono::prelude::*;
+use chrono::Duration;
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{decode, encode, Algorithm, Header, Validation};
+use juniper::{FieldError, FieldResult};
+
+use stq_api::orders::CartClient;
+use stq_api::types::ApiFutureExt;
+use stq_routes::service::Service;
+use stq_static_resources::{Currency, CurrencyType};
+use stq_types::{CartItem, DeliveryMethodId, ProductId, Quantity, ShippingId, UserId};
+
+use errors::into_graphql;
+use graphql::context::Context;
+use graphql::models::*;
+use graphql::schema::buy_now;
+use graphql::schema::cart;
+use graphql::schema::order;
+use graphql::schema::product as product_module;
+
+pub fn run_checkout_quote(context: &Context, mut input: CheckoutQuoteInput) -> FieldResult<CheckoutQuote> {
+    //todo remove as soon as multi fiat currency becomes available
+    if input.currency.currency_type() == CurrencyType::Fiat {
+        input.currency = crate::config::FIAT_SELLER_CURRENCY;
+    }
+    let user = context.user.clone().ok_or_else(|| {
+        FieldError::new(
+            "Could not get checkout quote for unauthorized user.",
+            graphql_value!({ "code": 100, "details": { "No user id in request header." }}),
+        )
+    })?;
+
+    let (kind, products, costs) = match input.buy_now {
+        Some(buy_now_input) => {
+            let (products, costs) = quote_buy_now(context, buy_now_input, &input.user_country_code)?;
+            (CheckoutQuoteKind::BuyNow, products, costs)
+        }
+        None => {
+            let (products, costs) = quote_cart(context, user.user_id, input.currency, &input.user_country_code)?;
+            (CheckoutQuoteKind::Cart, products, costs)
+        }
+    };
+    let QuoteCosts {
+        products_cost,
+        coupons_discount,
+        delivery_cost,
+        total_cost,
+    } = costs;
+
+    let expires_at = Utc::now() + Duration::seconds(context.config.checkout_quote.ttl_secs);
+    let claims = CheckoutQuoteClaims {
+        exp: expires_at.timestamp(),
+        kind,
+        user_id: user.user_id,
+        currency: input.currency,
+        user_country_code: input.user_country_code,
+        products,
+        exchange_rates: context.exchange_rates()?,
+        total_cost: total_cost.to_string(),
+    };
+
+    let token = encode(&Header::new(Algorithm::HS256), &claims, quote_secret(context)?).map_err(|e| {
+        FieldError::new(
+            "Could not create checkout quote.",
+            graphql_value!({ "code": 100, "details": { format!("Quote signing failed: {}", e) }}),
+        )
+    })?;
+
+    Ok(CheckoutQuote {
+        token,
+        expires_at: expires_at.to_rfc3339(),
+        currency: total_cost.currency,
+        products_cost,
+        coupons_discount,
+        delivery_cost,
+        total_cost,
+    })
+}
+
+/// Verifies signature and expiration of the quote and that it was issued for this checkout
+pub fn decode_quote(
+    context: &Context,
+    token: &str,
+    kind: CheckoutQuoteKind,
+    user_id: UserId,
+    currency: Currency,
+) -> FieldResult<CheckoutQuoteClaims> {
+    let validation = Validation {
+        leeway: 0,
+        ..Validation::new(Algorithm::HS256)
+    };
+
+    let claims = decode::<CheckoutQuoteClaims>(token, quote_secret(context)?, &validation)
+        .map_err(|e| match *e.kind() {
+            ErrorKind::ExpiredSignature => stale_quote("Checkout quote has expired. Please request a new quote."),
+            _ => invalid_quote("Checkout quote token is not valid."),
+        })?
+        .claims;
+
+    if claims.kind != kind || claims.user_id != user_id {
+        return Err(invalid_quote("Checkout quote was issued for another checkout."));
+    }
+
+    if claims.currency != currency {
+        return Err(invalid_quote("Checkout quote was issued for another currency."));
+    }
+
+    Ok(claims)
+}
+
+/// Checks that the cart was not changed since the quote was issued,
+/// its orders are created with the quoted prices, exchange rates and total cost
+pub fn validate_cart_quote(quote: &CheckoutQuoteClaims, current_cart: &HashSet<CartItem>, user_country_code: &str) -> FieldResult<()> {
+    if quote.user_country_code != user_country_code {
+        return Err(stale_quote("Checkout input differs from the quoted one. Please request a new quote."));
+    }
+
+    let is_same_cart = quote.products.len() == current_cart.len() && current_cart.iter().all(|cart_item| {
+        quote
+            .products
+            .iter()
+            .find(|quoted| quoted.product_id == cart_item.product_id)
+            .map(|quoted| {
+                quoted.quantity.0 == cart_item.quantity.0
+                    && quoted.coupon.as_ref().map(|coupon| coupon.id) == cart_item.coupon_id
+                    && quoted.package.as_ref().map(|package| package.shipping_id) == selected_shipping_id(cart_item)
+            })
+            .unwrap_or(false)
+    });
+
+    if !is_same_cart {
+        return Err(stale_quote("Cart has changed since the quote was issued. Please request a new quote."));
+    }
+
+    Ok(())
+}
+
+pub fn frozen_cart_checkout(quote: CheckoutQuoteClaims) -> CartCheckout {
+    let mut checkout = CartCheckout {
+        prices: Default::default(),
+        coupons: Default::default(),
+        packages: Default::default(),
+    };
+
+    for quoted in quote.products {
+        checkout.prices.insert(quoted.product_id, quoted.seller_price);
+        if let Some(coupon) = quoted.coupon {
+            checkout.coupons.insert(coupon.id, coupon);
+        }
+        if let Some(package) = quoted.package {
+            checkout.packages.insert(quoted.product_id, package);
+        }
+    }
+
+    checkout
+}
+
+/// Checks that buy now input matches the quote and returns quoted product,
+/// the order is created with the quoted price, exchange rates and total cost
+pub fn validate_buy_now_quote(quote: &CheckoutQuoteClaims, input: &BuyNowInputV2) -> FieldResult<QuotedProduct> {
+    let quoted = quote
+        .products
+        .first()
+        .cloned()
+        .filter(|quoted| {
+            quoted.product_id == ProductId(input.product_id)
+                && quoted.quantity.0 == input.quantity
+                && quoted.package.as_ref().map(|package| package.shipping_id) == Some(ShippingId(input.shipping_id))
+        })
+        .ok_or_else(|| stale_quote("Buy now input differs from the quoted one. Please request a new quote."))?;
+
+    if quote.user_country_code != input.user_country_code {
+        return Err(stale_quote("Buy now input differs from the quoted one. Please request a new quote."));
+    }
+
+    Ok(quoted)
+}
+
+struct QuoteCosts {
+    products_cost: Money,
+    coupons_discount: Money,
+    delivery_cost: Money,
+    total_cost: Money,
+}
+
+fn quote_cart(
+    context: &Context,
+    user_id: UserId,
+    currency: Currency,
+    user_country_code: &str,
+) -> FieldResult<(Vec<QuotedProduct>, QuoteCosts)> {
+    let rpc_client = context.get_rest_api_client(Service::Orders);
+    let current_cart = rpc_client
+        .get_cart(user_id.into(), Some(currency.currency_type()))
+        .sync()
+        .map_err(into_graphql)?;
+
+    let checkout = order::prepare_cart_checkout(context, &current_cart, user_country_code.to_string())?;
+
+    let products = current_cart
+        .iter()
+        .filter_map(|cart_item| {
+            checkout.prices.get(&cart_item.product_id).map(|seller_price| QuotedProduct {
+                product_id: cart_item.product_id,
+                store_id: cart_item.store_id,
+                quantity: cart_item.quantity,
+                seller_price: seller_price.clone(),
+                coupon: cart_item.coupon_id.and_then(|coupon_id| checkout.coupons.get(&coupon_id).cloned()),
+                package: checkout.packages.get(&cart_item.product_id).cloned(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if products.is_empty() {
+        return Err(FieldError::new(
+            "Could not get checkout quote for empty cart.",
+            graphql_value!({ "code": 100, "details": { "There is no products, selected in cart." }}),
+        ));
+    }
+
+    let cart = cart::get_cart(context, Some(currency.currency_type()))?;
+    let costs = QuoteCosts {
+        products_cost: cart::calculate_cart_price(context, &cart.inner)?,
+        coupons_discount: cart::calculate_cart_coupons_discount(context, &cart.inner)?,
+        delivery_cost: cart::calculate_cart_delivery_cost(context, &cart.inner)?,
+        total_cost: cart::calculate_cart_total_cost(context, &cart.inner)?,
+    };
+
+    Ok((products, costs))
+}
+
+fn quote_buy_now(
+    context: &Context,
+    input: CheckoutQuoteBuyNowInput,
+    user_country_code: &str,
+) -> FieldResult<(Vec<QuotedProduct>, QuoteCosts)> {
+    let product = product_module::get_product(context, ProductId(input.product_id))?;
+    let quantity = Quantity(input.quantity);
+
+    let quoted = buy_now::prepare_buy_now_checkout(
+        context,
+        &product,
+        quantity,
+        user_country_code,
+        input.coupon_code,
+        ShippingId(input.shipping_id),
+    )?;
+
+    let checkout = BuyNowCheckout {
+        user_country_code: Some(user_country_code.to_string()),
+        product,
+        quantity,
+        coupon: quoted.coupon.clone(),
+        package: quoted.package.clone(),
+    };
+    let costs = QuoteCosts {
+        products_cost: buy_now::calculate_cost(&checkout)?,
+        coupons_discount: buy_now::calculate_coupon_discount(&checkout),
+        delivery_cost: buy_now::calculate_delivery_cost(context, &checkout.package, checkout.quantity, &checkout.product)?,
+        total_cost: buy_now::calculate_total_cost(context, &checkout)?,
+    };
+
+    Ok((vec![quoted], costs))
+}
+
+fn selected_shipping_id(cart_item: &CartItem) -> Option<ShippingId> {
+    match cart_item.delivery_method_id {
+        Some(DeliveryMethodId::ShippingPackage { ref id }) => Some(id.clone()),
+        _ => None,
+    }
+}
+
+/// Quotes can not be signed without the secret, an empty one would let anybody forge them
+fn quote_secret(context: &Context) -> FieldResult<&[u8]> {
+    let secret = context.config.checkout_quote.secret.as_bytes();
+    if secret.is_empty() {
+        return Err(FieldError::new(
+            "Checkout quotes are not available.",
+            graphql_value!({ "code": 100, "details": { "Checkout quote secret is not configured." }}),
+        ));
+    }
+
+    Ok(secret)
+}
+
+fn stale_quote(details: &str) -> FieldError {
+    FieldError::new("Checkout quote is stale.", graphql_value!({ "code": 100, "details": { details }}))
+}
+
+fn invalid_quote(details: &str) -> FieldError {
+    FieldError::new("Checkout quote is not valid.", graphql_value!({ "code": 100, "details": { details }}))
+}
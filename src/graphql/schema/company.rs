@@ -49,16 +49,14 @@ graphql_object!(Company: Context as "Company" |&self| {
         &self.deliveries_from
     }
 
+    field tracking_url_template() -> &Option<String> as "Tracking page url with {tracking_number} placeholder" {
+        &self.tracking_url_template
+    }
+
     field packages(&executor) -> FieldResult<Vec<Packages>> as "Fetches packages by id." {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}/{}/{}",
-            &context.config.service_url(Service::Delivery),
-            Model::Company.to_url(),
-            self.id,
-            Model::Package.to_url(),
-        );
+        let url = context.config.url(Service::Delivery).model(Model::Company).segment(self.id).model(Model::Package).build();
 
         context.request::<Vec<Packages>>(Method::Get, url, None)
             .wait()
@@ -30,12 +30,7 @@ graphql_object!(CompaniesPackages: Context as "CompaniesPackages" |&self| {
     field company(&executor) -> FieldResult<Option<Company>> as "Fetches company." {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}/{}",
-            &context.config.service_url(Service::Delivery),
-            Model::Company.to_url(),
-            self.company_id
-        );
+        let url = context.config.url(Service::Delivery).model(Model::Company).segment(self.company_id).build();
 
         context.request::<Option<Company>>(Method::Get, url, None)
             .wait()
@@ -48,12 +43,7 @@ graphql_object!(CompaniesPackages: Context as "CompaniesPackages" |&self| {
     field package(&executor) -> FieldResult<Option<Packages>> as "Fetches package." {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}/{}",
-            &context.config.service_url(Service::Delivery),
-            Model::Package.to_url(),
-            self.package_id
-        );
+        let url = context.config.url(Service::Delivery).model(Model::Package).segment(self.package_id).build();
 
         context.request::<Option<Packages>>(Method::Get, url, None)
             .wait()
@@ -65,13 +55,14 @@ graphql_object!(CompaniesPackages: Context as "CompaniesPackages" |&self| {
     ) -> FieldResult<Vec<ShippingRates>> as "Fetches shipping rates for the specified country" {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}/{}/rates?from={}",
-            &context.config.service_url(Service::Delivery),
-            Model::CompanyPackage.to_url(),
-            self.id,
-            country_from,
-        );
+        let url = context
+            .config
+            .url(Service::Delivery)
+            .model(Model::CompanyPackage)
+            .segment(self.id)
+            .path("rates")
+            .query("from", &country_from)
+            .build();
 
         context.request::<Vec<ShippingRates>>(Method::Get, url, None).wait()
     }
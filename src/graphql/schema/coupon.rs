@@ -78,10 +78,7 @@ graphql_object!(Coupon: Context as "Coupon" |&self| {
 
     field base_products(&executor) -> FieldResult<Vec<BaseProduct>> as "Base products coupon can be applied to" {
         let context = executor.context();
-        let url = format!("{}/{}/{}/base_products",
-            context.config.service_url(Service::Stores),
-            Model::Coupon.to_url(),
-            self.id);
+        let url = context.config.url(Service::Stores).model(Model::Coupon).segment(self.id).path("base_products").build();
         context.request::<Vec<BaseProduct>>(Method::Get, url, None).wait()
     }
 
@@ -89,11 +86,7 @@ graphql_object!(Coupon: Context as "Coupon" |&self| {
 
 pub fn validate_coupon_by_code(context: &Context, coupon_code: CouponCode, store_id: StoreId) -> FieldResult<()> {
     // Validate coupon
-    let url = format!(
-        "{}/{}/validate/code",
-        context.config.service_url(Service::Stores),
-        Model::Coupon.to_url()
-    );
+    let url = context.config.url(Service::Stores).model(Model::Coupon).path("validate/code").build();
 
     let search_code = CouponsSearchCodePayload {
         code: coupon_code,
@@ -117,12 +110,7 @@ pub fn validate_coupon_by_code(context: &Context, coupon_code: CouponCode, store
 
 pub fn validate_coupon(context: &Context, coupon_id: CouponId) -> FieldResult<()> {
     // Validate coupon
-    let url = format!(
-        "{}/{}/{}/validate",
-        context.config.service_url(Service::Stores),
-        Model::Coupon.to_url(),
-        coupon_id,
-    );
+    let url = context.config.url(Service::Stores).model(Model::Coupon).segment(coupon_id).path("validate").build();
 
     let check_result = context
         .request::<Option<CouponValidate>>(Method::Get, url, None)
@@ -138,11 +126,7 @@ pub fn validate_coupon(context: &Context, coupon_id: CouponId) -> FieldResult<()
 }
 
 pub fn get_coupon_by_code(context: &Context, coupon_code: CouponCode, store_id: StoreId) -> FieldResult<Coupon> {
-    let url = format!(
-        "{}/{}/search/code",
-        context.config.service_url(Service::Stores),
-        Model::Coupon.to_url()
-    );
+    let url = context.config.url(Service::Stores).model(Model::Coupon).path("search/code").build();
 
     let search_code = CouponsSearchCodePayload {
         code: coupon_code,
@@ -172,23 +156,13 @@ pub fn get_coupon(context: &Context, coupon_id: CouponId) -> FieldResult<Coupon>
 }
 
 pub fn try_get_coupon(context: &Context, coupon_id: CouponId) -> FieldResult<Option<Coupon>> {
-    let url = format!(
-        "{}/{}/{}",
-        context.config.service_url(Service::Stores),
-        Model::Coupon.to_url(),
-        coupon_id,
-    );
+    let url = context.config.url(Service::Stores).model(Model::Coupon).segment(coupon_id).build();
 
     context.request::<Option<Coupon>>(Method::Get, url, None).wait()
 }
 
 pub fn get_base_products(context: &Context, coupon_id: CouponId) -> FieldResult<Vec<BaseProduct>> {
-    let url = format!(
-        "{}/{}/{}/base_products",
-        context.config.service_url(Service::Stores),
-        Model::Coupon.to_url(),
-        coupon_id
-    );
+    let url = context.config.url(Service::Stores).model(Model::Coupon).segment(coupon_id).path("base_products").build();
 
     context.request::<Vec<BaseProduct>>(Method::Get, url, None).wait()
 }
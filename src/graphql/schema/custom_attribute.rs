@@ -30,10 +30,7 @@ graphql_object!(CustomAttribute: Context as "CustomAttribute" |&self| {
 
     field attribute(&executor) -> FieldResult<Option<Attribute>> as "Attribute" {
         let context = executor.context();
-        let url = format!("{}/{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::Attribute.to_url(),
-            self.attribute_id.0);
+        let url = context.config.url(Service::Stores).model(Model::Attribute).segment(self.attribute_id.0).build();
 
         context.request::<Option<Attribute>>(Method::Get, url, None)
             .wait()
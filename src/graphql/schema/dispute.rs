@@ -0,0 +1,329 @@
+//! File containing dispute object of graphql schema
+use std::cmp;
+
+use juniper;
+use juniper::ID as GraphqlID;
+use juniper::{FieldError, FieldResult};
+
+use stq_static_resources::{CommitterRole, OrderState};
+use stq_types::{OrderIdentifier, OrderSlug};
+
+use errors::FORBIDDEN_CODE;
+use graphql::context::Context;
+use graphql::models::*;
+use graphql::schema::order as order_module;
+use graphql::schema::order_transition::TransitionChecker;
+use graphql::schema::refund;
+
+graphql_object!(Dispute: Context as "Dispute" |&self| {
+    description: "Dispute of the customer about the order."
+
+    field raw_id() -> &i32 as "Unique int id" {
+        &self.id.0
+    }
+
+    field order_slug() -> &i32 as "Slug of disputed order" {
+        &self.order_slug.0
+    }
+
+    field order(&executor) -> FieldResult<Option<GraphQLOrder>> as "Disputed order" {
+        executor.context().get_orders_microservice().get_order_by_id(self.order_id)
+    }
+
+    field customer_id() -> &i32 as "Customer int id" {
+        &self.customer_id.0
+    }
+
+    field store_id() -> &i32 as "Store int id" {
+        &self.store_id.0
+    }
+
+    field reason() -> &DisputeReason as "Reason" {
+        &self.reason
+    }
+
+    field status() -> &DisputeStatus as "Status" {
+        &self.status
+    }
+
+    field messages() -> &[DisputeMessage] as "Messages of the customer, the seller and moderators" {
+        &self.messages
+    }
+
+    field created_at() -> String as "Created at" {
+        self.created_at.to_rfc3339()
+    }
+
+    field updated_at() -> String as "Updated at" {
+        self.updated_at.to_rfc3339()
+    }
+});
+
+graphql_object!(DisputeMessage: Context as "DisputeMessage" |&self| {
+    description: "Message in the dispute thread."
+
+    field raw_id() -> &i32 as "Unique int id" {
+        &self.id
+    }
+
+    field author_id() -> &i32 as "Author int id" {
+        &self.author_id.0
+    }
+
+    field author_role() -> &DisputeParticipant as "Author role in the dispute" {
+        &self.author_role
+    }
+
+    field text() -> &str as "Text" {
+        &self.text
+    }
+
+    field attachments() -> &[String] as "Urls of attached photos or documents" {
+        &self.attachments
+    }
+
+    field created_at() -> String as "Created at" {
+        self.created_at.to_rfc3339()
+    }
+});
+
+graphql_object!(Connection<Dispute, PageInfoSegments>: Context as "DisputeConnectionPages" |&self| {
+    description: "Dispute Connection"
+
+    field edges() -> &[Edge<Dispute>] {
+        &self.edges
+    }
+
+    field page_info() -> &PageInfoSegments {
+        &self.page_info
+    }
+});
+
+graphql_object!(Edge<Dispute>: Context as "DisputeEdge" |&self| {
+    description:"Dispute Edge"
+
+    field cursor() -> &juniper::ID {
+        &self.cursor
+    }
+
+    field node() -> &Dispute {
+        &self.node
+    }
+});
+
+pub fn find_disputes(
+    context: &Context,
+    current_page: i32,
+    items_count: i32,
+    search: DisputeSearch,
+) -> FieldResult<Connection<Dispute, PageInfoSegments>> {
+    let current_page = cmp::max(current_page, 1);
+    let records_limit = context.config.gateway.records_limit;
+    let items_count = cmp::max(1, cmp::min(items_count, records_limit as i32));
+    let skip = items_count * (current_page - 1);
+    let disputes = context.get_orders_microservice().search_disputes(skip, items_count, search)?;
+    let total_pages = cmp::max(0, disputes.total_count as i32 - 1) / items_count + 1;
+    let edges = disputes
+        .disputes
+        .into_iter()
+        .map(|dispute| Edge::new(GraphqlID::from(dispute.id.to_string()), dispute))
+        .collect();
+    let page_info = PageInfoSegments {
+        current_page,
+        page_items_count: items_count,
+        total_pages,
+        total_count: disputes.total_count as i32,
+    };
+    Ok(Connection::new(edges, page_info))
+}
+
+pub fn get_dispute(context: &Context, dispute_id: DisputeId) -> FieldResult<Dispute> {
+    context.get_orders_microservice().get_dispute(dispute_id)?.ok_or_else(|| {
+        FieldError::new(
+            "Dispute not found",
+            graphql_value!({ "code": 400, "details": { format!("dispute with id: {} not found", dispute_id) }}),
+        )
+    })
+}
+
+pub fn run_open_dispute_mutation(context: &Context, input: OpenDisputeInput) -> FieldResult<Dispute> {
+    let user = context.user.clone().ok_or_else(|| {
+        FieldError::new(
+            "Could not open dispute for unauthorized user.",
+            graphql_value!({ "code": 100, "details": { "No user id in request header." }}),
+        )
+    })?;
+
+    // only the customer can dispute the order and only after it was sent
+    let order_slug = OrderSlug(input.order_slug);
+    let committer_role = TransitionChecker::new(context).check(order_slug, &OrderState::Dispute, Some(CommitterRole::Customer))?;
+    let order = order_module::get_order(context, OrderIdentifier::Slug(order_slug))?.0;
+
+    // the dispute is created first, so that the order never stays in dispute state without a dispute
+    let orders = context.get_orders_microservice();
+    let dispute = orders.create_dispute(NewDispute {
+        order_id: order.id,
+        order_slug,
+        customer_id: user.user_id,
+        store_id: order.store,
+        reason: input.reason,
+        message: NewDisputeMessage {
+            author_id: user.user_id,
+            author_role: DisputeParticipant::Customer,
+            text: input.text.clone(),
+            attachments: input.attachments.unwrap_or_default(),
+        },
+    })?;
+
+    let moved = context
+        .get_saga_microservice()
+        .set_order_state(UpdateOrderState {
+            order_slug,
+            state: OrderState::Dispute,
+            track_id: None,
+            comment: Some(input.text),
+            committer_role,
+        })
+        .and_then(|order| {
+            order.map(|_| ()).ok_or_else(|| {
+                FieldError::new(
+                    "Order not found",
+                    graphql_value!({ "code": 400, "details": { format!("order by slug: {} not found", order_slug) }}),
+                )
+            })
+        });
+    if let Err(e) = moved {
+        if let Err(delete_error) = orders.delete_dispute(dispute.id) {
+            error!(
+                "Could not delete dispute {} of order {} which was not moved to dispute state: {:?}",
+                dispute.id, order_slug, delete_error
+            );
+        }
+        return Err(e);
+    }
+
+    Ok(dispute)
+}
+
+pub fn run_add_dispute_message_mutation(context: &Context, input: AddDisputeMessageInput) -> FieldResult<Dispute> {
+    let user = context.user.clone().ok_or_else(|| {
+        FieldError::new(
+            "Could not add dispute message for unauthorized user.",
+            graphql_value!({ "code": 100, "details": { "No user id in request header." }}),
+        )
+    })?;
+
+    let dispute = get_dispute(context, DisputeId(input.dispute_id))?;
+    if dispute.status.is_resolved() {
+        return Err(FieldError::new(
+            "Could not add dispute message.",
+            graphql_value!({ "code": 100, "details": { "Dispute is already resolved." }}),
+        ));
+    }
+
+    let author_role = participant(context, &dispute)?;
+    let await_seller = input.await_seller.unwrap_or(false);
+    if await_seller && author_role != DisputeParticipant::Moderator {
+        return Err(FieldError::new(
+            "Could not add dispute message.",
+            graphql_value!({ "code": FORBIDDEN_CODE, "details": { "Only moderator can ask the seller to respond." }}),
+        ));
+    }
+
+    let orders = context.get_orders_microservice();
+    let dispute = orders.add_dispute_message(
+        dispute.id,
+        NewDisputeMessage {
+            author_id: user.user_id,
+            author_role,
+            text: input.text,
+            attachments: input.attachments.unwrap_or_default(),
+        },
+    )?;
+
+    let next_status = match author_role {
+        DisputeParticipant::Moderator if await_seller => Some(DisputeStatus::AwaitingSeller),
+        DisputeParticipant::Seller if dispute.status == DisputeStatus::AwaitingSeller => Some(DisputeStatus::Open),
+        _ => None,
+    };
+    match next_status {
+        Some(status) => orders.update_dispute_status(dispute.id, UpdateDisputeStatus { status }),
+        None => Ok(dispute),
+    }
+}
+
+pub fn run_resolve_dispute_mutation(context: &Context, input: ResolveDisputeInput) -> FieldResult<Dispute> {
+    let user = context.user.clone().ok_or_else(|| {
+        FieldError::new(
+            "Could not resolve dispute for unauthorized user.",
+            graphql_value!({ "code": 100, "details": { "No user id in request header." }}),
+        )
+    })?;
+
+    let dispute = get_dispute(context, DisputeId(input.dispute_id))?;
+    if dispute.status.is_resolved() {
+        return Err(FieldError::new(
+            "Could not resolve dispute.",
+            graphql_value!({ "code": 100, "details": { "Dispute is already resolved." }}),
+        ));
+    }
+
+    let (order_state, status) = match input.resolution {
+        DisputeResolution::Refund => (OrderState::Cancelled, DisputeStatus::ResolvedRefund),
+        DisputeResolution::Reject => (OrderState::Complete, DisputeStatus::ResolvedRejected),
+    };
+
+    // disputed orders can only be moved by moderators, so the checker rejects everybody else
+    let committer_role = TransitionChecker::new(context).check(dispute.order_slug, &order_state, Some(CommitterRole::System))?;
+    // the refund is made while the order is still disputed, a failed refund leaves the dispute open
+    if input.resolution == DisputeResolution::Refund {
+        let order = order_module::get_order(context, OrderIdentifier::Slug(dispute.order_slug))?.0;
+        refund::refund_disputed_order(context, &order, input.comment.clone())?;
+    }
+    context.get_saga_microservice().set_order_state(UpdateOrderState {
+        order_slug: dispute.order_slug,
+        state: order_state,
+        track_id: None,
+        comment: Some(input.comment.clone()),
+        committer_role,
+    })?;
+
+    let orders = context.get_orders_microservice();
+    orders.add_dispute_message(
+        dispute.id,
+        NewDisputeMessage {
+            author_id: user.user_id,
+            author_role: DisputeParticipant::Moderator,
+            text: input.comment,
+            attachments: vec![],
+        },
+    )?;
+    orders.update_dispute_status(dispute.id, UpdateDisputeStatus { status })
+}
+
+/// Role of the current user in the dispute, the customer and the seller take precedence over moderator
+fn participant(context: &Context, dispute: &Dispute) -> FieldResult<DisputeParticipant> {
+    let order = context.get_orders_microservice().get_order_by_id(dispute.order_id)?.ok_or_else(|| {
+        FieldError::new(
+            "Order not found",
+            graphql_value!({ "code": 400, "details": { format!("order by id: {} not found", dispute.order_id) }}),
+        )
+    })?;
+
+    TransitionChecker::new(context)
+        .actors(&order.0)?
+        .into_iter()
+        .filter_map(|actor| match actor {
+            OrderActor::Customer => Some(DisputeParticipant::Customer),
+            OrderActor::Seller => Some(DisputeParticipant::Seller),
+            OrderActor::Moderator => Some(DisputeParticipant::Moderator),
+            OrderActor::System => None,
+        })
+        .next()
+        .ok_or_else(|| {
+            FieldError::new(
+                "Could not add dispute message.",
+                graphql_value!({ "code": FORBIDDEN_CODE, "details": { "Current user does not participate in the dispute." }}),
+            )
+        })
+}
@@ -46,6 +46,7 @@ fn find_orders(
         current_page,
         page_items_count: items_count,
         total_pages,
+        total_count: orders.total_count as i32,
     };
     Ok(Connection::new(orders_edges, page_info))
 }
@@ -0,0 +1,153 @@
+//! Distance filtering and sorting of stores and products search results
+use std::cmp::Ordering;
+
+use geo::algorithm::haversine_distance::HaversineDistance;
+use geo::Point;
+use juniper::{FieldError, FieldResult};
+
+use stq_types::StoreId;
+
+use graphql::context::Context;
+use graphql::models::*;
+use graphql::schema::warehouse as warehouse_module;
+
+/// Search results fetched to be filtered and sorted by distance in the gateway,
+/// results beyond them are not found by the searches with distance
+pub const GEO_CANDIDATES_LIMIT: i32 = 200;
+/// Sort key of the cursors of the results sorted by distance
+pub const DISTANCE_SORT_KEY: &str = "Distance";
+
+/// NaN coordinates fail the comparisons, so they are rejected too
+pub fn validate(near: &GeoDistanceInput) -> FieldResult<()> {
+    if !(near.latitude >= -90.0 && near.latitude <= 90.0) || !(near.longitude >= -180.0 && near.longitude <= 180.0) {
+        return Err(geo_search_error("Latitude must be from -90 to 90 and longitude from -180 to 180.".to_string()));
+    }
+    if let Some(within_km) = near.within_km {
+        if within_km.is_nan() || within_km <= 0.0 {
+            return Err(geo_search_error("Distance must be greater than zero.".to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// Keeps the results of the stores within `near.within_km` and puts the nearest first if asked to,
+/// results of the stores without warehouse locations are dropped by the filter and put last by the sorting
+pub fn by_distance<T, F>(context: &Context, near: &GeoDistanceInput, items: Vec<T>, store_id: F) -> FieldResult<Vec<T>>
+where
+    F: Fn(&T) -> StoreId,
+{
+    let mut distances = StoreDistances::new(near.point());
+    let mut located = vec![];
+    for item in items {
+        let distance = distances.distance_km(context, store_id(&item))?;
+        located.push((distance, item));
+    }
+    Ok(filter_and_sort(near, located))
+}
+
+fn filter_and_sort<T>(near: &GeoDistanceInput, located: Vec<(Option<f64>, T)>) -> Vec<T> {
+    let mut located = located
+        .into_iter()
+        .filter(|(distance, _)| match (near.within_km, distance) {
+            (Some(within_km), Some(distance)) => *distance <= within_km,
+            (Some(_), None) => false,
+            (None, _) => true,
+        })
+        .collect::<Vec<_>>();
+
+    if near.sort_by_distance() {
+        // stable sort keeps the search order of the stores at the same distance
+        located.sort_by(|(a, _), (b, _)| match (a, b) {
+            (Some(a), Some(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        });
+    }
+
+    located.into_iter().map(|(_, item)| item).collect()
+}
+
+/// Distance in kilometers to the nearest of the locations
+fn nearest_km<I>(origin: Point<f64>, locations: I) -> Option<f64>
+where
+    I: IntoIterator<Item = Point<f64>>,
+{
+    locations
+        .into_iter()
+        .map(|location| origin.haversine_distance(&location) / 1000.0)
+        .fold(None, |nearest: Option<f64>, distance| match nearest {
+            Some(nearest) if nearest <= distance => Some(nearest),
+            _ => Some(distance),
+        })
+}
+
+/// Distances to the nearest warehouses of the stores, fetched once per store
+struct StoreDistances {
+    origin: Point<f64>,
+    known: Vec<(StoreId, Option<f64>)>,
+}
+
+impl StoreDistances {
+    fn new(origin: Point<f64>) -> Self {
+        Self { origin, known: vec![] }
+    }
+
+    fn distance_km(&mut self, context: &Context, store_id: StoreId) -> FieldResult<Option<f64>> {
+        if let Some((_, distance)) = self.known.iter().find(|(known_id, _)| *known_id == store_id) {
+            return Ok(*distance);
+        }
+
+        let locations = warehouse_module::get_warehouses_for_store(context, store_id)?
+            .into_iter()
+            .filter_map(|warehouse| warehouse.location);
+        let distance = nearest_km(self.origin, locations);
+        self.known.push((store_id, distance));
+        Ok(distance)
+    }
+}
+
+fn geo_search_error(details: String) -> FieldError {
+    FieldError::new("Could not search by distance.", graphql_value!({ "code": 100, "details": { details }}))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn berlin(within_km: Option<f64>, sort_by_distance: bool) -> GeoDistanceInput {
+        GeoDistanceInput {
+            latitude: 52.52,
+            longitude: 13.405,
+            within_km,
+            sort_by_distance: Some(sort_by_distance),
+        }
+    }
+
+    #[test]
+    fn nearest_warehouse_distance() {
+        let near = berlin(None, false);
+        let hamburg = Point::new(9.9937, 53.5511);
+        let potsdam = Point::new(13.0645, 52.3906);
+
+        let distance = nearest_km(near.point(), vec![hamburg, potsdam]).unwrap();
+        assert!(distance > 25.0 && distance < 28.0, "distance to Potsdam is {}", distance);
+        assert_eq!(nearest_km(near.point(), vec![]), None);
+    }
+
+    #[test]
+    fn filters_by_radius() {
+        let located = vec![(Some(255.0), "hamburg"), (Some(1610.0), "moscow"), (None, "unknown"), (Some(27.0), "potsdam")];
+
+        assert_eq!(filter_and_sort(&berlin(Some(300.0), false), located.clone()), vec!["hamburg", "potsdam"]);
+        assert_eq!(filter_and_sort(&berlin(Some(255.0), false), located.clone()), vec!["hamburg", "potsdam"]);
+        assert_eq!(filter_and_sort(&berlin(Some(254.9), false), located.clone()), vec!["potsdam"]);
+        assert_eq!(filter_and_sort(&berlin(None, false), located), vec!["hamburg", "moscow", "unknown", "potsdam"]);
+    }
+
+    #[test]
+    fn sorts_by_distance() {
+        let located = vec![(Some(255.0), "hamburg"), (None, "unknown"), (Some(27.0), "potsdam"), (Some(27.0), "teltow")];
+
+        assert_eq!(filter_and_sort(&berlin(None, true), located.clone()), vec!["potsdam", "teltow", "hamburg", "unknown"]);
+        assert_eq!(filter_and_sort(&berlin(Some(100.0), true), located), vec!["potsdam", "teltow"]);
+    }
+}
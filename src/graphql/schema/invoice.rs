@@ -80,11 +80,7 @@ graphql_object!(Transaction: Context as "Transaction" |&self| {
 
 impl Invoice {
     fn get_orders(&self, context: &Context) -> FieldResult<Vec<GraphQLOrder>> {
-        let url = format!(
-            "{}/invoices/by-id/{}/order_ids",
-            &context.config.service_url(Service::Billing),
-            self.invoice_id.to_string()
-        );
+        let url = context.config.url(Service::Billing).path("invoices/by-id").segment(self.invoice_id).path("order_ids").build();
 
         context.request::<Vec<OrderId>>(Method::Get, url, None).wait().and_then(|ids| {
             ids.into_iter()
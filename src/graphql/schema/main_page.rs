@@ -1,6 +1,5 @@
 //! File containing search object of graphql schema
 use std::cmp;
-use std::str::FromStr;
 
 use futures::Future;
 use hyper::Method;
@@ -25,21 +24,19 @@ graphql_object!(MainPage: Context as "MainPage" |&self| {
             -> FieldResult<Option<Connection<BaseProduct, PageInfo>>> as "Find most viewed base products each one contains one variant." {
         let context = executor.context();
 
-        let offset = after
-            .and_then(|id|{
-                i32::from_str(&id).map(|i| i + 1).ok()
-            })
-            .unwrap_or_default();
+        let offset = Cursor::offset_after(after)?;
 
         let records_limit = context.config.gateway.records_limit;
         let count = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
 
-        let url = format!("{}/{}/most_viewed?offset={}&count={}",
-            context.config.service_url(Service::Stores),
-            Model::BaseProduct.to_url(),
-            offset,
-            count + 1
-            );
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::BaseProduct)
+            .path("most_viewed")
+            .query("offset", offset)
+            .query("count", count + 1)
+            .build();
 
         let options = if let Some(mut options) = search_term.options.clone() {
             options.status = Some(ModerationStatus::Published);
@@ -63,14 +60,15 @@ graphql_object!(MainPage: Context as "MainPage" |&self| {
                 if has_next_page {
                     base_product_edges.pop();
                 };
-                let has_previous_page = true;
+                let has_previous_page = offset > 0;
                 let start_cursor =  base_product_edges.get(0).map(|e| e.cursor.clone());
                 let end_cursor = base_product_edges.iter().last().map(|e| e.cursor.clone());
                 let page_info = PageInfo {
                     has_next_page,
                     has_previous_page,
                     start_cursor,
-                    end_cursor};
+                    end_cursor,
+                    total_count: None};
                 Connection::new(base_product_edges, page_info)
             })
             .wait()
@@ -85,21 +83,19 @@ graphql_object!(MainPage: Context as "MainPage" |&self| {
             -> FieldResult<Option<Connection<BaseProduct, PageInfo>>> as "Find base products each one with most discount variant." {
         let context = executor.context();
 
-        let offset = after
-            .and_then(|id|{
-                i32::from_str(&id).map(|i| i + 1).ok()
-            })
-            .unwrap_or_default();
+        let offset = Cursor::offset_after(after)?;
 
         let records_limit = context.config.gateway.records_limit;
         let count = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
 
-        let url = format!("{}/{}/most_discount?offset={}&count={}",
-            context.config.service_url(Service::Stores),
-            Model::BaseProduct.to_url(),
-            offset,
-            count + 1
-            );
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::BaseProduct)
+            .path("most_discount")
+            .query("offset", offset)
+            .query("count", count + 1)
+            .build();
 
         let options = if let Some(mut options) = search_term.options.clone() {
             options.status = Some(ModerationStatus::Published);
@@ -123,14 +119,15 @@ graphql_object!(MainPage: Context as "MainPage" |&self| {
                 if has_next_page {
                     base_product_edges.pop();
                 };
-                let has_previous_page = true;
+                let has_previous_page = offset > 0;
                 let start_cursor =  base_product_edges.get(0).map(|e| e.cursor.clone());
                 let end_cursor = base_product_edges.iter().last().map(|e| e.cursor.clone());
                 let page_info = PageInfo {
                     has_next_page,
                     has_previous_page,
                     start_cursor,
-                    end_cursor};
+                    end_cursor,
+                    total_count: None};
                 Connection::new(base_product_edges, page_info)
             })
             .wait()
@@ -8,40 +8,58 @@ pub mod cart;
 pub mod cart_product;
 pub mod cart_store;
 pub mod category;
+pub mod checkout_quote;
 pub mod company;
 pub mod company_package;
 pub mod country;
 pub mod coupon;
 pub mod custom_attribute;
 pub mod customer;
+pub mod dispute;
 pub mod fee;
 pub mod financial_manager;
+pub mod geo_search;
 pub mod invoice;
 pub mod main_page;
 pub mod moderator_comment;
 pub mod mutations;
 pub mod node;
 pub mod order;
+pub mod order_batch;
 pub mod order_billing;
+pub mod order_export;
 pub mod order_history;
+pub mod order_return;
+pub mod order_transition;
 pub mod package;
 pub mod page_info;
 pub mod payment_intent;
 pub mod payout;
 pub mod product;
+pub mod product_code;
+pub mod product_import;
 pub mod query;
+pub mod recommendation;
+pub mod refund;
 pub mod reset_token;
+pub mod review;
+pub mod saved_search;
 pub mod search;
+pub mod search_suggestion;
+pub mod shipment;
 pub mod shipping;
 pub mod shipping_rates;
 pub mod stock;
 pub mod store;
+pub mod store_follow;
 pub mod stripe;
 pub mod subscription;
 pub mod user;
 pub mod user_delivery_address;
 pub mod user_role;
+pub mod view_history;
 pub mod warehouse;
+pub mod wishlist;
 pub mod wizard_store;
 
 use juniper;
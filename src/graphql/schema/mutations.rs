@@ -18,19 +18,32 @@ use stq_api::warehouses::WarehouseClient;
 use stq_routes::model::Model;
 use stq_routes::service::Service;
 use stq_static_resources::{CurrencyType, Provider};
-use stq_types::{BaseProductId, CartItem, CouponCode, CouponId, ProductId, SagaId, StoreId, UserId, WarehouseId};
+use stq_types::{BaseProductId, CartItem, CouponCode, CouponId, OrderSlug, ProductId, SagaId, StoreId, UserId, WarehouseId};
 
 use errors::into_graphql;
 use graphql::schema::base_product as base_product_module;
 use graphql::schema::buy_now;
 use graphql::schema::cart as cart_module;
 use graphql::schema::category as category_module;
+use graphql::schema::dispute;
 use graphql::schema::order;
+use graphql::schema::order_batch;
+use graphql::schema::order_export;
+use graphql::schema::order_return;
+use graphql::schema::order_transition::TransitionChecker;
 use graphql::schema::payout;
 use graphql::schema::product as product_module;
+use graphql::schema::product_import;
+use graphql::schema::refund;
+use graphql::schema::review;
+use graphql::schema::saved_search;
+use graphql::schema::shipment;
 use graphql::schema::store as store_module;
+use graphql::schema::store_follow;
 use graphql::schema::stripe as stripe_module;
 use graphql::schema::user as user_module;
+use graphql::schema::view_history;
+use graphql::schema::wishlist as wishlist_module;
 
 pub struct Mutation;
 
@@ -55,9 +68,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field createUser(&executor, input: CreateUserInput as "Create user input.") -> FieldResult<User> as "Creates new user." {
         let context = executor.context();
-        let saga_addr = context.config.saga_microservice.url.clone();
-        let url = format!("{}/create_account",
-            saga_addr);
+        let url = context.config.saga_url().path("create_account").build();
 
         let new_ident = NewIdentity {
             provider: Provider::Email,
@@ -129,10 +140,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field blockUser(&executor, id: i32 as "Users raw id.") -> FieldResult<User>  as "Block existing user." {
         let context = executor.context();
-        let url = format!("{}/{}/{}/block",
-            context.config.service_url(Service::Users),
-            Model::User.to_url(),
-            id);
+        let url = context.config.url(Service::Users).model(Model::User).segment(id).path("block").build();
 
         context.request::<User>(Method::Post, url, None)
             .wait()
@@ -140,10 +148,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field unblockUser(&executor, id: i32 as "User raw id.") -> FieldResult<User>  as "Unblock existing user." {
         let context = executor.context();
-        let url = format!("{}/{}/{}/unblock",
-            context.config.service_url(Service::Users),
-            Model::User.to_url(),
-            id);
+        let url = context.config.url(Service::Users).model(Model::User).segment(id).path("unblock").build();
 
         context.request::<User>(Method::Post, url, None)
             .wait()
@@ -151,10 +156,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field deleteUser(&executor, id: i32 as "User raw id.") -> FieldResult<Mock> as "Delete user from DB" {
         let context = executor.context();
-        let url = format!("{}/{}/{}/delete",
-            context.config.service_url(Service::Users),
-            Model::User.to_url(),
-            id);
+        let url = context.config.url(Service::Users).model(Model::User).segment(id).path("delete").build();
 
         context.request::<()>(Method::Delete, url, None)
             .wait().map(|_| Mock)
@@ -162,9 +164,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field changePassword(&executor, input: ChangePasswordInput as "Password change input.") -> FieldResult<ResetApplyActionOutput>  as "Changes user password." {
         let context = executor.context();
-        let url = format!("{}/{}/password_change",
-            context.config.service_url(Service::Users),
-            Model::User.to_url());
+        let url = context.config.url(Service::Users).model(Model::User).path("password_change").build();
         let body: String = serde_json::to_string(&input)?.to_string();
 
         let token = context.request::<String>(Method::Post, url, Some(body))
@@ -178,9 +178,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field requestPasswordReset(&executor, input: ResetRequest as "Password reset request input.") -> FieldResult<ResetActionOutput>  as "Requests password reset." {
         let context = executor.context();
-        let saga_addr = context.config.saga_microservice.url.clone();
-        let url = format!("{}/reset_password",
-            saga_addr);
+        let url = context.config.saga_url().path("reset_password").build();
 
         let input = input.fill_uuid();
         let body = serde_json::to_string(&input)?;
@@ -194,9 +192,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field applyPasswordReset(&executor, input: ResetApply as "Password reset apply input.") -> FieldResult<ResetApplyActionOutput>  as "Applies password reset." {
         let context = executor.context();
-        let saga_addr = context.config.saga_microservice.url.clone();
-        let url = format!("{}/reset_password_apply",
-            saga_addr);
+        let url = context.config.saga_url().path("reset_password_apply").build();
         let body = serde_json::to_string(&input)?;
         let token = context.request::<String>(Method::Post, url, Some(body))
             .wait()?;
@@ -209,10 +205,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field resendEmailVerificationLink(&executor, input: VerifyEmailResend as "Email verify request input.") -> FieldResult<VerifyEmailResendOutput>  as "Requests email verification link on email send." {
         let context = executor.context();
-        let saga_addr = context.config.saga_microservice.url.clone();
-        let url = format!("{}/email_verify",
-            saga_addr
-            );
+        let url = context.config.saga_url().path("email_verify").build();
         let body = serde_json::to_string(&input)?;
         context.request::<()>(Method::Post, url, Some(body))
             .wait()?;
@@ -230,8 +223,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field addRoleToUserOnUsersMicroservice(&executor, input: NewUsersRoleInput as "New Users  Role Input.") -> FieldResult<NewRole<UserMicroserviceRole>>  as "Adds users  role to user." {
         let context = executor.context();
-        let url = format!("{}/roles",
-            context.config.service_url(Service::Users));
+        let url = context.config.url(Service::Users).path("roles").build();
         let body: String = serde_json::to_string(&input)?.to_string();
 
         context.request::<NewRole<UserMicroserviceRole>>(Method::Post, url, Some(body))
@@ -254,8 +246,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field removeRoleFromUserOnUsersMicroservice(&executor, input: RemoveUsersRoleInput as "New Users  Role Input.") -> FieldResult<NewRole<UserMicroserviceRole>>  as "Removes users role." {
         let context = executor.context();
-        let url = format!("{}/roles",
-            context.config.service_url(Service::Users));
+        let url = context.config.url(Service::Users).path("roles").build();
         let body: String = serde_json::to_string(&input)?.to_string();
 
         context.request::<NewRole<UserMicroserviceRole>>(Method::Delete, url, Some(body))
@@ -278,10 +269,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field createStore(&executor, input: CreateStoreInput as "Create store input.") -> FieldResult<Store> as "Creates new store." {
         let context = executor.context();
-        let saga_addr = context.config.saga_microservice.url.clone();
-        let url = format!("{}/{}",
-            saga_addr,
-            "create_store");
+        let url = context.config.saga_url().path("create_store").build();
         let body: String = serde_json::to_string(&input.fill_uuid())?.to_string();
 
         context.request::<Store>(Method::Post, url, Some(body))
@@ -296,10 +284,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field deleteStore(&executor, id: i32 as "Delete store raw id.") -> FieldResult<Mock> as "Deletes existing store from DB." {
         let context = executor.context();
-        let url = format!("{}/{}/{}/delete",
-            context.config.service_url(Service::Stores),
-            Model::Store.to_url(),
-            id);
+        let url = context.config.url(Service::Stores).model(Model::Store).segment(id).path("delete").build();
 
         context.request::<()>(Method::Delete, url, None)
             .wait().map(|_| Mock)
@@ -308,17 +293,14 @@ graphql_object!(Mutation: Context |&self| {
     field deactivateStore(&executor, input: DeactivateStoreInput as "Deactivate store input.") -> FieldResult<Store>  as "Deactivates existing store." {
         let context = executor.context();
         let identifier = ID::from_str(&*input.id)?;
-        let url = format!("{}/{}/{}/deactivate", context.config.saga_microservice.url, Model::Store.to_url(), identifier.raw_id);
+        let url = context.config.saga_url().model(Model::Store).segment(identifier.raw_id).path("deactivate").build();
         context.request::<Store>(Method::Post, url, None)
             .wait()
     }
 
     field deprecated "use setModerationStatusStore" publishStore(&executor, id: i32 as "Store raw id.") -> FieldResult<Store>  as "Publish store." {
         let context = executor.context();
-        let url = format!("{}/{}/{}/publish",
-            context.config.service_url(Service::Stores),
-            Model::Store.to_url(),
-            id);
+        let url = context.config.url(Service::Stores).model(Model::Store).segment(id).path("publish").build();
 
         context.request::<Store>(Method::Post, url, None)
             .wait()
@@ -344,9 +326,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field createProduct(&executor, input: CreateProductWithAttributesInput as "Create product with attributes input.") -> FieldResult<Product> as "Creates new product." {
         let context = executor.context();
-        let url = format!("{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::Product.to_url());
+        let url = context.config.url(Service::Stores).model(Model::Product).build();
         let mut input = input;
         input.product = input.product.fill_uuid(input.client_mutation_id.clone());
         let body: String = serde_json::to_string(&input)?.to_string();
@@ -365,16 +345,14 @@ graphql_object!(Mutation: Context |&self| {
     field deactivateProduct(&executor, input: DeactivateProductInput as "Deactivate product input.") -> FieldResult<Product>  as "Deactivates existing product." {
         let context = executor.context();
         let identifier = ID::from_str(&*input.id)?;
-        let url = format!("{}/{}/{}/deactivate", context.config.saga_microservice.url, Model::Product.to_url(), identifier.raw_id);
+        let url = context.config.saga_url().model(Model::Product).segment(identifier.raw_id).path("deactivate").build();
         context.request::<Product>(Method::Post, url, None)
             .wait()
     }
 
     field createBaseProduct(&executor, input: CreateBaseProductInput as "Create base product with attributes input.") -> FieldResult<BaseProduct> as "Creates new base product." {
         let context = executor.context();
-        let url = format!("{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::BaseProduct.to_url());
+        let url = context.config.url(Service::Stores).model(Model::BaseProduct).build();
         let body: String = serde_json::to_string(&input.fill_uuid())?.to_string();
 
         context.request::<BaseProduct>(Method::Post, url, Some(body))
@@ -383,20 +361,14 @@ graphql_object!(Mutation: Context |&self| {
 
     field createBaseProductWithVariants(&executor, input: NewBaseProductWithVariantsInput as "Create base product with variants input.") -> FieldResult<BaseProduct> as "Creates new base product with variants." {
         let context = executor.context();
-        let url = format!("{}/{}/create_with_variants",
-            context.config.saga_microservice.url,
-            Model::BaseProduct.to_url());
-        let mut input = input;
-        input.variants = input.variants.into_iter()
-            .map(|mut variant| {
-                variant.product = variant.product.fill_uuid(variant.client_mutation_id.clone());
-                variant
-            })
-            .collect();
-        let body: String = serde_json::to_string(&input.fill_uuid())?.to_string();
 
-        context.request::<BaseProduct>(Method::Post, url, Some(body))
-            .wait()
+        base_product_module::run_create_base_product_with_variants(context, input)
+    }
+
+    field importProducts(&executor, input: ImportProductsInput as "Import products input.") -> FieldResult<ImportProductsOutput> as "Creates base products with variants from CSV or JSON file, reporting errors per row." {
+        let context = executor.context();
+
+        product_import::run_import_products(context, input)
     }
 
     field updateBaseProduct(&executor, input: UpdateBaseProductInput as "Update base product input.") -> FieldResult<BaseProduct>  as "Updates existing base product."{
@@ -409,16 +381,14 @@ graphql_object!(Mutation: Context |&self| {
     field deactivateBaseProduct(&executor, input: DeactivateBaseProductInput as "Deactivate base product input.") -> FieldResult<BaseProduct>  as "Deactivates existing base product." {
         let context = executor.context();
         let identifier = ID::from_str(&*input.id)?;
-        let url = format!("{}/{}/{}/deactivate", context.config.saga_microservice.url, Model::BaseProduct.to_url(), identifier.raw_id);
+        let url = context.config.saga_url().model(Model::BaseProduct).segment(identifier.raw_id).path("deactivate").build();
         context.request::<BaseProduct>(Method::Post, url, None)
             .wait()
     }
 
     field deprecated "use setModerationStatusBaseProduct" publishBaseProducts(&executor, ids: Vec<i32> as "BaseProduct raw ids.") -> FieldResult<Vec<BaseProduct>>  as "Published base_products." {
         let context = executor.context();
-        let url = format!("{}/{}/publish",
-            context.config.service_url(Service::Stores),
-            Model::BaseProduct.to_url());
+        let url = context.config.url(Service::Stores).model(Model::BaseProduct).path("publish").build();
 
         let body: String = serde_json::to_string(&ids)?.to_string();
 
@@ -446,9 +416,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field createCustomAttribute(&executor, input: NewCustomAttributeInput as "Create custom attribute input.") -> FieldResult<CustomAttribute> as "Creates new custom attribute" {
         let context = executor.context();
-        let url = format!("{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::CustomAttribute.to_url());
+        let url = context.config.url(Service::Stores).model(Model::CustomAttribute).build();
 
         let body: String = serde_json::to_string(&input)?.to_string();
 
@@ -458,10 +426,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field deleteCustomAttribute(&executor, input: DeleteCustomAttributeInput as "Delete custom attribute input.") -> FieldResult<CustomAttribute> as "Deletes custom attribute" {
         let context = executor.context();
-        let url = format!("{}/{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::CustomAttribute.to_url(),
-            input.custom_attribute_id);
+        let url = context.config.url(Service::Stores).model(Model::CustomAttribute).segment(input.custom_attribute_id).build();
 
         context.request::<CustomAttribute>(Method::Delete, url, None)
             .wait()
@@ -469,22 +434,24 @@ graphql_object!(Mutation: Context |&self| {
 
     field getJWTByEmail(&executor, input: CreateJWTEmailInput as "Create jwt input.") -> FieldResult<JWT> as "Get JWT Token by email." {
         let context = executor.context();
-        let url = format!("{}/{}/email",
-            context.config.service_url(Service::Users),
-            Model::JWT.to_url());
+        let url = context.config.url(Service::Users).model(Model::JWT).path("email").build();
 
         let body: String = serde_json::to_string(&input)?.to_string();
 
-        context.request::<JWT>(Method::Post, url, Some(body))
-            .wait()
+        let jwt = context.request::<JWT>(Method::Post, url, Some(body))
+            .wait()?;
+
+        if input.merge_session_cart.unwrap_or(false) {
+            cart_module::merge_session_cart_on_login(context, &jwt);
+        }
+        wishlist_module::merge_session_wishlist_on_login(context, &jwt);
+
+        Ok(jwt)
     }
 
     field getJWTByProvider(&executor, input: CreateJWTProviderInput as "Create jwt input.") -> FieldResult<JWT> as "Get JWT Token by provider." {
         let context = executor.context();
-        let url = format!("{}/{}/{}",
-            context.config.service_url(Service::Users),
-            Model::JWT.to_url(),
-            input.provider);
+        let url = context.config.url(Service::Users).model(Model::JWT).segment(&input.provider).build();
 
         let mut additional_data = input.additional_data.unwrap_or_default().into();
         user_module::change_alpha2_to_alpha3(&context, &mut additional_data);
@@ -492,15 +459,20 @@ graphql_object!(Mutation: Context |&self| {
         let oauth = ProviderOauth { token: input.token, additional_data: Some(additional_data)};
         let body: String = serde_json::to_string(&oauth)?;
 
-        context.request::<JWT>(Method::Post, url, Some(body))
-            .wait()
+        let jwt = context.request::<JWT>(Method::Post, url, Some(body))
+            .wait()?;
+
+        if input.merge_session_cart.unwrap_or(false) {
+            cart_module::merge_session_cart_on_login(context, &jwt);
+        }
+        wishlist_module::merge_session_wishlist_on_login(context, &jwt);
+
+        Ok(jwt)
     }
 
     field deprecated "do not use" renewJWT(&executor) -> FieldResult<JWT> as "Get JWT Token by email." {
         let context = executor.context();
-        let url = format!("{}/{}/renew",
-            context.config.service_url(Service::Users),
-            Model::JWT.to_url());
+        let url = context.config.url(Service::Users).model(Model::JWT).path("renew").build();
 
         context.request::<JWT>(Method::Post, url, None)
             .wait()
@@ -508,9 +480,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field createAttribute(&executor, input: CreateAttributeInput as "Create attribute input.") -> FieldResult<Attribute> as "Creates new attribute." {
         let context = executor.context();
-        let url = format!("{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::Attribute.to_url());
+        let url = context.config.url(Service::Stores).model(Model::Attribute).build();
 
         let body: String = serde_json::to_string(&input.fill_uuid())?.to_string();
 
@@ -548,13 +518,13 @@ graphql_object!(Mutation: Context |&self| {
 
     field createAttributeValue(&executor, input: CreateAttributeValueInput) -> FieldResult<AttributeValue> as "Creates new attribute value" {
         let context = executor.context();
-        let url = format!(
-            "{}/{}/{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::Attribute.to_url(),
-            input.raw_attribute_id,
-            Model::AttributeValue.to_url(),
-        );
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::Attribute)
+            .segment(input.raw_attribute_id)
+            .model(Model::AttributeValue)
+            .build();
 
         let body: String = serde_json::to_string(&input)?.to_string();
 
@@ -571,13 +541,7 @@ graphql_object!(Mutation: Context |&self| {
             ));
         }
 
-        let url = format!(
-            "{}/{}/{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::Attribute.to_url(),
-            Model::AttributeValue.to_url(),
-            input.raw_id,
-        );
+        let url = context.config.url(Service::Stores).model(Model::Attribute).model(Model::AttributeValue).segment(input.raw_id).build();
 
         let body: String = serde_json::to_string(&input)?.to_string();
 
@@ -587,13 +551,7 @@ graphql_object!(Mutation: Context |&self| {
     field deleteAttributeValue(&executor, input: DeleteAttributeValueInput) -> FieldResult<Mock> as "Deletes existing attribute value" {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}/{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::Attribute.to_url(),
-            Model::AttributeValue.to_url(),
-            input.raw_id,
-        );
+        let url = context.config.url(Service::Stores).model(Model::Attribute).model(Model::AttributeValue).segment(input.raw_id).build();
 
         context.request::<AttributeValue>(Method::Delete, url, None).wait()?;
 
@@ -602,9 +560,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field createCategory(&executor, input: CreateCategoryInput as "Create category input.") -> FieldResult<Category> as "Creates new category." {
         let context = executor.context();
-        let url = format!("{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::Category.to_url());
+        let url = context.config.url(Service::Stores).model(Model::Category).build();
         let body: String = serde_json::to_string(&input.fill_uuid())?.to_string();
 
         context.request::<Category>(Method::Post, url, Some(body))
@@ -633,12 +589,7 @@ graphql_object!(Mutation: Context |&self| {
     field deleteCategory(&executor, input: DeleteCategoryInput as "Category to delete") -> FieldResult<Mock> as "Delete specific category" {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::Category.to_url(),
-            input.cat_id,
-        );
+        let url = context.config.url(Service::Stores).model(Model::Category).segment(input.cat_id).build();
 
         context.request::<()>(Method::Delete, url, None)
             .wait()?;
@@ -647,9 +598,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field addAttributeToCategory(&executor, input: AddAttributeToCategoryInput as "Create category input.") -> FieldResult<Mock> as "Creates new category." {
         let context = executor.context();
-        let url = format!("{}/{}/attributes",
-            context.config.service_url(Service::Stores),
-            Model::Category.to_url());
+        let url = context.config.url(Service::Stores).model(Model::Category).path("attributes").build();
         let body: String = serde_json::to_string(&input)?.to_string();
 
         context.request::<()>(Method::Post, url, Some(body))
@@ -659,9 +608,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field deleteAttributeFromCategory(&executor, input: DeleteAttributeFromCategory as "Update category input.") -> FieldResult<Mock>  as "Updates existing category."{
         let context = executor.context();
-        let url = format!("{}/{}/attributes",
-            context.config.service_url(Service::Stores),
-            Model::Category.to_url());
+        let url = context.config.url(Service::Stores).model(Model::Category).path("attributes").build();
         let body: String = serde_json::to_string(&input)?.to_string();
 
         context.request::<()>(Method::Delete, url, Some(body))
@@ -806,10 +753,7 @@ graphql_object!(Mutation: Context |&self| {
         }
 
         // validate products
-        let url = format!("{}/{}/{}/base_products",
-            context.config.service_url(Service::Stores),
-            Model::Coupon.to_url(),
-            coupon.id);
+        let url = context.config.url(Service::Stores).model(Model::Coupon).segment(coupon.id).path("base_products").build();
         let base_products = context.request::<Vec<BaseProduct>>(Method::Get, url, None).wait()?;
         let all_support_products = base_products.into_iter().flat_map(|b| {
             if let Some(variants) = b.variants {
@@ -898,10 +842,7 @@ graphql_object!(Mutation: Context |&self| {
         }
 
         // validate products
-        let url = format!("{}/{}/{}/base_products",
-            context.config.service_url(Service::Stores),
-            Model::Coupon.to_url(),
-            coupon.id);
+        let url = context.config.url(Service::Stores).model(Model::Coupon).segment(coupon.id).path("base_products").build();
         let base_products = context.request::<Vec<BaseProduct>>(Method::Get, url, None).wait()?;
         let all_support_products = base_products.into_iter().flat_map(|b| {
             if let Some(variants) = b.variants {
@@ -1201,6 +1142,12 @@ graphql_object!(Mutation: Context |&self| {
         cart_module::convert_products_to_cart(context, &products, Some(input.user_country_code))
     }
 
+    field mergeCart(&executor, input: MergeCartInput as "Merge cart input.") -> FieldResult<Cart> as "Merges cart of the anonymous session into the cart of the logged in user." {
+        let context = executor.context();
+
+        cart_module::run_merge_cart(context, input)
+    }
+
     field clearCart(&executor) -> FieldResult<Cart> as "Clears cart." {
         let context = executor.context();
 
@@ -1245,9 +1192,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field createWizardStore(&executor) -> FieldResult<WizardStore> as "Creates new wizard store." {
         let context = executor.context();
-        let url = format!("{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::WizardStore.to_url());
+        let url = context.config.url(Service::Stores).model(Model::WizardStore).build();
 
         context.request::<WizardStore>(Method::Post, url, None)
             .wait()
@@ -1255,9 +1200,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field updateWizardStore(&executor, input: UpdateWizardStoreInput as "Update wizard store input.") -> FieldResult<WizardStore>  as "Updates existing wizard store."{
         let context = executor.context();
-        let url = format!("{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::WizardStore.to_url());
+        let url = context.config.url(Service::Stores).model(Model::WizardStore).build();
 
         if input.is_none() {
              return Err(FieldError::new(
@@ -1274,9 +1217,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field deleteWizardStore(&executor) -> FieldResult<WizardStore>  as "Delete existing wizard store." {
         let context = executor.context();
-        let url = format!("{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::WizardStore.to_url());
+        let url = context.config.url(Service::Stores).model(Model::WizardStore).build();
 
         context.request::<WizardStore>(Method::Delete, url, None)
             .wait()
@@ -1284,9 +1225,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field createProductComment(&executor, input: CreateModeratorProductCommentsInput as "Create Moderator Product Comment Input.") -> FieldResult<ModeratorProductComments> as "Creates new product comment." {
         let context = executor.context();
-        let url = format!("{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::ModeratorProductComment.to_url());
+        let url = context.config.url(Service::Stores).model(Model::ModeratorProductComment).build();
 
         let body: String = serde_json::to_string(&input)?.to_string();
 
@@ -1296,9 +1235,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field createStoreComment(&executor, input: CreateModeratorStoreCommentsInput as "Create Moderator Store Comment Input.") -> FieldResult<ModeratorStoreComments> as "Creates new store comment." {
         let context = executor.context();
-        let url = format!("{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::ModeratorStoreComment.to_url());
+        let url = context.config.url(Service::Stores).model(Model::ModeratorStoreComment).build();
 
         let body: String = serde_json::to_string(&input)?.to_string();
 
@@ -1308,9 +1245,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field deprecated "use createUserDeliveryAddressFull" createUserDeliveryAddress(&executor, input: NewUserDeliveryAddressInput  as "Create delivery address input.") -> FieldResult<UserDeliveryAddress> as "Creates new user delivery address." {
         let context = executor.context();
-        let url = format!("{}/{}/delivery_addresses",
-            context.config.service_url(Service::Users),
-            Model::User.to_url());
+        let url = context.config.url(Service::Users).model(Model::User).path("delivery_addresses").build();
 
         let body: String = serde_json::to_string(&input)?.to_string();
 
@@ -1320,10 +1255,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field deprecated "use updateUserDeliveryAddressFull" updateUserDeliveryAddress(&executor, input: UpdateUserDeliveryAddressInput as "Update delivery address input.") -> FieldResult<UserDeliveryAddress>  as "Updates delivery address."{
         let context = executor.context();
-        let url = format!("{}/{}/delivery_addresses/{}",
-            context.config.service_url(Service::Users),
-            Model::User.to_url(),
-            input.id.to_string());
+        let url = context.config.url(Service::Users).model(Model::User).path("delivery_addresses").segment(input.id).build();
 
         if input.is_none() {
              return Err(FieldError::new(
@@ -1340,10 +1272,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field deprecated "use deleteUserDeliveryAddressFull" deleteUserDeliveryAddress(&executor, id: i32 as "Raw id of delivery address") -> FieldResult<UserDeliveryAddress>  as "Deletes delivery address." {
         let context = executor.context();
-        let url = format!("{}/{}/delivery_addresses/{}",
-            context.config.service_url(Service::Users),
-            Model::User.to_url(),
-            id);
+        let url = context.config.url(Service::Users).model(Model::User).path("delivery_addresses").segment(id).build();
 
         context.request::<UserDeliveryAddress>(Method::Delete, url, None)
             .wait()
@@ -1351,9 +1280,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field createUserDeliveryAddressFull(&executor, input: NewUserDeliveryAddressFullInput  as "Create delivery address full input.") -> FieldResult<UserDeliveryAddress> as "Creates new user delivery address full." {
         let context = executor.context();
-        let url = format!("{}/{}/addresses",
-            context.config.service_url(Service::Delivery),
-            Model::User.to_url());
+        let url = context.config.url(Service::Delivery).model(Model::User).path("addresses").build();
 
         let body: String = serde_json::to_string(&input)?.to_string();
 
@@ -1377,10 +1304,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field deleteUserDeliveryAddressFull(&executor, id: i32 as "Raw id of delivery address") -> FieldResult<UserDeliveryAddress>  as "Deletes delivery address." {
         let context = executor.context();
-        let url = format!("{}/{}/addresses/{}",
-            context.config.service_url(Service::Delivery),
-            Model::User.to_url(),
-            id);
+        let url = context.config.url(Service::Delivery).model(Model::User).path("addresses").segment(id).build();
 
         context.request::<UserDeliveryAddress>(Method::Delete, url, None)
             .wait()
@@ -1397,10 +1321,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field updateWarehouse(&executor, input: UpdateWarehouseInput as "Update Warehouse input.") -> FieldResult<Option<GraphQLWarehouse>>  as "Updates existing Warehouse."{
         let context = executor.context();
-        let url = format!("{}/{}/by-id/{}",
-            context.config.service_url(Service::Warehouses),
-            Model::Warehouse.to_url(),
-            input.id.to_string());
+        let url = context.config.url(Service::Warehouses).model(Model::Warehouse).path("by-id").segment(input.id).build();
 
         if input.is_none() {
              return Err(FieldError::new(
@@ -1496,39 +1417,17 @@ graphql_object!(Mutation: Context |&self| {
 
     field setOrderStatusDelivery(&executor, input: OrderStatusDeliveryInput as "Order Status Delivery input.") -> FieldResult<Option<GraphQLOrder>>  as "Set Order Status Delivery."{
         let context = executor.context();
-        let slug = input.order_slug;
-        let mut order: OrderStatusDelivery = input.into();
-        if let Some(ref track_id) = order.track_id {
-            let comment = if let Some(mut comment) = order.comment {
-                comment += format!(" | Track id: {}", track_id).as_ref();
-                Some(comment)
-            } else {
-                Some(format!("Track id: {}", track_id))
-            };
-            order.comment = comment;
-        }
-        let url = format!("{}/{}/{}/set_state",
-            context.config.saga_microservice.url.clone(),
-            Model::Order.to_url(),
-            slug,
-            );
-
-        let body = serde_json::to_string(&order)?;
 
-        context.request::<Option<Order>>(Method::Post, url, Some(body))
-            .map(|res| res.map(GraphQLOrder))
-            .wait()
+        shipment::run_set_order_status_delivery_mutation(context, input)
     }
 
     field setOrderStatusCanceled(&executor, input: OrderStatusCanceledInput as "Order Status Canceled input.") -> FieldResult<Option<GraphQLOrder>>  as "Set Order Status Canceled."{
         let context = executor.context();
         let slug = input.order_slug;
-        let order: OrderStatusCanceled = input.into();
-        let url = format!("{}/{}/{}/set_state",
-            context.config.saga_microservice.url.clone(),
-            Model::Order.to_url(),
-            slug,
-            );
+        let committer_role = input.committer_role.clone();
+        let mut order: OrderStatusCanceled = input.into();
+        order.committer_role = TransitionChecker::new(context).check(OrderSlug(slug), &order.state, committer_role)?;
+        let url = context.config.saga_url().model(Model::Order).segment(slug).path("set_state").build();
 
         let body = serde_json::to_string(&order)?;
 
@@ -1540,12 +1439,10 @@ graphql_object!(Mutation: Context |&self| {
     field setOrderStatusComplete(&executor, input: OrderStatusCompleteInput as "Order Status Complete input.") -> FieldResult<Option<GraphQLOrder>>  as "Set Order Status Complete."{
         let context = executor.context();
         let slug = input.order_slug;
-        let order: OrderStatusComplete = input.into();
-        let url = format!("{}/{}/{}/set_state",
-            context.config.saga_microservice.url.clone(),
-            Model::Order.to_url(),
-            slug,
-            );
+        let committer_role = input.committer_role.clone();
+        let mut order: OrderStatusComplete = input.into();
+        order.committer_role = TransitionChecker::new(context).check(OrderSlug(slug), &order.state, committer_role)?;
+        let url = context.config.saga_url().model(Model::Order).segment(slug).path("set_state").build();
 
         let body = serde_json::to_string(&order)?;
 
@@ -1554,11 +1451,33 @@ graphql_object!(Mutation: Context |&self| {
             .wait()
     }
 
+    field setOrderStatusDeliveryBatch(&executor, input: OrderStatusDeliveryBatchInput as "Order Status Delivery batch input.") -> FieldResult<OrderStateBatchOutput> as "Set Order Status Delivery for several orders." {
+        let context = executor.context();
+
+        order_batch::run_set_order_status_delivery_batch(context, input)
+    }
+
+    field setOrderStatusCanceledBatch(&executor, input: OrderStatusBatchInput as "Order Status batch input.") -> FieldResult<OrderStateBatchOutput> as "Set Order Status Canceled for several orders." {
+        let context = executor.context();
+
+        order_batch::run_set_order_status_canceled_batch(context, input)
+    }
+
+    field setOrderStatusCompleteBatch(&executor, input: OrderStatusBatchInput as "Order Status batch input.") -> FieldResult<OrderStateBatchOutput> as "Set Order Status Complete for several orders." {
+        let context = executor.context();
+
+        order_batch::run_set_order_status_complete_batch(context, input)
+    }
+
+    field exportStoreOrders(&executor, input: ExportStoreOrdersInput as "Export store orders input.") -> FieldResult<OrdersExport> as "Link to download store orders matching search options as CSV or XLSX file." {
+        let context = executor.context();
+
+        order_export::run_export_store_orders(context, input)
+    }
+
     field recalcInvoiceAmount(&executor, id: String as "Invoice id") -> FieldResult<Invoice> as "Invoice" {
         let context = executor.context();
-        let url = format!("{}/invoices/by-id/{}/recalc",
-            context.config.service_url(Service::Billing),
-            id);
+        let url = context.config.url(Service::Billing).path("invoices/by-id").segment(id).path("recalc").build();
 
         context.request::<Invoice>(Method::Post, url, None)
             .wait()
@@ -1568,10 +1487,7 @@ graphql_object!(Mutation: Context |&self| {
         input: EmailTemplateInput as "Update EmailTemplate input.") -> FieldResult<String> as "Update email messages template" {
         let context = executor.context();
 
-        let url = format!(
-            "{}/templates/{}",
-            &context.config.service_url(Service::Notifications),
-            input.variant);
+        let url = context.config.url(Service::Notifications).path("templates").segment(&input.variant).build();
 
         let body: String = input.data;
 
@@ -1629,7 +1545,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field createCompany(&executor, input: NewCompanyInput as "Create company input.") -> FieldResult<Company> as "Creates new company." {
         let context = executor.context();
-        let countries_url = format!("{}/{}/flatten", context.config.service_url(Service::Delivery), Model::Country.to_url());
+        let countries_url = context.config.url(Service::Delivery).model(Model::Country).path("flatten").build();
         let all_countries = context.request::<Vec<Country>>(Method::Get, countries_url, None).wait()?;
         if !is_all_codes_valid(&all_countries, &input.deliveries_from) {
             return Err(FieldError::new(
@@ -1638,9 +1554,7 @@ graphql_object!(Mutation: Context |&self| {
             ));
         }
 
-        let url = format!("{}/{}",
-            context.config.service_url(Service::Delivery),
-            Model::Company.to_url());
+        let url = context.config.url(Service::Delivery).model(Model::Company).build();
 
         let body: String = serde_json::to_string(&input)?.to_string();
 
@@ -1661,7 +1575,7 @@ graphql_object!(Mutation: Context |&self| {
         }
 
         if let Some(deliveries_from) = &input.deliveries_from {
-            let countries_url = format!("{}/{}/flatten", context.config.service_url(Service::Delivery), Model::Country.to_url());
+            let countries_url = context.config.url(Service::Delivery).model(Model::Country).path("flatten").build();
             let all_countries = context.request::<Vec<Country>>(Method::Get, countries_url, None).wait()?;
             if !is_all_codes_valid(&all_countries, deliveries_from) {
                 return Err(FieldError::new(
@@ -1679,10 +1593,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field deleteCompany(&executor, id: i32 as "Raw id of company") -> FieldResult<Company>  as "Deletes company." {
         let context = executor.context();
-        let url = format!("{}/{}/{}",
-            context.config.service_url(Service::Delivery),
-            Model::Company.to_url(),
-            id);
+        let url = context.config.url(Service::Delivery).model(Model::Company).segment(id).build();
 
         context.request::<Company>(Method::Delete, url, None)
             .wait()
@@ -1690,7 +1601,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field createPackage(&executor, input: NewPackagesInput as "Create package input.") -> FieldResult<Packages> as "Creates new package." {
         let context = executor.context();
-        let countries_url = format!("{}/{}/flatten", context.config.service_url(Service::Delivery), Model::Country.to_url());
+        let countries_url = context.config.url(Service::Delivery).model(Model::Country).path("flatten").build();
         let all_countries = context.request::<Vec<Country>>(Method::Get, countries_url, None).wait()?;
         if !is_all_codes_valid(&all_countries, &input.deliveries_to) {
             return Err(FieldError::new(
@@ -1699,9 +1610,7 @@ graphql_object!(Mutation: Context |&self| {
             ));
         }
 
-        let url = format!("{}/{}",
-            context.config.service_url(Service::Delivery),
-            Model::Package.to_url());
+        let url = context.config.url(Service::Delivery).model(Model::Package).build();
 
         let body: String = serde_json::to_string(&input)?.to_string();
 
@@ -1722,7 +1631,7 @@ graphql_object!(Mutation: Context |&self| {
         }
 
         if let Some(deliveries_to) = &input.deliveries_to {
-            let countries_url = format!("{}/{}/flatten", context.config.service_url(Service::Delivery), Model::Country.to_url());
+            let countries_url = context.config.url(Service::Delivery).model(Model::Country).path("flatten").build();
             let all_countries = context.request::<Vec<Country>>(Method::Get, countries_url, None).wait()?;
             if !is_all_codes_valid(&all_countries, deliveries_to) {
                 return Err(FieldError::new(
@@ -1740,10 +1649,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field deletePackage(&executor, id: i32 as "Raw id of package") -> FieldResult<Packages>  as "Deletes package." {
         let context = executor.context();
-        let url = format!("{}/{}/{}",
-            context.config.service_url(Service::Delivery),
-            Model::Package.to_url(),
-            id);
+        let url = context.config.url(Service::Delivery).model(Model::Package).segment(id).build();
 
         context.request::<Packages>(Method::Delete, url, None)
             .wait()
@@ -1754,9 +1660,7 @@ graphql_object!(Mutation: Context |&self| {
         input: NewCompaniesPackagesInput as "Create company_package input.",
     ) -> FieldResult<CompaniesPackages> as "Creates new company_package." {
         let context = executor.context();
-        let url = format!("{}/{}",
-            context.config.service_url(Service::Delivery),
-            Model::CompanyPackage.to_url());
+        let url = context.config.url(Service::Delivery).model(Model::CompanyPackage).build();
 
         let body: String = serde_json::to_string(&NewCompaniesPackagesPayload::from(input))?.to_string();
 
@@ -1766,12 +1670,14 @@ graphql_object!(Mutation: Context |&self| {
 
     field deleteCompanyPackage(&executor, company_id: i32 as "Raw id of company", package_id: i32 as "Raw id of package") -> FieldResult<CompaniesPackages>  as "Deletes company_package." {
         let context = executor.context();
-        let url = format!("{}/{}/{}/{}/{}",
-            context.config.service_url(Service::Delivery),
-            Model::Company.to_url(),
-            company_id,
-            Model::Package.to_url(),
-            package_id);
+        let url = context
+            .config
+            .url(Service::Delivery)
+            .model(Model::Company)
+            .segment(company_id)
+            .model(Model::Package)
+            .segment(package_id)
+            .build();
 
         context.request::<CompaniesPackages>(Method::Delete, url, None)
             .wait()
@@ -1779,11 +1685,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field createCoupon(&executor, input: NewCouponInput as "Create coupon input") -> FieldResult<Coupon> as "Creates new coupon." {
         let context = executor.context();
-        let url = format!(
-            "{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::Coupon.to_url()
-        );
+        let url = context.config.url(Service::Stores).model(Model::Coupon).build();
 
         let body: String = serde_json::to_string(&NewCoupon::from(input))?.to_string();
 
@@ -1811,12 +1713,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field deleteCoupon(&executor, coupon_id: i32 as "Delete coupon by raw id") -> FieldResult<Coupon> as "Delete exists coupon." {
         let context = executor.context();
-        let url = format!(
-            "{}/{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::Coupon.to_url(),
-            coupon_id
-        );
+        let url = context.config.url(Service::Stores).model(Model::Coupon).segment(coupon_id).build();
 
         context.request::<Coupon>(Method::Delete, url, None)
             .wait()
@@ -1824,14 +1721,14 @@ graphql_object!(Mutation: Context |&self| {
 
     field addBaseProductToCoupon(&executor, input: ChangeBaseProductsInCoupon as "Add base product input") ->  FieldResult<Mock> as "Add base product to coupon." {
         let context = executor.context();
-        let url = format!(
-            "{}/{}/{}/{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::Coupon.to_url(),
-            input.raw_id,
-            Model::BaseProduct.to_url(),
-            input.raw_base_product_id,
-        );
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::Coupon)
+            .segment(input.raw_id)
+            .model(Model::BaseProduct)
+            .segment(input.raw_base_product_id)
+            .build();
 
         context.request::<CouponScopeBaseProducts>(Method::Post, url, None)
             .wait()?;
@@ -1840,14 +1737,14 @@ graphql_object!(Mutation: Context |&self| {
 
     field deleteBaseProductFromCoupon(&executor, input: ChangeBaseProductsInCoupon as "Delete base product input") ->  FieldResult<Mock> as "Delete base product from coupon." {
         let context = executor.context();
-        let url = format!(
-            "{}/{}/{}/{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::Coupon.to_url(),
-            input.raw_id,
-            Model::BaseProduct.to_url(),
-            input.raw_base_product_id,
-        );
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::Coupon)
+            .segment(input.raw_id)
+            .model(Model::BaseProduct)
+            .segment(input.raw_base_product_id)
+            .build();
 
         context.request::<CouponScopeBaseProducts>(Method::Delete, url, None)
             .wait()?;
@@ -1866,12 +1763,13 @@ graphql_object!(Mutation: Context |&self| {
     ) -> FieldResult<Vec<ShippingRates>> as "Replace shipping rates for a single 'from' country for a particular company-package" {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}/{}/rates",
-            &context.config.service_url(Service::Delivery),
-            Model::CompanyPackage.to_url(),
-            input.company_package_id,
-        );
+        let url = context
+            .config
+            .url(Service::Delivery)
+            .model(Model::CompanyPackage)
+            .segment(input.company_package_id)
+            .path("rates")
+            .build();
 
         let body = serde_json::to_string(&ReplaceShippingRatesPayload::from(input))?;
 
@@ -1879,9 +1777,7 @@ graphql_object!(Mutation: Context |&self| {
     }
     field refreshJWT(&executor) -> FieldResult<String> as "Refresh JWT Token." {
         let context = executor.context();
-        let url = format!("{}/{}/refresh",
-            context.config.service_url(Service::Users),
-            Model::JWT.to_url());
+        let url = context.config.url(Service::Users).model(Model::JWT).path("refresh").build();
 
         if let Some(ref payload) = context.user {
             let body: String = serde_json::to_string(payload)?.to_string();
@@ -1899,9 +1795,7 @@ graphql_object!(Mutation: Context |&self| {
 
     field revokeJWT(&executor) -> FieldResult<String> as "Revoke JWT Tokens." {
         let context = executor.context();
-        let url = format!("{}/{}/revoke",
-            context.config.service_url(Service::Users),
-            Model::JWT.to_url());
+        let url = context.config.url(Service::Users).model(Model::JWT).path("revoke").build();
 
         if let Some(ref payload) = context.user {
             let body: String = serde_json::to_string(payload)?.to_string();
@@ -1923,6 +1817,12 @@ graphql_object!(Mutation: Context |&self| {
         order::run_confirm_order_mutation(context, input)
     }
 
+    field confirmOrders(&executor, input: OrdersConfirmedInput as "Confirm orders input object") -> FieldResult<OrderStateBatchOutput> as "Confirm several orders for seller" {
+        let context = executor.context();
+
+        order_batch::run_confirm_orders_mutation(context, input)
+    }
+
     field createCustomerWithSource(&executor,
                             input: CreateCustomerWithSourceInput as "Creates Customer object in Stripe",) -> FieldResult<Customer> as "Creates Customer object" {
         let context = executor.context();
@@ -1952,6 +1852,12 @@ graphql_object!(Mutation: Context |&self| {
         order::run_set_paid_to_seller_order_state_mutation(context, input).map(|_| Mock)
     }
 
+    field refundOrder(&executor, input: RefundOrderInput as "Refund order input.") -> FieldResult<Refund> as "Refunds the order fully or partially to the buyer." {
+        let context = executor.context();
+
+        refund::run_refund_order_mutation(context, input)
+    }
+
     field createInternationalBillingInfo(&executor, input: NewInternationalBillingInfoInput as "Create international billing info for a store")
     -> FieldResult<InternationalBillingInfo> as "Created international billing info" {
         let context = executor.context();
@@ -2004,13 +1910,127 @@ graphql_object!(Mutation: Context |&self| {
             .create_payment_intent_fee(FeeId::new(input.fee_id))
     }
 
-    field createDispute(
+    field deprecated "use openDispute" createDispute(
         &executor,
         input: CreateDisputeInput as "Open dispute by order.",
     ) -> FieldResult<Mock> as "" {
         let context = executor.context();
 
-        order::run_create_dispute_mutation(context, input).map(|_| Mock)
+        dispute::run_open_dispute_mutation(context, input.into()).map(|_| Mock)
+    }
+
+    field openDispute(&executor, input: OpenDisputeInput as "Open dispute input.") -> FieldResult<Dispute> as "Opens dispute by the order for the customer." {
+        let context = executor.context();
+
+        dispute::run_open_dispute_mutation(context, input)
+    }
+
+    field addDisputeMessage(&executor, input: AddDisputeMessageInput as "Add dispute message input.") -> FieldResult<Dispute> as "Adds message to the dispute thread." {
+        let context = executor.context();
+
+        dispute::run_add_dispute_message_mutation(context, input)
+    }
+
+    field resolveDispute(&executor, input: ResolveDisputeInput as "Resolve dispute input.") -> FieldResult<Dispute> as "Resolves dispute by moderator, refunding or completing the order." {
+        let context = executor.context();
+
+        dispute::run_resolve_dispute_mutation(context, input)
+    }
+
+    field openReturn(&executor, input: OpenReturnInput as "Open return input.") -> FieldResult<OrderReturn> as "Opens return of delivered orders for the customer." {
+        let context = executor.context();
+
+        order_return::run_open_return_mutation(context, input)
+    }
+
+    field acceptReturn(&executor, input: AcceptReturnInput as "Accept return input.") -> FieldResult<OrderReturn> as "Accepts return by the seller with return shipping instructions." {
+        let context = executor.context();
+
+        order_return::run_accept_return_mutation(context, input)
+    }
+
+    field rejectReturn(&executor, input: RejectReturnInput as "Reject return input.") -> FieldResult<OrderReturn> as "Rejects return by the seller." {
+        let context = executor.context();
+
+        order_return::run_reject_return_mutation(context, input)
+    }
+
+    field setReturnTrackId(&executor, input: SetReturnTrackIdInput as "Set return track id input.") -> FieldResult<OrderReturn> as "Sets track id of the return shipment by the customer." {
+        let context = executor.context();
+
+        order_return::run_set_return_track_id_mutation(context, input)
+    }
+
+    field inspectReturn(&executor, input: InspectReturnInput as "Inspect return input.") -> FieldResult<OrderReturn> as "Inspects returned items by the seller, refunding the customer if they pass." {
+        let context = executor.context();
+
+        order_return::run_inspect_return_mutation(context, input)
+    }
+
+    field addToWishlist(&executor, input: AddToWishlistInput as "Add to wishlist input.") -> FieldResult<WishlistEntry> as "Saves product to the wishlist of the user or the session." {
+        let context = executor.context();
+
+        wishlist_module::run_add_to_wishlist_mutation(context, input)
+    }
+
+    field removeFromWishlist(&executor, input: RemoveFromWishlistInput as "Remove from wishlist input.") -> FieldResult<Option<WishlistEntry>> as "Removes product from the wishlist of the user or the session." {
+        let context = executor.context();
+
+        wishlist_module::run_remove_from_wishlist_mutation(context, input)
+    }
+
+    field createReview(&executor, input: CreateReviewInput as "Create review input.") -> FieldResult<Review> as "Creates review of the product of the complete order." {
+        let context = executor.context();
+
+        review::run_create_review_mutation(context, input)
+    }
+
+    field replyToReview(&executor, input: ReplyToReviewInput as "Reply to review input.") -> FieldResult<Review> as "Replies to the review by the seller." {
+        let context = executor.context();
+
+        review::run_reply_to_review_mutation(context, input)
+    }
+
+    field setReviewHidden(&executor, input: SetReviewHiddenInput as "Hide or show review input.") -> FieldResult<Review> as "Hides or shows the review by moderator." {
+        let context = executor.context();
+
+        review::run_set_review_hidden_mutation(context, input)
+    }
+
+    field followStore(&executor, input: FollowStoreInput as "Follow store input.") -> FieldResult<Store> as "Follows the store to see its news in the feed." {
+        let context = executor.context();
+
+        store_follow::run_follow_store_mutation(context, input)
+    }
+
+    field unfollowStore(&executor, input: UnfollowStoreInput as "Unfollow store input.") -> FieldResult<Option<Store>> as "Unfollows the store." {
+        let context = executor.context();
+
+        store_follow::run_unfollow_store_mutation(context, input)
+    }
+
+    field clearViewHistory(&executor, input: ClearViewHistoryInput as "Clear recently viewed products input.") -> FieldResult<ViewHistorySettings> as "Clears recently viewed products of the user or the session." {
+        let context = executor.context();
+
+        view_history::run_clear_view_history_mutation(context, input)
+    }
+
+    field setViewHistoryEnabled(&executor, input: SetViewHistoryEnabledInput as "Turn recording of viewed products on or off input.") -> FieldResult<ViewHistorySettings> as "Turns recording of viewed products on or off, turning off clears the history." {
+        let context = executor.context();
+
+        view_history::run_set_view_history_enabled_mutation(context, input)
+    }
+
+    field saveSearch(&executor, input: SaveSearchInput as "Save search input.") -> FieldResult<SavedSearch> as "Saves products search to be notified about new products found by it." {
+        let context = executor.context();
+
+        saved_search::run_save_search_mutation(context, input)
+    }
+
+    field deleteSavedSearch(&executor, input: DeleteSavedSearchInput as "Delete saved search input.") -> FieldResult<SavedSearch> as "Deletes saved search of the user." {
+        let context = executor.context();
+
+        saved_search::run_delete_saved_search_mutation(context, input)
     }
 
     field payOutCryptoToSeller(
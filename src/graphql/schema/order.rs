@@ -1,7 +1,6 @@
 //! File containing PageInfo object of graphql schema
 use std::cmp;
-use std::collections::HashMap;
-use std::str::FromStr;
+use std::collections::{HashMap, HashSet};
 
 use futures::Future;
 use hyper::Method;
@@ -16,7 +15,7 @@ use stq_api::{
 use stq_routes::{model::Model, service::Service};
 use stq_static_resources::CurrencyType;
 use stq_static_resources::{Currency, OrderState};
-use stq_types::{CouponId, OrderId, OrderIdentifier, ProductSellerPrice};
+use stq_types::{CartItem, CouponId, OrderId, OrderIdentifier, ProductSellerPrice};
 
 use super::*;
 use errors::into_graphql;
@@ -25,8 +24,10 @@ use graphql::microservice::requests::*;
 use graphql::models::*;
 use graphql::schema::base_product as base_product_module;
 use graphql::schema::cart as cart_module;
+use graphql::schema::checkout_quote;
 use graphql::schema::coupon::try_get_coupon;
 use graphql::schema::coupon::*;
+use graphql::schema::order_transition::TransitionChecker;
 use graphql::schema::product as product_module;
 use graphql::schema::user::get_user_by_id;
 
@@ -43,16 +44,38 @@ graphql_object!(GraphQLOrder: Context as "Order" |&self| {
         &self.0.state
     }
 
+    field dispute(&executor) -> FieldResult<Option<Dispute>> as "Dispute opened by the customer" {
+        executor.context().get_orders_microservice().get_dispute_by_order(self.0.id)
+    }
+
+    field shipments(&executor) -> FieldResult<Vec<Shipment>> as "Shipments of the order with tracking events" {
+        executor.context().get_delivery_microservice().shipments_by_order_id(self.0.id)
+    }
+
+    field returns(&executor) -> FieldResult<Vec<OrderReturn>> as "Return requests including the order" {
+        let context = executor.context();
+        let search = OrderReturnSearch {
+            order_id: Some(self.0.id),
+            ..Default::default()
+        };
+
+        context
+            .get_orders_microservice()
+            .search_returns(0, context.config.gateway.records_limit as i32, search)
+            .map(|results| results.returns)
+    }
+
+    field allowed_transitions(&executor) -> FieldResult<Vec<OrderStateTransition>> as "State transitions available for the current user" {
+        TransitionChecker::new(executor.context()).allowed_transitions(&self.0)
+    }
+
     field customer_id() -> &i32 as "Customer int id"{
         &self.0.customer.0
     }
 
     field customer(&executor) -> FieldResult<Option<User>> as "Customer" {
         let context = executor.context();
-        let url = format!("{}/{}/{}",
-            context.config.service_url(Service::Users),
-            Model::User.to_url(),
-            self.0.customer);
+        let url = context.config.url(Service::Users).model(Model::User).segment(self.0.customer).build();
 
         context.request::<Option<User>>(Method::Get, url, None)
             .wait()
@@ -64,10 +87,7 @@ graphql_object!(GraphQLOrder: Context as "Order" |&self| {
 
     field deprecated "use current_product" product(&executor) -> FieldResult<Option<Product>> as "Product" {
         let context = executor.context();
-        let url = format!("{}/{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::Product.to_url(),
-            self.0.product);
+        let url = context.config.url(Service::Stores).model(Model::Product).segment(self.0.product).build();
 
         context.request::<Option<Product>>(Method::Get, url, None)
             .wait()
@@ -84,10 +104,7 @@ graphql_object!(GraphQLOrder: Context as "Order" |&self| {
 
     field store(&executor) -> FieldResult<Option<Store>> as "Store" {
         let context = executor.context();
-        let url = format!("{}/{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::Store.to_url(),
-            self.0.store);
+        let url = context.config.url(Service::Stores).model(Model::Store).segment(self.0.store).build();
 
         context.request::<Option<Store>>(Method::Get, url, None)
             .wait()
@@ -105,14 +122,12 @@ graphql_object!(GraphQLOrder: Context as "Order" |&self| {
         &self.0.currency
     }
 
-    field subtotal() -> f64 as "Subtotal" {
+    field deprecated "use subtotalV2" subtotal() -> FieldResult<f64> as "Subtotal" {
+        calculate_order_subtotal(&self.0).map(|money| money.to_f64())
+    }
 
-        // product_discount - here is the already calculated discount amount
-        if let Some(discount) = self.0.product_discount.map(|c| c.0).filter(|discount| *discount > ZERO_DISCOUNT) {
-            (self.0.price.0 * f64::from(self.0.quantity.0)) - discount
-        } else {
-            self.0.price.0 * f64::from(self.0.quantity.0)
-        }
+    field subtotal_v2() -> FieldResult<Money> as "Subtotal" {
+        calculate_order_subtotal(&self.0)
     }
 
     field coupon(&executor) -> FieldResult<Option<Coupon>> as "Coupon added user" {
@@ -129,16 +144,24 @@ graphql_object!(GraphQLOrder: Context as "Order" |&self| {
         &self.0.coupon_percent
     }
 
-    field coupon_discount() -> Option<f64> as "Coupon discount" {
-        self.0.coupon_discount.map(|c| c.0)
+    field deprecated "use couponDiscountV2" coupon_discount() -> Option<f64> as "Coupon discount" {
+        self.0.coupon_discount.map(|c| Money::from_f64(c.0, self.0.currency).to_f64())
+    }
+
+    field coupon_discount_v2() -> Option<Money> as "Coupon discount" {
+        self.0.coupon_discount.map(|c| Money::from_f64(c.0, self.0.currency).round())
     }
 
     field product_discount() -> Option<f64> as "Product discount" {
         self.0.product_discount.map(|c| c.0)
     }
 
-    field total_amount() -> f64 as "Total amount" {
-        self.0.total_amount.0
+    field deprecated "use totalAmountV2" total_amount() -> f64 as "Total amount" {
+        Money::from_f64(self.0.total_amount.0, self.0.currency).to_f64()
+    }
+
+    field total_amount_v2() -> Money as "Total amount" {
+        Money::from_f64(self.0.total_amount.0, self.0.currency).round()
     }
 
     field slug() -> &i32 as "Slug" {
@@ -153,8 +176,12 @@ graphql_object!(GraphQLOrder: Context as "Order" |&self| {
         &self.0.delivery_company
     }
 
-    field delivery_price() -> &f64 as "Delivery price" {
-        &self.0.delivery_price
+    field deprecated "use deliveryPriceV2" delivery_price() -> f64 as "Delivery price" {
+        Money::from_f64(self.0.delivery_price, self.0.currency).to_f64()
+    }
+
+    field delivery_price_v2() -> Money as "Delivery price" {
+        Money::from_f64(self.0.delivery_price, self.0.currency).round()
     }
 
     field deprecated "use deliveryCompany and deliveryPrice" company_package_id() -> Option<i32> as "Selected package raw id" {
@@ -210,11 +237,7 @@ graphql_object!(GraphQLOrder: Context as "Order" |&self| {
 
         let context = executor.context();
 
-        let offset = after
-            .and_then(|id|{
-                i32::from_str(&id).map(|i| i + 1).ok()
-            })
-            .unwrap_or_default();
+        let offset = Cursor::offset_after(after)?;
 
         let records_limit = context.config.gateway.records_limit;
         let count = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
@@ -229,20 +252,21 @@ graphql_object!(GraphQLOrder: Context as "Order" |&self| {
                     .skip(offset as usize)
                     .take(count as usize)
                     .enumerate()
-                    .map(|(i, item)| Edge::new(juniper::ID::from((i as i32 + offset).to_string()), OrderHistoryItem(item)))
+                    .map(|(i, item)| Edge::new(Cursor::new(i as i32 + offset).encode(), OrderHistoryItem(item)))
                     .collect();
                 let has_next_page = item_edges.len() as i32 == count + 1;
                 if has_next_page {
                     item_edges.pop();
                 };
-                let has_previous_page = true;
+                let has_previous_page = offset > 0;
                 let start_cursor =  item_edges.get(0).map(|e| e.cursor.clone());
                 let end_cursor = item_edges.iter().last().map(|e| e.cursor.clone());
                 let page_info = PageInfo {
                     has_next_page,
                     has_previous_page,
                     start_cursor,
-                    end_cursor};
+                    end_cursor,
+                    total_count: None};
                 Connection::new(item_edges, page_info)
             })
             .map(Some)
@@ -250,10 +274,7 @@ graphql_object!(GraphQLOrder: Context as "Order" |&self| {
 
     field allowed_statuses(&executor) -> FieldResult<Vec<OrderState>> as "Allowed statuses" {
         let context = executor.context();
-        let url = format!("{}/{}/{}/allowed_statuses",
-            context.config.service_url(Service::Orders),
-            Model::Order.to_url(),
-            self.0.id);
+        let url = context.config.url(Service::Orders).model(Model::Order).segment(self.0.id).path("allowed_statuses").build();
 
         context.request::<Vec<OrderState>>(Method::Get, url, None)
             .wait()
@@ -261,9 +282,7 @@ graphql_object!(GraphQLOrder: Context as "Order" |&self| {
 
     field invoice(&executor) -> FieldResult<Option<Invoice>> as "Invoice" {
         let context = executor.context();
-        let url = format!("{}/invoices/by-order-id/{}",
-            context.config.service_url(Service::Billing),
-            self.0.id);
+        let url = context.config.url(Service::Billing).path("invoices/by-order-id").segment(self.0.id).build();
 
         context.request::<Option<Invoice>>(Method::Get, url, None)
             .wait()
@@ -462,10 +481,7 @@ graphql_object!(OrderProduct: Context as "OrderProduct" |&self| {
 
     field attributes(&executor) -> FieldResult<Option<Vec<ProdAttrValue>>> as "Variants" {
        let context = executor.context();
-        let url = format!("{}/{}/{}/attributes",
-            context.config.service_url(Service::Stores),
-            Model::Product.to_url(),
-            self.0.id);
+        let url = context.config.url(Service::Stores).model(Model::Product).segment(self.0.id).path("attributes").build();
 
         context.request::<Vec<ProdAttrValue>>(Method::Get, url, None)
             .wait()
@@ -593,6 +609,7 @@ pub fn run_create_orders_mutation_v1(context: &Context, input: CreateOrderInput)
         product_info,
         uuid: input.uuid,
         currency_type: Some(input.currency.currency_type()),
+        quoted_pricing: None,
     };
 
     if create_order.currency.currency_type() == CurrencyType::Fiat {
@@ -622,6 +639,55 @@ pub fn run_create_orders_mutation(context: &Context, input: CreateOrderInputV2)
         .sync()
         .map_err(into_graphql)?;
 
+    let (checkout, quoted_pricing) = match input.quote_token.take() {
+        Some(quote_token) => {
+            let quote = checkout_quote::decode_quote(context, &quote_token, CheckoutQuoteKind::Cart, user.user_id, input.currency)?;
+            checkout_quote::validate_cart_quote(&quote, &current_cart, &input.user_country_code)?;
+            context.freeze_exchange_rates(quote.exchange_rates.clone());
+            let quoted_pricing = quote.pricing();
+            (checkout_quote::frozen_cart_checkout(quote), Some(quoted_pricing))
+        }
+        None => (prepare_cart_checkout(context, &current_cart, input.user_country_code)?, None),
+    };
+
+    if checkout.prices.len() == 0 {
+        return Err(FieldError::new(
+            "Could not create orders for empty cart.",
+            graphql_value!({ "code": 100, "details": { "There is no products, selected in cart." }}),
+        ));
+    }
+
+    let delivery_info = cart_product::get_delivery_info(checkout.packages);
+    let customer = get_user_by_id(context, user.user_id)?;
+
+    let product_info = cart_product::get_product_info(context, &current_cart)?;
+
+    let create_order = CreateOrder {
+        customer_id: user.user_id,
+        address: input.address_full,
+        receiver_name: input.receiver_name,
+        receiver_phone: input.receiver_phone,
+        receiver_email: customer.email,
+        prices: checkout.prices,
+        currency: input.currency,
+        coupons: checkout.coupons,
+        delivery_info,
+        product_info,
+        uuid: input.uuid,
+        currency_type: Some(input.currency.currency_type()),
+        quoted_pricing,
+    };
+
+    if create_order.currency.currency_type() == CurrencyType::Fiat {
+        validate_products_fiat(create_order.prices.values())?;
+    }
+
+    let saga = context.get_saga_microservice();
+    saga.create_orders(create_order)
+}
+
+/// Collects current seller prices, coupons and delivery packages of the cart
+pub fn prepare_cart_checkout(context: &Context, current_cart: &HashSet<CartItem>, user_country_code: String) -> FieldResult<CartCheckout> {
     if let Some(cart_item) = current_cart.iter().find(|p| p.delivery_method_id.is_none()) {
         return Err(FieldError::new(
             "Not select delivery package.",
@@ -643,19 +709,12 @@ pub fn run_create_orders_mutation(context: &Context, input: CreateOrderInputV2)
         .map(|p| product_module::get_seller_price(context, p.product_id).and_then(|seller_price| Ok((p.product_id, seller_price))))
         .collect::<FieldResult<CartProductWithPriceHash>>()?;
 
-    if products_with_prices.len() == 0 {
-        return Err(FieldError::new(
-            "Could not create orders for empty cart.",
-            graphql_value!({ "code": 100, "details": { "There is no products, selected in cart." }}),
-        ));
-    }
-
     let coupons_info = coupons_info
         .into_iter()
         .map(|coupon| (coupon.id, coupon))
         .collect::<HashMap<CouponId, Coupon>>();
 
-    let selected_packages = cart_product::get_selected_packages(context, &current_cart, Some(input.user_country_code))?;
+    let selected_packages = cart_product::get_selected_packages(context, current_cart, Some(user_country_code))?;
 
     // validate packages
     for (item, package) in selected_packages.iter() {
@@ -666,46 +725,52 @@ pub fn run_create_orders_mutation(context: &Context, input: CreateOrderInputV2)
         .into_iter()
         .map(|(item, value)| (item.product_id, value))
         .collect();
-    let delivery_info = cart_product::get_delivery_info(packages);
-    let customer = get_user_by_id(context, user.user_id)?;
-
-    let product_info = cart_product::get_product_info(context, &current_cart)?;
 
-    let create_order = CreateOrder {
-        customer_id: user.user_id,
-        address: input.address_full,
-        receiver_name: input.receiver_name,
-        receiver_phone: input.receiver_phone,
-        receiver_email: customer.email,
+    Ok(CartCheckout {
         prices: products_with_prices,
-        currency: input.currency,
         coupons: coupons_info,
-        delivery_info,
-        product_info,
-        uuid: input.uuid,
-        currency_type: Some(input.currency.currency_type()),
-    };
-
-    if create_order.currency.currency_type() == CurrencyType::Fiat {
-        validate_products_fiat(create_order.prices.values())?;
-    }
-
-    let saga = context.get_saga_microservice();
-    saga.create_orders(create_order)
+        packages,
+    })
 }
 
-pub fn try_get_order(context: &Context, order_id: OrderIdentifier) -> FieldResult<Option<GraphQLOrder>> {
-    let order_route = match order_id {
-        OrderIdentifier::Id(id) => format!("by-id/{}", id),
-        OrderIdentifier::Slug(slug) => format!("by-slug/{}", slug),
+/// Page of the found orders, paged either by page number or by relay cursors
+pub fn orders_connection(
+    orders: Vec<GraphQLOrder>,
+    page: PageRequest,
+    search_term_options: SearchOrderOption,
+) -> Connection<GraphQLOrder, PageInfoOrdersSearch> {
+    let total_count = orders.len() as i32;
+    let page = page.with_total_count(total_count);
+    let page_items_count = cmp::max(page.count, 1);
+
+    let orders = orders
+        .into_iter()
+        .skip(page.offset as usize)
+        .take(page.fetch_count() as usize)
+        .collect();
+    let (orders_edges, page_info) = page.edges(orders, |order| Some(order.0.slug.0));
+
+    let page_info = PageInfoOrdersSearch {
+        total_pages: (total_count + page_items_count - 1) / page_items_count,
+        total_count,
+        current_page: page.offset / page_items_count + 1,
+        page_items_count,
+        search_term_options,
+        has_next_page: page_info.has_next_page,
+        has_previous_page: page_info.has_previous_page,
+        start_cursor: page_info.start_cursor,
+        end_cursor: page_info.end_cursor,
     };
+    Connection::new(orders_edges, page_info)
+}
 
-    let url = format!(
-        "{}/{}/{}",
-        context.config.service_url(Service::Orders),
-        Model::Order.to_url(),
-        order_route
-    );
+pub fn try_get_order(context: &Context, order_id: OrderIdentifier) -> FieldResult<Option<GraphQLOrder>> {
+    let orders_url = context.config.url(Service::Orders).model(Model::Order);
+    let url = match order_id {
+        OrderIdentifier::Id(id) => orders_url.path("by-id").segment(id),
+        OrderIdentifier::Slug(slug) => orders_url.path("by-slug").segment(slug),
+    }
+    .build();
 
     context
         .request::<Option<Order>>(Method::Get, url, None)
@@ -727,6 +792,17 @@ pub fn get_order(context: &Context, order_id: OrderIdentifier) -> FieldResult<Gr
     })
 }
 
+pub fn calculate_order_subtotal(order: &Order) -> FieldResult<Money> {
+    let price = Money::from_f64(order.price.0, order.currency).times(order.quantity);
+
+    // product_discount - here is the already calculated discount amount
+    if let Some(discount) = order.product_discount.map(|c| c.0).filter(|discount| *discount > ZERO_DISCOUNT) {
+        price.checked_sub(Money::from_f64(discount, order.currency)).map(|money| money.round())
+    } else {
+        Ok(price.round())
+    }
+}
+
 pub fn validate_products_fiat<'a>(products: impl Iterator<Item = &'a ProductSellerPrice>) -> FieldResult<()> {
     let mut currencies = products.map(|p| p.currency);
 
@@ -751,8 +827,17 @@ pub fn validate_products_fiat<'a>(products: impl Iterator<Item = &'a ProductSell
     Ok(())
 }
 
+/// Appends track id to the comment of the delivery state transition
+pub fn delivery_comment(comment: Option<String>, track_id: Option<&String>) -> Option<String> {
+    match (comment, track_id) {
+        (Some(comment), Some(track_id)) => Some(format!("{} | Track id: {}", comment, track_id)),
+        (None, Some(track_id)) => Some(format!("Track id: {}", track_id)),
+        (comment, None) => comment,
+    }
+}
+
 pub fn run_confirm_order_mutation(context: &Context, input: OrderConfirmedInput) -> FieldResult<Option<GraphQLOrder>> {
-    context.get_saga_microservice().set_order_state(input.into())
+    run_checked_order_state_mutation(context, input.into())
 }
 
 pub fn run_set_paid_to_seller_order_state_mutation(context: &Context, input: PaidToSellerOrderStateInput) -> FieldResult<()> {
@@ -765,8 +850,9 @@ pub fn run_set_paid_to_seller_order_state_mutation(context: &Context, input: Pai
     saga.set_order_payment_state(order_id, state)
 }
 
-pub fn run_create_dispute_mutation(context: &Context, input: CreateDisputeInput) -> FieldResult<Option<GraphQLOrder>> {
-    context.get_saga_microservice().set_order_state(input.into())
+fn run_checked_order_state_mutation(context: &Context, mut update: UpdateOrderState) -> FieldResult<Option<GraphQLOrder>> {
+    update.committer_role = TransitionChecker::new(context).check(update.order_slug, &update.state, Some(update.committer_role))?;
+    context.get_saga_microservice().set_order_state(update)
 }
 
 pub fn run_charge_fee_mutation(context: &Context, input: ChargeFeeInput) -> FieldResult<Fee> {
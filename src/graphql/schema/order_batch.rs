@@ -0,0 +1,269 @@
+//! File containing batch order state transitions
+use std::collections::{HashMap, HashSet};
+
+use juniper::{FieldError, FieldResult};
+
+use stq_static_resources::{CommitterRole, OrderState};
+use stq_types::OrderSlug;
+
+use graphql::context::Context;
+use graphql::models::*;
+use graphql::schema::order;
+use graphql::schema::order_transition::TransitionChecker;
+use graphql::schema::shipment;
+
+graphql_object!(OrderStateBatchItem: Context as "OrderStateBatchItem" |&self| {
+    description: "Result of the order state transition in batch."
+
+    field order_slug() -> &i32 as "Slug of order" {
+        &self.order_slug.0
+    }
+
+    field order() -> &Option<GraphQLOrder> as "Order after the transition, if it succeeded" {
+        &self.order
+    }
+
+    field error() -> &Option<String> as "Error message, if the transition or saving of the shipment failed" {
+        &self.error
+    }
+});
+
+graphql_object!(OrderStateBatchOutput: Context as "OrderStateBatchOutput" |&self| {
+    description: "Results of the order state transitions in batch."
+
+    field items() -> &[OrderStateBatchItem] as "Results in the order of input slugs" {
+        &self.items
+    }
+
+    field succeeded_count() -> i32 as "Number of orders with changed state" {
+        self.items.iter().filter(|item| item.error.is_none()).count() as i32
+    }
+
+    field failed_count() -> i32 as "Number of orders with errors" {
+        self.items.iter().filter(|item| item.error.is_some()).count() as i32
+    }
+});
+
+pub fn run_set_order_status_delivery_batch(context: &Context, input: OrderStatusDeliveryBatchInput) -> FieldResult<OrderStateBatchOutput> {
+    let comment = input.comment;
+    let companies = input
+        .orders
+        .iter()
+        .map(|order| (OrderSlug(order.order_slug), order.company_id))
+        .collect::<HashMap<_, _>>();
+    let transitions = input
+        .orders
+        .into_iter()
+        .map(|order| BatchTransition {
+            order_slug: OrderSlug(order.order_slug),
+            comment: order::delivery_comment(comment.clone(), order.track_id.as_ref()),
+            track_id: order.track_id,
+        })
+        .collect();
+
+    // shipments are saved before the orders are sent and deleted for the orders which were not sent,
+    // the same way as for a single order, so a sent order never lacks the shipment
+    let mut batch = check_batch(context, OrderState::Sent, Some(CommitterRole::Seller), transitions)?;
+    let mut shipments = HashMap::new();
+    let mut updates = vec![];
+    for update in batch.updates.drain(..) {
+        let company_id = companies.get(&update.order_slug).cloned().unwrap_or_default();
+        match save_shipment(context, update.order_slug, company_id, update.track_id.clone()) {
+            Ok(shipment) => {
+                if let Some(shipment) = shipment {
+                    shipments.insert(update.order_slug, shipment.id);
+                }
+                updates.push(update);
+            }
+            Err(e) => {
+                batch.errors.insert(update.order_slug, format!("Shipment is not saved: {}", e.message()));
+            }
+        }
+    }
+    batch.updates = updates;
+
+    let output = send_batch(context, batch);
+    for item in output.items.iter().filter(|item| item.error.is_some() || item.order.is_none()) {
+        if let Some(shipment_id) = shipments.remove(&item.order_slug) {
+            if let Err(delete_error) = context.get_delivery_microservice().delete_shipment(shipment_id) {
+                error!(
+                    "Could not delete shipment {} of order {} which was not moved to sent state: {:?}",
+                    shipment_id, item.order_slug, delete_error
+                );
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+fn save_shipment(
+    context: &Context,
+    order_slug: OrderSlug,
+    company_id: Option<i32>,
+    track_id: Option<String>,
+) -> FieldResult<Option<Shipment>> {
+    let order_id = context
+        .get_orders_microservice()
+        .get_order_by_slug(order_slug)?
+        .map(|order| order.0.id)
+        .ok_or_else(|| {
+            FieldError::new(
+                "Could not find order.",
+                graphql_value!({ "code": 400, "details": { "Order with such slug does not exist." }}),
+            )
+        })?;
+    shipment::create_shipment(context, order_id, company_id, track_id)
+}
+
+pub fn run_set_order_status_canceled_batch(context: &Context, input: OrderStatusBatchInput) -> FieldResult<OrderStateBatchOutput> {
+    let committer_role = input.committer_role.clone();
+    run_batch(context, OrderState::Cancelled, committer_role, same_comment_transitions(input.order_slugs, input.comment))
+}
+
+pub fn run_set_order_status_complete_batch(context: &Context, input: OrderStatusBatchInput) -> FieldResult<OrderStateBatchOutput> {
+    let committer_role = input.committer_role.clone();
+    run_batch(context, OrderState::Complete, committer_role, same_comment_transitions(input.order_slugs, input.comment))
+}
+
+pub fn run_confirm_orders_mutation(context: &Context, input: OrdersConfirmedInput) -> FieldResult<OrderStateBatchOutput> {
+    run_batch(
+        context,
+        OrderState::InProcessing,
+        Some(CommitterRole::Seller),
+        same_comment_transitions(input.order_slugs, input.comment),
+    )
+}
+
+struct BatchTransition {
+    order_slug: OrderSlug,
+    track_id: Option<String>,
+    comment: Option<String>,
+}
+
+fn same_comment_transitions(order_slugs: Vec<i32>, comment: Option<String>) -> Vec<BatchTransition> {
+    order_slugs
+        .into_iter()
+        .map(|order_slug| BatchTransition {
+            order_slug: OrderSlug(order_slug),
+            track_id: None,
+            comment: comment.clone(),
+        })
+        .collect()
+}
+
+/// Orders of the batch checked for the transition, illegal transitions are reported per order and are not sent to saga
+struct CheckedBatch {
+    order_slugs: Vec<OrderSlug>,
+    updates: Vec<UpdateOrderState>,
+    errors: HashMap<OrderSlug, String>,
+}
+
+fn run_batch(
+    context: &Context,
+    state: OrderState,
+    committer_role: Option<CommitterRole>,
+    transitions: Vec<BatchTransition>,
+) -> FieldResult<OrderStateBatchOutput> {
+    let batch = check_batch(context, state, committer_role, transitions)?;
+    Ok(send_batch(context, batch))
+}
+
+fn check_batch(
+    context: &Context,
+    state: OrderState,
+    committer_role: Option<CommitterRole>,
+    transitions: Vec<BatchTransition>,
+) -> FieldResult<CheckedBatch> {
+    if context.user.is_none() {
+        return Err(FieldError::new(
+            "Could not change orders state for unauthorized user.",
+            graphql_value!({ "code": 100, "details": { "No user id in request header." }}),
+        ));
+    }
+
+    if transitions.is_empty() {
+        return Err(FieldError::new(
+            "Could not change orders state.",
+            graphql_value!({ "code": 100, "details": { "No orders given." }}),
+        ));
+    }
+
+    let limit = context.config.gateway.records_limit;
+    if transitions.len() > limit {
+        return Err(FieldError::new(
+            "Could not change orders state.",
+            graphql_value!({ "code": 100, "details": { format!("No more than {} orders can be changed at once.", limit) }}),
+        ));
+    }
+
+    let mut slugs = HashSet::new();
+    if let Some(transition) = transitions.iter().find(|transition| !slugs.insert(transition.order_slug.0)) {
+        return Err(FieldError::new(
+            "Could not change orders state.",
+            graphql_value!({ "code": 100, "details": { format!("Order {} is given more than once.", transition.order_slug) }}),
+        ));
+    }
+
+    let order_slugs = transitions.iter().map(|transition| transition.order_slug).collect::<Vec<_>>();
+    let checker = TransitionChecker::new(context);
+    let mut errors = HashMap::new();
+    let mut updates = vec![];
+    for transition in transitions {
+        match checker.check(transition.order_slug, &state, committer_role.clone()) {
+            Ok(committer_role) => updates.push(UpdateOrderState {
+                order_slug: transition.order_slug,
+                state: state.clone(),
+                track_id: transition.track_id,
+                comment: transition.comment,
+                committer_role,
+            }),
+            Err(e) => {
+                errors.insert(transition.order_slug, e.message().to_string());
+            }
+        }
+    }
+
+    Ok(CheckedBatch {
+        order_slugs,
+        updates,
+        errors,
+    })
+}
+
+fn send_batch(context: &Context, batch: CheckedBatch) -> OrderStateBatchOutput {
+    let CheckedBatch {
+        order_slugs,
+        updates,
+        mut errors,
+    } = batch;
+    let mut results = context
+        .get_saga_microservice()
+        .set_order_states(updates)
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    let items = order_slugs
+        .into_iter()
+        .map(|order_slug| {
+            let result = match (errors.remove(&order_slug), results.remove(&order_slug)) {
+                (Some(error), _) => Err(error),
+                (None, Some(result)) => result.map_err(|e| e.message().to_string()),
+                (None, None) => Err("Order state was not changed.".to_string()),
+            };
+            match result {
+                Ok(order) => OrderStateBatchItem {
+                    order_slug,
+                    order,
+                    error: None,
+                },
+                Err(error) => OrderStateBatchItem {
+                    order_slug,
+                    order: None,
+                    error: Some(error),
+                },
+            }
+        })
+        .collect();
+
+    OrderStateBatchOutput { items }
+}
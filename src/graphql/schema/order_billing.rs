@@ -47,6 +47,17 @@ graphql_object!(OrderBillingInfo: Context as "OrderBillingInfo" |&self| {
         &self.order.stripe_fee
     }
 
+    field refunded_amount() -> f64 as "Amount refunded to the buyer" {
+        self.order.refunded_amount
+    }
+
+    field refunds(&executor) -> FieldResult<Vec<Refund>> as "Refunds" {
+        executor
+        .context()
+        .get_billing_microservice()
+        .refunds_by_order_id(self.order.id)
+    }
+
     field store(&executor) -> FieldResult<Option<Store>> as "Store" {
          executor
         .context()
@@ -177,6 +188,17 @@ graphql_object!(OrderBilling: Context as "OrderBilling" |&self| {
         &self.stripe_fee
     }
 
+    field refunded_amount() -> f64 as "Amount refunded to the buyer" {
+        self.refunded_amount
+    }
+
+    field refunds(&executor) -> FieldResult<Vec<Refund>> as "Refunds" {
+        executor
+        .context()
+        .get_billing_microservice()
+        .refunds_by_order_id(self.id)
+    }
+
 });
 
 graphql_object!(ProxyCompanyBillingInfo: Context as "ProxyCompanyBillingInfo" |&self| {
@@ -0,0 +1,216 @@
+//! File containing export of store orders to spreadsheets
+use std::collections::HashMap;
+
+use chrono::prelude::*;
+use chrono::Duration;
+use jsonwebtoken::{decode, encode, Algorithm, Header, Validation};
+use juniper::{FieldError, FieldResult};
+
+use stq_api::orders::{Order, OrderClient};
+use stq_api::types::ApiFutureExt;
+use stq_routes::service::Service;
+use stq_static_resources::Language;
+use stq_types::{ProductId, StoreId, StoresRole, UserId};
+
+use config::Config;
+use controller::downloads::DownloadFile;
+use errors::{into_graphql, FORBIDDEN_CODE};
+use graphql::context::Context;
+use graphql::models::*;
+use graphql::schema::base_product as base_product_module;
+use graphql::schema::product as product_module;
+use graphql::schema::store as store_module;
+use graphql::schema::user as user_module;
+use spreadsheet::{self, Cell, Row};
+
+const HEADER: &[&str] = &[
+    "Order",
+    "Created at",
+    "State",
+    "Payment status",
+    "Product",
+    "Vendor code",
+    "Quantity",
+    "Price",
+    "Currency",
+    "Customer",
+    "Receiver name",
+    "Receiver phone",
+    "Receiver email",
+    "Address",
+    "Delivery company",
+    "Track id",
+    "Delivery price",
+    "Coupon discount",
+    "Total amount",
+];
+
+/// Checks access to the orders of the store and signs the export request into a short-lived download link,
+/// the file itself is served by the gateway download route instead of being inlined into the response
+pub fn run_export_store_orders(context: &Context, input: ExportStoreOrdersInput) -> FieldResult<OrdersExport> {
+    let user = context.user.clone().ok_or_else(|| {
+        FieldError::new(
+            "Could not export orders for unauthorized user.",
+            graphql_value!({ "code": 100, "details": { "No user id in request header." }}),
+        )
+    })?;
+
+    let store_id = StoreId(input.store_id);
+    check_export_access(context, store_id)?;
+    // fails early on invalid dates instead of on download
+    store_module::store_orders_search_terms(context, store_id, &input.search_term_options)?;
+
+    let expires_at = Utc::now() + Duration::seconds(context.config.downloads.ttl_secs);
+    let claims = OrdersExportClaims {
+        exp: expires_at.timestamp(),
+        user,
+        store_id,
+        format: input.format,
+        search_term_options: input.search_term_options,
+    };
+    let token = encode(&Header::new(Algorithm::HS256), &claims, downloads_secret(&context.config)?).map_err(|e| {
+        FieldError::new(
+            "Could not export orders.",
+            graphql_value!({ "code": 100, "details": { format!("Download link signing failed: {}", e) }}),
+        )
+    })?;
+
+    Ok(OrdersExport {
+        url: format!("/exports/orders/{}", token),
+        expires_at: expires_at.to_rfc3339(),
+    })
+}
+
+/// Reads the export request from the download link token, expired and forged tokens are rejected
+pub fn export_claims(config: &Config, token: &str) -> FieldResult<OrdersExportClaims> {
+    decode::<OrdersExportClaims>(token, downloads_secret(config)?, &Validation::new(Algorithm::HS256))
+        .map(|data| data.claims)
+        .map_err(|e| {
+            FieldError::new(
+                "Download link is not valid.",
+                graphql_value!({ "code": 100, "details": { format!("{}", e) }}),
+            )
+        })
+}
+
+/// Orders of the store as a file, the context must be created for the user of the claims
+/// and the access of the user must be checked with `check_export_access`
+pub fn export_store_orders(context: &Context, claims: &OrdersExportClaims) -> FieldResult<DownloadFile> {
+    let search_term = store_module::store_orders_search_terms(context, claims.store_id, &claims.search_term_options)?;
+    let mut orders = context
+        .get_rest_api_client(Service::Orders)
+        .search(search_term)
+        .sync()
+        .map_err(into_graphql)?;
+    orders.sort_by_key(|order| order.slug.0);
+
+    let mut rows = Vec::with_capacity(orders.len() + 1);
+    rows.push(HEADER.iter().map(|title| Cell::text(*title)).collect());
+    let mut products = HashMap::new();
+    let mut customers = HashMap::new();
+    for order in &orders {
+        rows.push(order_row(context, order, &mut products, &mut customers)?);
+    }
+
+    let content = match claims.format {
+        OrdersExportFormat::Csv => spreadsheet::to_csv(&rows).into_bytes(),
+        OrdersExportFormat::Xlsx => spreadsheet::to_xlsx("Orders", &rows),
+    };
+
+    Ok(DownloadFile {
+        file_name: format!("orders-{}-{}.{}", claims.store_id, Utc::now().format("%Y%m%d"), claims.format.extension()),
+        content_type: claims.format.content_type().to_string(),
+        content,
+    })
+}
+
+/// Orders are exported by the owner and managers of the store, financial managers and superusers
+pub fn check_export_access(context: &Context, store_id: StoreId) -> FieldResult<()> {
+    let permissions = context.permissions();
+    if permissions.store_roles()?.contains(&StoresRole::Superuser) || permissions.manages_store_billing(store_id)? {
+        return Ok(());
+    }
+
+    Err(FieldError::new(
+        "Could not export orders.",
+        graphql_value!({ "code": FORBIDDEN_CODE, "details": { format!("Orders of store {} can not be exported by the user.", store_id) }}),
+    ))
+}
+
+fn downloads_secret(config: &Config) -> FieldResult<&[u8]> {
+    let secret = config.downloads.secret.as_bytes();
+    if secret.is_empty() {
+        return Err(FieldError::new(
+            "Downloads are not available.",
+            graphql_value!({ "code": 100, "details": { "Downloads secret is not configured." }}),
+        ));
+    }
+
+    Ok(secret)
+}
+
+#[derive(Clone)]
+struct ProductColumns {
+    name: Option<String>,
+    vendor_code: Option<String>,
+}
+
+fn order_row(
+    context: &Context,
+    order: &Order,
+    products: &mut HashMap<ProductId, ProductColumns>,
+    customers: &mut HashMap<UserId, Option<String>>,
+) -> FieldResult<Row> {
+    let product = match products.get(&order.product) {
+        Some(product) => product.clone(),
+        None => {
+            let product = product_columns(context, order.product)?;
+            products.insert(order.product, product.clone());
+            product
+        }
+    };
+
+    let customer = match customers.get(&order.customer) {
+        Some(customer) => customer.clone(),
+        None => {
+            let customer = user_module::get_user_by_id(context, order.customer).ok().map(|user| user.email);
+            customers.insert(order.customer, customer.clone());
+            customer
+        }
+    };
+
+    let address: Address = order.address.clone().into();
+    let money = |amount: f64| Cell::Number(Money::from_f64(amount, order.currency).to_f64());
+
+    Ok(vec![
+        Cell::Number(f64::from(order.slug.0)),
+        Cell::text(order.created_at.to_rfc3339()),
+        Cell::text(format!("{:?}", order.state)),
+        Cell::text(if order.payment_status { "Paid" } else { "Not paid" }),
+        Cell::optional_text(product.name),
+        Cell::optional_text(product.vendor_code),
+        Cell::Number(f64::from(order.quantity.0)),
+        money(order.price.0),
+        Cell::text(order.currency.code()),
+        Cell::optional_text(customer),
+        Cell::text(order.receiver_name.clone()),
+        Cell::text(order.receiver_phone.clone()),
+        Cell::text(order.receiver_email.clone()),
+        Cell::optional_text(address.value),
+        Cell::optional_text(order.delivery_company.clone()),
+        Cell::optional_text(order.track_id.clone()),
+        money(order.delivery_price),
+        money(order.coupon_discount.map(|discount| discount.0).unwrap_or_default()),
+        money(order.total_amount.0),
+    ])
+}
+
+fn product_columns(context: &Context, product_id: ProductId) -> FieldResult<ProductColumns> {
+    let vendor_code = product_module::try_get_product_without_filters(context, product_id)?.map(|product| product.vendor_code);
+    let name = base_product_module::try_get_base_product_by_product(context, product_id)?.and_then(|base_product| {
+        let english = base_product.name.iter().find(|translation| translation.lang == Language::En).cloned();
+        english.or_else(|| base_product.name.into_iter().next()).map(|translation| translation.text)
+    });
+
+    Ok(ProductColumns { name, vendor_code })
+}
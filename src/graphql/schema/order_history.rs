@@ -31,10 +31,7 @@ graphql_object!(OrderHistoryItem: Context as "OrderHistoryItem" |&self| {
 
     field deprecated "use comitter_role" user(&executor) -> FieldResult<Option<User>> as "User" {
         let context = executor.context();
-        let url = format!("{}/{}/{}",
-            context.config.service_url(Service::Users),
-            Model::User.to_url(),
-            self.0.committer);
+        let url = context.config.url(Service::Users).model(Model::User).segment(self.0.committer).build();
 
         context.request::<Option<User>>(Method::Get, url, None)
             .wait()
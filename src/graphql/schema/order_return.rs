@@ -0,0 +1,387 @@
+//! File containing order return object of graphql schema
+use std::cmp;
+use std::collections::HashSet;
+
+use juniper;
+use juniper::ID as GraphqlID;
+use juniper::{FieldError, FieldResult};
+
+use stq_static_resources::OrderState;
+use stq_types::{OrderIdentifier, OrderSlug, Quantity, StoreId};
+
+use errors::FORBIDDEN_CODE;
+use graphql::context::Context;
+use graphql::models::*;
+use graphql::schema::order as order_module;
+use graphql::schema::refund as refund_module;
+
+graphql_object!(OrderReturn: Context as "OrderReturn" |&self| {
+    description: "Return request of the customer."
+
+    field raw_id() -> &i32 as "Unique int id" {
+        &self.id.0
+    }
+
+    field customer_id() -> &i32 as "Customer int id" {
+        &self.customer_id.0
+    }
+
+    field store_id() -> &i32 as "Store int id" {
+        &self.store_id.0
+    }
+
+    field store(&executor) -> FieldResult<Option<Store>> as "Store" {
+        executor.context().get_stores_microservice().get_store_by_id(self.store_id)
+    }
+
+    field items() -> &[OrderReturnItem] as "Returned items" {
+        &self.items
+    }
+
+    field reason() -> &ReturnReason as "Reason" {
+        &self.reason
+    }
+
+    field status() -> &ReturnStatus as "Status" {
+        &self.status
+    }
+
+    field comment() -> &Option<String> as "Comment of the customer" {
+        &self.comment
+    }
+
+    field instructions() -> &Option<String> as "Return shipping instructions of the seller" {
+        &self.instructions
+    }
+
+    field seller_comment() -> &Option<String> as "Rejection or inspection comment of the seller" {
+        &self.seller_comment
+    }
+
+    field track_id() -> &Option<String> as "Track id of the return shipment" {
+        &self.track_id
+    }
+
+    field created_at() -> String as "Created at" {
+        self.created_at.to_rfc3339()
+    }
+
+    field updated_at() -> String as "Updated at" {
+        self.updated_at.to_rfc3339()
+    }
+});
+
+graphql_object!(OrderReturnItem: Context as "OrderReturnItem" |&self| {
+    description: "Returned order item."
+
+    field order_slug() -> &i32 as "Slug of order" {
+        &self.order_slug.0
+    }
+
+    field order(&executor) -> FieldResult<Option<GraphQLOrder>> as "Returned order" {
+        executor.context().get_orders_microservice().get_order_by_id(self.order_id)
+    }
+
+    field quantity() -> &i32 as "Returned quantity" {
+        &self.quantity.0
+    }
+
+    field refund_id() -> Option<i32> as "Refund raw id, set after the inspection" {
+        self.refund_id.map(|id| id.0)
+    }
+});
+
+graphql_object!(Connection<OrderReturn, PageInfoSegments>: Context as "OrderReturnConnectionPages" |&self| {
+    description: "OrderReturn Connection"
+
+    field edges() -> &[Edge<OrderReturn>] {
+        &self.edges
+    }
+
+    field page_info() -> &PageInfoSegments {
+        &self.page_info
+    }
+});
+
+graphql_object!(Edge<OrderReturn>: Context as "OrderReturnEdge" |&self| {
+    description:"OrderReturn Edge"
+
+    field cursor() -> &juniper::ID {
+        &self.cursor
+    }
+
+    field node() -> &OrderReturn {
+        &self.node
+    }
+});
+
+pub fn find_returns(
+    context: &Context,
+    current_page: i32,
+    items_count: i32,
+    search: OrderReturnSearch,
+) -> FieldResult<Connection<OrderReturn, PageInfoSegments>> {
+    let current_page = cmp::max(current_page, 1);
+    let records_limit = context.config.gateway.records_limit;
+    let items_count = cmp::max(1, cmp::min(items_count, records_limit as i32));
+    let skip = items_count * (current_page - 1);
+    let returns = context.get_orders_microservice().search_returns(skip, items_count, search)?;
+    let total_pages = cmp::max(0, returns.total_count as i32 - 1) / items_count + 1;
+    let edges = returns
+        .returns
+        .into_iter()
+        .map(|order_return| Edge::new(GraphqlID::from(order_return.id.to_string()), order_return))
+        .collect();
+    let page_info = PageInfoSegments {
+        current_page,
+        page_items_count: items_count,
+        total_pages,
+        total_count: returns.total_count as i32,
+    };
+    Ok(Connection::new(edges, page_info))
+}
+
+pub fn run_open_return_mutation(context: &Context, input: OpenReturnInput) -> FieldResult<OrderReturn> {
+    let user_id = context.current_user_id("Could not open return for unauthorized user.")?;
+
+    let orders = context.get_orders_microservice();
+    let mut slugs = HashSet::new();
+    let mut store_id: Option<StoreId> = None;
+    let mut items = vec![];
+    for item in input.items {
+        let order_slug = OrderSlug(item.order_slug);
+        if !slugs.insert(order_slug.0) {
+            return Err(return_error(format!("Order {} is given more than once.", order_slug)));
+        }
+
+        let order = order_module::get_order(context, OrderIdentifier::Slug(order_slug))?.0;
+        if order.customer != user_id {
+            return Err(FieldError::new(
+                "Could not open return.",
+                graphql_value!({ "code": FORBIDDEN_CODE, "details": { format!("Order {} belongs to another customer.", order_slug) }}),
+            ));
+        }
+        match order.state {
+            OrderState::Delivered | OrderState::Received | OrderState::Complete => {}
+            ref state => {
+                return Err(return_error(format!(
+                    "Order {} can not be returned in state {:?}, only delivered orders can.",
+                    order_slug, state
+                )))
+            }
+        }
+        if *store_id.get_or_insert(order.store) != order.store {
+            return Err(return_error("All returned orders must be from the same store.".to_string()));
+        }
+        if item.quantity < 1 || item.quantity > order.quantity.0 {
+            return Err(return_error(format!(
+                "Returned quantity of order {} must be from 1 to {}.",
+                order_slug, order.quantity.0
+            )));
+        }
+
+        items.push(OrderReturnItem {
+            order_id: order.id,
+            order_slug,
+            quantity: Quantity(item.quantity),
+            refund_id: None,
+        });
+    }
+
+    let store_id = match store_id {
+        Some(store_id) => store_id,
+        None => return Err(return_error("No items given.".to_string())),
+    };
+
+    // returns of all the orders are fetched at once, rejected returns do not prevent returning the order again
+    let search = OrderReturnSearch {
+        customer_id: Some(user_id),
+        order_ids: Some(items.iter().map(|item| item.order_id).collect()),
+        ..Default::default()
+    };
+    let active_returns = orders
+        .search_returns(0, context.config.gateway.records_limit as i32, search)?
+        .returns
+        .into_iter()
+        .filter(|order_return| order_return.status != ReturnStatus::Rejected)
+        .collect::<Vec<_>>();
+    for item in &items {
+        let is_returned = active_returns
+            .iter()
+            .any(|order_return| order_return.items.iter().any(|returned| returned.order_id == item.order_id));
+        if is_returned {
+            return Err(return_error(format!("Order {} is already returned.", item.order_slug)));
+        }
+    }
+
+    orders.create_return(NewOrderReturn {
+        customer_id: user_id,
+        store_id,
+        items,
+        reason: input.reason,
+        comment: input.comment,
+    })
+}
+
+pub fn run_accept_return_mutation(context: &Context, input: AcceptReturnInput) -> FieldResult<OrderReturn> {
+    let order_return = get_return(context, ReturnId(input.return_id))?;
+    check_seller(context, &order_return)?;
+    check_status(&order_return, ReturnStatus::Requested)?;
+
+    let update = UpdateOrderReturn {
+        status: Some(ReturnStatus::Accepted),
+        instructions: Some(input.instructions),
+        ..Default::default()
+    };
+    context.get_orders_microservice().update_return(order_return.id, update)
+}
+
+pub fn run_reject_return_mutation(context: &Context, input: RejectReturnInput) -> FieldResult<OrderReturn> {
+    let order_return = get_return(context, ReturnId(input.return_id))?;
+    check_seller(context, &order_return)?;
+    check_status(&order_return, ReturnStatus::Requested)?;
+
+    let update = UpdateOrderReturn {
+        status: Some(ReturnStatus::Rejected),
+        seller_comment: Some(input.comment),
+        ..Default::default()
+    };
+    context.get_orders_microservice().update_return(order_return.id, update)
+}
+
+pub fn run_set_return_track_id_mutation(context: &Context, input: SetReturnTrackIdInput) -> FieldResult<OrderReturn> {
+    let user_id = context.current_user_id("Could not set return track id for unauthorized user.")?;
+    let order_return = get_return(context, ReturnId(input.return_id))?;
+    if order_return.customer_id != user_id {
+        return Err(FieldError::new(
+            "Could not set return track id.",
+            graphql_value!({ "code": FORBIDDEN_CODE, "details": { "Only the customer can ship the return." }}),
+        ));
+    }
+    if order_return.status != ReturnStatus::Accepted && order_return.status != ReturnStatus::Shipped {
+        return Err(return_error(format!("Return {} is not accepted by the seller.", order_return.id)));
+    }
+
+    let update = UpdateOrderReturn {
+        status: Some(ReturnStatus::Shipped),
+        track_id: Some(input.track_id),
+        ..Default::default()
+    };
+    context.get_orders_microservice().update_return(order_return.id, update)
+}
+
+/// Refunds every returned item if the inspection is passed. Refunded items are saved even if
+/// one of the refunds fails, so the inspection can be repeated without refunding twice.
+pub fn run_inspect_return_mutation(context: &Context, input: InspectReturnInput) -> FieldResult<OrderReturn> {
+    let order_return = get_return(context, ReturnId(input.return_id))?;
+    check_seller(context, &order_return)?;
+    check_status(&order_return, ReturnStatus::Shipped)?;
+
+    let orders = context.get_orders_microservice();
+    if !input.passed {
+        let update = UpdateOrderReturn {
+            status: Some(ReturnStatus::InspectionFailed),
+            seller_comment: input.comment,
+            ..Default::default()
+        };
+        return orders.update_return(order_return.id, update);
+    }
+
+    let reason = format!("Return {}", order_return.id);
+    let mut items = order_return.items.clone();
+    let mut refund_error = None;
+    for item in items.iter_mut().filter(|item| item.refund_id.is_none()) {
+        match refund_item(context, item, reason.clone()) {
+            Ok(refund) => item.refund_id = Some(refund.id),
+            Err(e) => {
+                refund_error = Some(e);
+                break;
+            }
+        }
+    }
+
+    if let Some(e) = refund_error {
+        let update = UpdateOrderReturn {
+            items: Some(items),
+            ..Default::default()
+        };
+        orders.update_return(order_return.id, update)?;
+        return Err(e);
+    }
+
+    let update = UpdateOrderReturn {
+        status: Some(ReturnStatus::Refunded),
+        seller_comment: input.comment,
+        items: Some(items),
+        ..Default::default()
+    };
+    orders.update_return(order_return.id, update)
+}
+
+/// Returning the whole order refunds everything paid for it including delivery.
+/// Delivery is not refunded for partial returns, they refund the paid price of the goods
+/// proportionally to the returned quantity
+fn refund_item(context: &Context, item: &OrderReturnItem, reason: String) -> FieldResult<Refund> {
+    let order = order_module::get_order(context, OrderIdentifier::Id(item.order_id))?.0;
+    if item.quantity.0 >= order.quantity.0 {
+        return refund_module::refund_order(context, &order, None, reason);
+    }
+
+    let order_billing = context.get_billing_microservice().order(order.id)?.ok_or_else(|| {
+        FieldError::new(
+            "Order billing not found",
+            graphql_value!({ "code": 400, "details": { format!("billing of order {} not found", order.slug) }}),
+        )
+    })?;
+
+    // billing amount may be in other currency than the order, so delivery is excluded by its share in the order total
+    let paid = Money::from_f64(order_billing.total_amount, order_billing.seller_currency);
+    let order_total = Money::from_f64(order.total_amount.0, order.currency);
+    let delivery = Money::from_f64(order.delivery_price, order.currency);
+    let goods_paid = if order_total.is_zero() {
+        paid
+    } else {
+        let goods_total = order_total.clone().checked_sub(delivery)?;
+        Money::new(paid.amount * goods_total.amount / order_total.amount, paid.currency)
+    };
+    let amount = goods_paid.share(item.quantity.0, order.quantity.0)?.round();
+
+    refund_module::refund_order(context, &order, Some(amount.to_f64()), reason)
+}
+
+fn get_return(context: &Context, return_id: ReturnId) -> FieldResult<OrderReturn> {
+    context.get_orders_microservice().get_return(return_id)?.ok_or_else(|| {
+        FieldError::new(
+            "Return not found",
+            graphql_value!({ "code": 400, "details": { format!("return with id: {} not found", return_id) }}),
+        )
+    })
+}
+
+/// Returns are processed by the owner and managers of the store
+fn check_seller(context: &Context, order_return: &OrderReturn) -> FieldResult<()> {
+    context.current_user_id("Could not change return for unauthorized user.")?;
+    if context.permissions().manages_store(order_return.store_id)? {
+        return Ok(());
+    }
+
+    Err(FieldError::new(
+        "Could not change return.",
+        graphql_value!({ "code": FORBIDDEN_CODE, "details": { "Only the seller can process the return." }}),
+    ))
+}
+
+fn check_status(order_return: &OrderReturn, expected: ReturnStatus) -> FieldResult<()> {
+    if order_return.status == expected {
+        return Ok(());
+    }
+
+    Err(return_error(format!(
+        "Return {} is {:?}, expected {:?}.",
+        order_return.id, order_return.status, expected
+    )))
+}
+
+fn return_error(details: String) -> FieldError {
+    FieldError::new("Could not process return.", graphql_value!({ "code": 100, "details": { details }}))
+}
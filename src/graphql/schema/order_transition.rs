@@ -0,0 +1,102 @@
+//! File containing validation of order state transitions made by the current user
+use juniper::{FieldError, FieldResult};
+
+use stq_api::orders::Order;
+use stq_static_resources::{CommitterRole, OrderState};
+use stq_types::{OrderIdentifier, OrderSlug, StoresRole};
+
+use errors::FORBIDDEN_CODE;
+use graphql::context::Context;
+use graphql::models::*;
+use graphql::schema::order as order_module;
+
+/// The order can not be moved to the requested state from the current one
+pub const TRANSITION_ILLEGAL_CODE: i32 = 121;
+
+/// Checks transitions of the current user, roles and store owners are cached on the context,
+/// so checking every order of a list fetches them once
+pub struct TransitionChecker<'ctx> {
+    context: &'ctx Context,
+}
+
+impl<'ctx> TransitionChecker<'ctx> {
+    pub fn new(context: &'ctx Context) -> Self {
+        Self { context }
+    }
+
+    /// Participants the current user acts as for the order, owner and managers of the store act as the seller
+    pub fn actors(&self, order: &Order) -> FieldResult<Vec<OrderActor>> {
+        let user_id = match self.context.user {
+            Some(ref user) => user.user_id,
+            None => return Ok(vec![]),
+        };
+
+        let permissions = self.context.permissions();
+        let mut actors = vec![];
+        if order.customer == user_id {
+            actors.push(OrderActor::Customer);
+        }
+        if permissions.is_store_manager(order.store)? {
+            actors.push(OrderActor::Seller);
+        }
+
+        let roles = permissions.store_roles()?;
+        if roles.contains(&StoresRole::Superuser) {
+            actors.push(OrderActor::System);
+        }
+        if roles.contains(&StoresRole::Superuser) || roles.contains(&StoresRole::Moderator) {
+            actors.push(OrderActor::Moderator);
+        }
+
+        Ok(actors)
+    }
+
+    pub fn allowed_transitions(&self, order: &Order) -> FieldResult<Vec<OrderStateTransition>> {
+        Ok(self
+            .actors(order)?
+            .into_iter()
+            .flat_map(|actor| {
+                actor.allowed_transitions(&order.state).iter().map(move |state| OrderStateTransition {
+                    state: state.clone(),
+                    actor,
+                    committer_role: actor.committer_role(),
+                })
+            })
+            .collect())
+    }
+
+    /// Checks that the current user can move the order to the state and returns committer role to pass to saga.
+    /// If committer role is not given, the first one allowing the transition is chosen.
+    pub fn check(&self, order_slug: OrderSlug, state: &OrderState, committer_role: Option<CommitterRole>) -> FieldResult<CommitterRole> {
+        let order = order_module::get_order(self.context, OrderIdentifier::Slug(order_slug))?.0;
+        let actors = self
+            .actors(&order)?
+            .into_iter()
+            .filter(|actor| committer_role.as_ref().map(|role| actor.committer_role() == *role).unwrap_or(true))
+            .collect::<Vec<_>>();
+
+        if actors.is_empty() {
+            let details = match committer_role {
+                Some(role) => format!("Current user can not change order {} as {:?}.", order_slug, role),
+                None => format!("Current user can not change order {}.", order_slug),
+            };
+            return Err(FieldError::new(
+                "Order state transition is forbidden.",
+                graphql_value!({ "code": FORBIDDEN_CODE, "details": { details }}),
+            ));
+        }
+
+        actors
+            .into_iter()
+            .find(|actor| actor.allowed_transitions(&order.state).contains(state))
+            .map(OrderActor::committer_role)
+            .ok_or_else(|| {
+                FieldError::new(
+                    "Order state transition is not allowed.",
+                    graphql_value!({ "code": TRANSITION_ILLEGAL_CODE, "details": {
+                        format!("Order {} can not be moved from {:?} to {:?} by current user.", order_slug, order.state, state)
+                    }}),
+                )
+            })
+    }
+}
@@ -51,13 +51,7 @@ graphql_object!(Packages: Context as "Packages" |&self| {
     field companies(&executor) -> FieldResult<Vec<Company>> as "Fetches companies by id." {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}/{}/{}",
-            &context.config.service_url(Service::Delivery),
-            Model::Package.to_url(),
-            self.id,
-            Model::Company.to_url(),
-        );
+        let url = context.config.url(Service::Delivery).model(Model::Package).segment(self.id).model(Model::Company).build();
 
         context.request::<Vec<Company>>(Method::Get, url, None)
             .wait()
@@ -1,6 +1,13 @@
 //! File containing PageInfo object of graphql schema
 
+use futures::Future;
+use hyper::Method;
 use juniper;
+use juniper::FieldResult;
+use serde_json;
+
+use stq_routes::model::Model;
+use stq_routes::service::Service;
 
 use graphql::context::Context;
 use graphql::models::*;
@@ -24,6 +31,10 @@ graphql_object!(PageInfo: Context as "PageInfo" |&self| {
         &self.start_cursor
     }
 
+    field total_count() -> &Option<i32> as "total elements count, if the connection knows it" {
+        &self.total_count
+    }
+
 });
 
 graphql_object!(PageInfoSegments: Context as "PageInfoSegments" |&self| {
@@ -40,6 +51,34 @@ graphql_object!(PageInfoSegments: Context as "PageInfoSegments" |&self| {
     field total_pages() -> &i32 as "total pages" {
         &self.total_pages
     }
+
+    field total_count() -> &i32 as "total elements count" {
+        &self.total_count
+    }
+});
+
+graphql_object!(PageInfoReviews: Context as "PageInfoReviews" |&self| {
+    description: "Page info for classic pagination with the rating of all found reviews"
+
+    field current_page() -> &i32 as "current page" {
+        &self.current_page
+    }
+
+    field page_items_count() -> &i32 as "item count per page" {
+        &self.page_items_count
+    }
+
+    field total_pages() -> &i32 as "total pages" {
+        &self.total_pages
+    }
+
+    field total_count() -> &i32 as "total elements count" {
+        &self.total_count
+    }
+
+    field rating_distribution() -> &RatingDistribution as "rating distribution of all found reviews" {
+        &self.rating_distribution
+    }
 });
 
 graphql_object!(PageInfoStoresSearch: Context as "PageInfoStoresSearch" |&self| {
@@ -86,6 +125,22 @@ graphql_object!(PageInfoProductsSearch: Context as "PageInfoProductsSearch" |&se
         &self.search_filters
     }
 
+    field total_count(&executor) -> FieldResult<Option<i32>> as "total elements count" {
+        let context = executor.context();
+
+        if let Some(ref search_filters) = self.search_filters {
+            let body = serde_json::to_string(&search_filters.search_term)?;
+
+            let url = context.config.url(Service::Stores).model(Model::BaseProduct).path("search/filters/count").build();
+
+            context.request::<i32>(Method::Post, url, Some(body))
+                .wait()
+                .map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
     field end_cursor() -> &Option<juniper::ID> as "end cursor" {
         &self.end_cursor
     }
@@ -94,6 +149,10 @@ graphql_object!(PageInfoProductsSearch: Context as "PageInfoProductsSearch" |&se
         &self.start_cursor
     }
 
+    field did_you_mean() -> &[String] as "Search queries finding products when nothing is found, empty otherwise" {
+        &self.did_you_mean
+    }
+
 });
 
 graphql_object!(PageInfoOrdersSearch: Context as "PageInfoOrdersSearch" |&self| {
@@ -103,6 +162,10 @@ graphql_object!(PageInfoOrdersSearch: Context as "PageInfoOrdersSearch" |&self|
         &self.total_pages
     }
 
+    field total_count() -> &i32 as "total elements count" {
+        &self.total_count
+    }
+
     field current_page() -> &i32 as "current_page" {
         &self.current_page
     }
@@ -115,6 +178,22 @@ graphql_object!(PageInfoOrdersSearch: Context as "PageInfoOrdersSearch" |&self|
         &self.search_term_options
     }
 
+    field has_next_page() -> &bool as "has next page"{
+        &self.has_next_page
+    }
+
+    field has_previous_page() -> &bool as "has previous page" {
+        &self.has_previous_page
+    }
+
+    field end_cursor() -> &Option<juniper::ID> as "end cursor" {
+        &self.end_cursor
+    }
+
+    field start_cursor() -> &Option<juniper::ID> as "start cursor" {
+        &self.start_cursor
+    }
+
 });
 
 graphql_object!(PageInfoWarehouseProductSearch: Context as "PageInfoWarehouseProductSearch" |&self| {
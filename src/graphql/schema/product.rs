@@ -93,10 +93,7 @@ graphql_object!(Product: Context as "Product" |&self| {
 
     field attributes(&executor) -> FieldResult<Option<Vec<ProdAttrValue>>> as "Variants" {
        let context = executor.context();
-        let url = format!("{}/{}/{}/attributes",
-            context.config.service_url(Service::Stores),
-            Model::Product.to_url(),
-            self.id);
+        let url = context.config.url(Service::Stores).model(Model::Product).segment(self.id).path("attributes").build();
 
         context.request::<Vec<ProdAttrValue>>(Method::Get, url, None)
             .wait()
@@ -156,23 +153,13 @@ graphql_object!(Edge<Product>: Context as "ProductsEdge" |&self| {
 });
 
 pub fn try_get_product(context: &Context, product_id: ProductId) -> FieldResult<Option<Product>> {
-    let url_product = format!(
-        "{}/{}/{}",
-        context.config.service_url(Service::Stores),
-        Model::Product.to_url(),
-        product_id
-    );
+    let url_product = context.config.url(Service::Stores).model(Model::Product).segment(product_id).build();
 
     context.request::<Option<Product>>(Method::Get, url_product, None).wait()
 }
 
 pub fn try_get_product_without_filters(context: &Context, product_id: ProductId) -> FieldResult<Option<Product>> {
-    let url_product = format!(
-        "{}/{}/{}/without_filters",
-        context.config.service_url(Service::Stores),
-        Model::Product.to_url(),
-        product_id
-    );
+    let url_product = context.config.url(Service::Stores).model(Model::Product).segment(product_id).path("without_filters").build();
 
     context.request::<Option<Product>>(Method::Get, url_product, None).wait()
 }
@@ -191,12 +178,7 @@ pub fn get_product(context: &Context, product_id: ProductId) -> FieldResult<Prod
 }
 
 pub fn get_seller_price(context: &Context, product_id: ProductId) -> FieldResult<ProductSellerPrice> {
-    let url = format!(
-        "{}/{}/{}/seller_price",
-        context.config.service_url(Service::Stores),
-        Model::Product.to_url(),
-        product_id
-    );
+    let url = context.config.url(Service::Stores).model(Model::Product).segment(product_id).path("seller_price").build();
 
     context
         .request::<Option<ProductSellerPrice>>(Method::Get, url, None)
@@ -240,12 +222,7 @@ pub fn run_update_product_mutation(context: &Context, input: UpdateProductWithAt
 }
 
 pub fn validate_update_product(context: &Context, product_id: ProductId) -> FieldResult<bool> {
-    let url = format!(
-        "{}/{}/{}/validate_update",
-        context.config.service_url(Service::Stores),
-        Model::Product.to_url(),
-        product_id
-    );
+    let url = context.config.url(Service::Stores).model(Model::Product).segment(product_id).path("validate_update").build();
 
     context.request::<bool>(Method::Get, url, None).wait()
 }
@@ -0,0 +1,117 @@
+//! File containing search by vendor code, barcode or store slug of graphql schema
+use std::cmp;
+
+use futures::Future;
+use hyper::Method;
+use juniper::{FieldError, FieldResult};
+
+use stq_routes::model::Model;
+use stq_routes::service::Service;
+use stq_types::AttributeId;
+
+use graphql::context::Context;
+use graphql::models::*;
+
+graphql_object!(CodeMatch: Context as "CodeMatch" |&self| {
+    description: "Product or store found by the code."
+
+    field kind() -> CodeMatchKind as "What the code was matched with" {
+        self.kind
+    }
+
+    field exact() -> bool as "The code is the whole vendor code, barcode or slug, not just its beginning" {
+        self.exact
+    }
+
+    field product() -> Option<Product> as "Product with the vendor code or barcode" {
+        self.product.clone()
+    }
+
+    field store() -> Option<Store> as "Store with the slug" {
+        self.store.clone()
+    }
+});
+
+/// Products with vendor code or barcode starting with the code and the store with the code as slug,
+/// exact matches go first
+pub fn find_by_code(context: &Context, code: &str, visibility: Visibility, count: Option<i32>) -> FieldResult<Vec<CodeMatch>> {
+    let code = code.trim().to_lowercase();
+    if code.is_empty() {
+        return Err(FieldError::new(
+            "Could not find product by empty code.",
+            graphql_value!({ "code": 100, "details": { "Code is empty." }}),
+        ));
+    }
+
+    let records_limit = context.config.gateway.records_limit as i32;
+    let count = cmp::max(1, cmp::min(count.unwrap_or(records_limit), records_limit));
+
+    let mut matches = vec![];
+    if let Some(store) = store_by_slug(context, &code, &visibility)? {
+        matches.push(CodeMatch {
+            kind: CodeMatchKind::StoreSlug,
+            exact: true,
+            product: None,
+            store: Some(store),
+        });
+    }
+
+    let codes = code_variants(&code);
+    let input = ProductCodeSearch {
+        codes: codes.clone(),
+        barcode_attribute_ids: context.config.search.barcode_attribute_ids.iter().cloned().map(AttributeId).collect(),
+    };
+    let results = context.get_stores_microservice().search_products_by_code(input, visibility, count)?;
+    matches.extend(results.into_iter().map(|result| product_match(&codes, result)));
+
+    // stable sort keeps the order of the service within exact and partial matches
+    matches.sort_by_key(|code_match| !code_match.exact);
+    matches.truncate(count as usize);
+    Ok(matches)
+}
+
+fn product_match(codes: &[String], result: ProductCodeSearchResult) -> CodeMatch {
+    let vendor_code = result.product.vendor_code.to_lowercase();
+    let vendor_code_exact = codes.contains(&vendor_code);
+    let barcode_exact = result.barcode.map(|barcode| codes.contains(&barcode.to_lowercase())).unwrap_or(false);
+    let vendor_code_partial = codes.iter().any(|code| vendor_code.starts_with(code.as_str()));
+
+    let kind = if vendor_code_exact || (vendor_code_partial && !barcode_exact) {
+        CodeMatchKind::VendorCode
+    } else {
+        CodeMatchKind::Barcode
+    };
+
+    CodeMatch {
+        kind,
+        exact: vendor_code_exact || barcode_exact,
+        product: Some(result.product),
+        store: None,
+    }
+}
+
+/// UPC-A barcode is EAN-13 barcode without the leading zero, so scanners may read either of them
+fn code_variants(code: &str) -> Vec<String> {
+    let mut codes = vec![code.to_string()];
+    if code.chars().all(|c| c.is_ascii_digit()) {
+        if code.len() == 12 {
+            codes.push(format!("0{}", code));
+        } else if code.len() == 13 && code.starts_with('0') {
+            codes.push(code[1..].to_string());
+        }
+    }
+    codes
+}
+
+fn store_by_slug(context: &Context, slug: &str, visibility: &Visibility) -> FieldResult<Option<Store>> {
+    let url = context
+        .config
+        .url(Service::Stores)
+        .model(Model::Store)
+        .path("by-slug")
+        .segment(slug)
+        .query("visibility", visibility)
+        .build();
+
+    context.request::<Option<Store>>(Method::Get, url, None).wait()
+}
@@ -0,0 +1,549 @@
+//! File containing bulk import of products from CSV and JSON files
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use juniper::{FieldError, FieldResult};
+use serde_json;
+use uuid::Uuid;
+
+use stq_api::types::ApiFutureExt;
+use stq_api::warehouses::WarehouseClient;
+use stq_routes::service::Service;
+use stq_static_resources::{Language, TranslationInput};
+use stq_types::{AttributeId, CategoryId, ProductId, Quantity, StoreId, WarehouseId};
+
+use errors::{into_graphql, FORBIDDEN_CODE};
+use graphql::context::Context;
+use graphql::models::*;
+use graphql::schema::attribute as attribute_module;
+use graphql::schema::base_product as base_product_module;
+use graphql::schema::category as category_module;
+use graphql::schema::store as store_module;
+use spreadsheet;
+
+const MAX_ROWS: usize = 1000;
+const ATTRIBUTE_COLUMN_PREFIX: &str = "attr:";
+const COLUMNS: &[&str] = &[
+    "base_product",
+    "name",
+    "short_description",
+    "long_description",
+    "category_id",
+    "slug",
+    "length_cm",
+    "width_cm",
+    "height_cm",
+    "weight_g",
+    "vendor_code",
+    "price",
+    "discount",
+    "cashback",
+    "photo_main",
+    "additional_photos",
+    "pre_order",
+    "pre_order_days",
+    "warehouse_id",
+    "quantity",
+];
+
+struct ParsedRow {
+    number: i32,
+    row: ImportProductRow,
+    errors: Vec<String>,
+    product_id: Option<ProductId>,
+}
+
+impl ParsedRow {
+    fn new(number: usize, row: ImportProductRow, errors: Vec<String>) -> Self {
+        Self {
+            number: number as i32,
+            row,
+            errors,
+            product_id: None,
+        }
+    }
+}
+
+pub fn run_import_products(context: &Context, input: ImportProductsInput) -> FieldResult<ImportProductsOutput> {
+    if context.user.is_none() {
+        return Err(FieldError::new(
+            "Could not import products for unauthorized user.",
+            graphql_value!({ "code": 100, "details": { "No user id in request header." }}),
+        ));
+    }
+
+    let store_id = StoreId(input.store_id);
+    if !context.permissions().manages_store(store_id)? {
+        return Err(FieldError::new(
+            "Could not import products.",
+            graphql_value!({ "code": FORBIDDEN_CODE, "details": { format!("Products can be imported only by managers of store {}.", store_id) }}),
+        ));
+    }
+
+    let store = store_module::get_store(context, store_id, Visibility::Active)?;
+    let mut rows = match input.format {
+        ProductsImportFormat::Csv => parse_csv_rows(&input.content)?,
+        ProductsImportFormat::Json => parse_json_rows(&input.content)?,
+    };
+
+    if rows.is_empty() {
+        return Err(FieldError::new(
+            "Could not import products.",
+            graphql_value!({ "code": 100, "details": { "File does not contain any rows." }}),
+        ));
+    }
+    if rows.len() > MAX_ROWS {
+        return Err(FieldError::new(
+            "Could not import products.",
+            graphql_value!({ "code": 100, "details": { format!("File can not contain more than {} rows.", MAX_ROWS) }}),
+        ));
+    }
+
+    let groups = group_rows(&rows);
+    validate_rows(context, &mut rows, &groups)?;
+
+    let dry_run = input.dry_run.unwrap_or(false);
+    for group in &groups {
+        if group.iter().any(|&i| !rows[i].errors.is_empty()) {
+            for &i in group {
+                if rows[i].errors.is_empty() {
+                    rows[i]
+                        .errors
+                        .push("Skipped because other rows of the base product have errors.".to_string());
+                }
+            }
+            continue;
+        }
+
+        if !dry_run {
+            import_base_product(context, &input, store.default_language.clone(), &mut rows, group);
+        }
+    }
+
+    let imported_count = rows
+        .iter()
+        .filter(|row| if dry_run { row.errors.is_empty() } else { row.product_id.is_some() })
+        .count() as i32;
+    let failed_count = rows.iter().filter(|row| !row.errors.is_empty()).count() as i32;
+
+    Ok(ImportProductsOutput {
+        dry_run,
+        imported_count,
+        failed_count,
+        rows: rows
+            .into_iter()
+            .map(|parsed| ImportProductRowResult {
+                row: parsed.number,
+                base_product: parsed.row.base_product,
+                vendor_code: parsed.row.vendor_code,
+                product_id: parsed.product_id.map(|id| id.0),
+                errors: parsed.errors,
+            })
+            .collect(),
+    })
+}
+
+fn parse_csv_rows(content: &str) -> FieldResult<Vec<ParsedRow>> {
+    let mut records = spreadsheet::parse_csv(content)
+        .map_err(|e| FieldError::new("Could not parse CSV file.", graphql_value!({ "code": 300, "details": { e }})))?
+        .into_iter();
+
+    let header = match records.next() {
+        Some(header) => header.into_iter().map(|column| column.trim().to_lowercase()).collect::<Vec<_>>(),
+        None => return Ok(vec![]),
+    };
+
+    for column in &header {
+        let is_known = COLUMNS.contains(&column.as_str())
+            || (column.starts_with(ATTRIBUTE_COLUMN_PREFIX) && column[ATTRIBUTE_COLUMN_PREFIX.len()..].parse::<i32>().is_ok());
+        if !is_known {
+            return Err(FieldError::new(
+                "Could not parse CSV file.",
+                graphql_value!({ "code": 300, "details": { format!("Unknown column \"{}\".", column) }}),
+            ));
+        }
+    }
+
+    Ok(records
+        .enumerate()
+        .map(|(i, record)| {
+            let mut row = ImportProductRow::default();
+            let mut errors = vec![];
+            if record.len() != header.len() {
+                errors.push(format!("Row has {} cells, but header has {} columns.", record.len(), header.len()));
+            }
+            for (column, value) in header.iter().zip(record.iter()) {
+                let value = value.trim();
+                if !value.is_empty() {
+                    set_csv_cell(&mut row, column, value, &mut errors);
+                }
+            }
+            ParsedRow::new(i + 1, row, errors)
+        })
+        .collect())
+}
+
+fn set_csv_cell(row: &mut ImportProductRow, column: &str, value: &str, errors: &mut Vec<String>) {
+    if column.starts_with(ATTRIBUTE_COLUMN_PREFIX) {
+        if let Ok(attr_id) = column[ATTRIBUTE_COLUMN_PREFIX.len()..].parse::<i32>() {
+            row.attributes.insert(attr_id, value.to_string());
+        }
+        return;
+    }
+
+    match column {
+        "base_product" => row.base_product = value.to_string(),
+        "name" => row.name = value.to_string(),
+        "short_description" => row.short_description = Some(value.to_string()),
+        "long_description" => row.long_description = Some(value.to_string()),
+        "category_id" => row.category_id = parse_cell(column, value, errors),
+        "slug" => row.slug = Some(value.to_string()),
+        "length_cm" => row.length_cm = parse_cell(column, value, errors),
+        "width_cm" => row.width_cm = parse_cell(column, value, errors),
+        "height_cm" => row.height_cm = parse_cell(column, value, errors),
+        "weight_g" => row.weight_g = parse_cell(column, value, errors),
+        "vendor_code" => row.vendor_code = value.to_string(),
+        "price" => row.price = parse_cell(column, value, errors),
+        "discount" => row.discount = parse_cell(column, value, errors),
+        "cashback" => row.cashback = parse_cell(column, value, errors),
+        "photo_main" => row.photo_main = Some(value.to_string()),
+        "additional_photos" => {
+            row.additional_photos = Some(
+                value
+                    .split('|')
+                    .map(str::trim)
+                    .filter(|photo| !photo.is_empty())
+                    .map(String::from)
+                    .collect(),
+            )
+        }
+        "pre_order" => {
+            row.pre_order = match value.to_lowercase().as_str() {
+                "true" | "yes" | "1" => Some(true),
+                "false" | "no" | "0" => Some(false),
+                _ => {
+                    errors.push(format!("Column \"{}\" must be true or false.", column));
+                    None
+                }
+            }
+        }
+        "pre_order_days" => row.pre_order_days = parse_cell(column, value, errors),
+        "warehouse_id" => row.warehouse_id = Some(value.to_string()),
+        "quantity" => row.quantity = parse_cell(column, value, errors),
+        _ => {}
+    }
+}
+
+fn parse_cell<T: FromStr>(column: &str, value: &str, errors: &mut Vec<String>) -> Option<T> {
+    value
+        .parse()
+        .map_err(|_| errors.push(format!("Column \"{}\" has invalid value \"{}\".", column, value)))
+        .ok()
+}
+
+fn parse_json_rows(content: &str) -> FieldResult<Vec<ParsedRow>> {
+    let values = serde_json::from_str::<Vec<serde_json::Value>>(content).map_err(|e| {
+        FieldError::new(
+            "Could not parse JSON file.",
+            graphql_value!({ "code": 300, "details": { format!("File must contain array of rows: {}", e) }}),
+        )
+    })?;
+
+    Ok(values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| match serde_json::from_value::<ImportProductRow>(value) {
+            Ok(row) => ParsedRow::new(i + 1, row, vec![]),
+            Err(e) => ParsedRow::new(i + 1, ImportProductRow::default(), vec![format!("Row can not be parsed: {}", e)]),
+        })
+        .collect())
+}
+
+/// Groups indices of rows by base product key, keeping order of the first appearance
+fn group_rows(rows: &[ParsedRow]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = vec![];
+    let mut group_indices = HashMap::new();
+    for (i, parsed) in rows.iter().enumerate() {
+        let group_index = *group_indices.entry(parsed.row.base_product.clone()).or_insert_with(|| {
+            groups.push(vec![]);
+            groups.len() - 1
+        });
+        groups[group_index].push(i);
+    }
+    groups
+}
+
+fn validate_rows(context: &Context, rows: &mut [ParsedRow], groups: &[Vec<usize>]) -> FieldResult<()> {
+    let mut vendor_codes = HashMap::new();
+    let mut categories = HashMap::new();
+    let mut attribute_values = HashMap::new();
+
+    for group in groups {
+        let first = rows[group[0]].row.clone();
+        for (position, &i) in group.iter().enumerate() {
+            let parsed = &mut rows[i];
+            let row = &mut parsed.row;
+            let errors = &mut parsed.errors;
+
+            if row.base_product.is_empty() {
+                errors.push("base_product is required.".to_string());
+            }
+            if row.vendor_code.is_empty() {
+                errors.push("vendor_code is required.".to_string());
+            } else if let Some(other) = vendor_codes.insert(row.vendor_code.clone(), parsed.number) {
+                errors.push(format!("vendor_code is the same as in row {}.", other));
+            }
+            match row.price {
+                None => errors.push("price is required.".to_string()),
+                Some(price) if !(price > 0.0) => errors.push("price must be positive.".to_string()),
+                _ => {}
+            }
+            if row.discount.map(|discount| discount < 0.0).unwrap_or(false) {
+                errors.push("discount must not be negative.".to_string());
+            }
+            if row.cashback.map(|cashback| cashback < 0.0).unwrap_or(false) {
+                errors.push("cashback must not be negative.".to_string());
+            }
+            if row.pre_order_days.map(|days| days < 0).unwrap_or(false) {
+                errors.push("pre_order_days must not be negative.".to_string());
+            }
+            if row.quantity.map(|quantity| quantity < 0).unwrap_or(false) {
+                errors.push("quantity must not be negative.".to_string());
+            }
+            if row.warehouse_id.as_ref().map(|id| Uuid::parse_str(id).is_err()).unwrap_or(false) {
+                errors.push("warehouse_id must be uuid.".to_string());
+            }
+
+            // base product fields can be left empty in every row but the first one of the group
+            if position == 0 {
+                if row.name.is_empty() {
+                    errors.push("name is required.".to_string());
+                }
+                if row.category_id.is_none() {
+                    errors.push("category_id is required.".to_string());
+                }
+            } else {
+                if !row.name.is_empty() && row.name != first.name {
+                    errors.push("name differs from the first row of the base product.".to_string());
+                }
+                if row.category_id.is_some() && row.category_id != first.category_id {
+                    errors.push("category_id differs from the first row of the base product.".to_string());
+                }
+                let mut attributes = row.attributes.keys().collect::<Vec<_>>();
+                let mut first_attributes = first.attributes.keys().collect::<Vec<_>>();
+                attributes.sort();
+                first_attributes.sort();
+                if attributes != first_attributes {
+                    errors.push("Attributes differ from the first row of the base product.".to_string());
+                }
+                row.category_id = first.category_id;
+            }
+
+            let category_id = match row.category_id {
+                Some(category_id) => category_id,
+                None => continue,
+            };
+            if !categories.contains_key(&category_id) {
+                let category = category_module::try_get_category(context, CategoryId(category_id))?;
+                categories.insert(category_id, category);
+            }
+            let category = match categories[&category_id] {
+                Some(ref category) => category,
+                None => {
+                    errors.push(format!("Category {} does not exist.", category_id));
+                    continue;
+                }
+            };
+
+            for attribute in &category.attributes {
+                if !row.attributes.contains_key(&attribute.id.0) {
+                    errors.push(format!("Attribute {} of category {} is required.", attribute.id.0, category_id));
+                }
+            }
+            for (attr_id, value) in &row.attributes {
+                if !category.attributes.iter().any(|attribute| attribute.id == AttributeId(*attr_id)) {
+                    errors.push(format!("Attribute {} does not belong to category {}.", attr_id, category_id));
+                    continue;
+                }
+                if !attribute_values.contains_key(attr_id) {
+                    let codes = attribute_module::get_attribute_values(context, AttributeId(*attr_id))?
+                        .map(|values| values.into_iter().map(|value| value.code.0).collect::<Vec<_>>())
+                        .unwrap_or_default();
+                    attribute_values.insert(*attr_id, codes);
+                }
+                let codes = &attribute_values[attr_id];
+                if !codes.is_empty() && !codes.contains(value) {
+                    errors.push(format!("Value \"{}\" is not allowed for attribute {}.", value, attr_id));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates base product with variants of the group and sets quantities in warehouses.
+/// Errors are reported in the rows, so that other groups are still imported.
+fn import_base_product(context: &Context, input: &ImportProductsInput, language: Language, rows: &mut [ParsedRow], group: &[usize]) {
+    let translation = |text: String| {
+        vec![TranslationInput {
+            lang: language.clone(),
+            text,
+        }]
+    };
+    let first = rows[group[0]].row.clone();
+    let mut selected_attributes = first.attributes.keys().cloned().collect::<Vec<_>>();
+    selected_attributes.sort();
+
+    let variants = group
+        .iter()
+        .map(|&i| {
+            let row = rows[i].row.clone();
+            let mut attributes = row
+                .attributes
+                .into_iter()
+                .map(|(attr_id, value)| ProdAttrValueInput {
+                    attr_id,
+                    value,
+                    meta_field: None,
+                })
+                .collect::<Vec<_>>();
+            attributes.sort_by_key(|attribute| attribute.attr_id);
+
+            CreateProductWithAttributesInput {
+                client_mutation_id: derived_uuid(&input.client_mutation_id, &format!("{}/{}", row.base_product, row.vendor_code)),
+                product: NewProduct {
+                    uuid: None,
+                    base_product_id: None,
+                    discount: row.discount,
+                    photo_main: row.photo_main,
+                    additional_photos: row.additional_photos,
+                    vendor_code: row.vendor_code,
+                    cashback: row.cashback,
+                    price: row.price.unwrap_or_default(),
+                    pre_order: row.pre_order,
+                    pre_order_days: row.pre_order_days,
+                },
+                attributes,
+                // quantity for specific warehouse is set after the product is created
+                quantity: if row.warehouse_id.is_none() { row.quantity } else { None },
+            }
+        })
+        .collect();
+
+    let new_base_product = NewBaseProductWithVariantsInput {
+        uuid: derived_uuid(&input.client_mutation_id, &first.base_product),
+        name: translation(first.name),
+        store_id: input.store_id,
+        short_description: first.short_description.map(&translation).unwrap_or_default(),
+        long_description: first.long_description.map(&translation),
+        seo_title: None,
+        seo_description: None,
+        currency: input.currency,
+        category_id: first.category_id.unwrap_or_default(),
+        slug: first.slug,
+        variants,
+        selected_attributes,
+        length_cm: first.length_cm,
+        width_cm: first.width_cm,
+        height_cm: first.height_cm,
+        weight_g: first.weight_g,
+    };
+
+    let products = base_product_module::run_create_base_product_with_variants(context, new_base_product).and_then(|base_product| {
+        match base_product.variants {
+            Some(ref variants) if !variants.is_empty() => Ok(variants.clone()),
+            _ => context.get_stores_microservice().get_products_by_base_product(base_product.id),
+        }
+    });
+    let products = match products {
+        Ok(products) => products,
+        Err(e) => {
+            for &i in group {
+                rows[i].errors.push(format!("Base product was not created: {}", e.message()));
+            }
+            return;
+        }
+    };
+
+    let rpc_client = context.get_rest_api_client(Service::Warehouses);
+    for &i in group {
+        let parsed = &mut rows[i];
+        parsed.product_id = products
+            .iter()
+            .find(|product| product.vendor_code == parsed.row.vendor_code)
+            .map(|product| product.id);
+        let product_id = match parsed.product_id {
+            Some(product_id) => product_id,
+            None => {
+                parsed
+                    .errors
+                    .push("Base product was created, but the variant was not found by vendor code.".to_string());
+                continue;
+            }
+        };
+
+        if let (Some(warehouse_id), Some(quantity)) = (parsed.row.warehouse_id.as_ref(), parsed.row.quantity) {
+            let warehouse_id = WarehouseId(Uuid::parse_str(warehouse_id).unwrap_or_else(|_| Uuid::nil()));
+            if let Err(e) = rpc_client
+                .set_product_in_warehouse(warehouse_id, product_id, Quantity(quantity))
+                .sync()
+                .map_err(into_graphql)
+            {
+                parsed
+                    .errors
+                    .push(format!("Product was created, but quantity was not set: {}", e.message()));
+            }
+        }
+    }
+}
+
+/// Uuid derived from the client mutation id of the import and the row key, so that retrying the same import
+/// does not create the products twice. Random uuid is used when the client mutation id is empty
+fn derived_uuid(client_mutation_id: &str, key: &str) -> String {
+    if client_mutation_id.is_empty() {
+        return String::new();
+    }
+
+    let name = format!("{}/{}", client_mutation_id, key);
+    let mut bytes = [0u8; 16];
+    for (half, offset_basis) in [0xcbf2_9ce4_8422_2325u64, 0x6c62_272e_07bb_0142u64].iter().enumerate() {
+        let hash = fnv1a(name.as_bytes(), *offset_basis);
+        for i in 0..8 {
+            bytes[half * 8 + i] = (hash >> (56 - i * 8)) as u8;
+        }
+    }
+    // version 4 and RFC 4122 variant bits, so that the services accept it as any client generated uuid
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    Uuid::from_bytes(&bytes)
+        .map(|uuid| uuid.hyphenated().to_string())
+        .unwrap_or_default()
+}
+
+fn fnv1a(data: &[u8], offset_basis: u64) -> u64 {
+    data.iter()
+        .fold(offset_basis, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derived_uuid_is_stable_per_mutation_and_key() {
+        let uuid = derived_uuid("import-1", "shirt/SH-1");
+        assert_eq!(uuid, derived_uuid("import-1", "shirt/SH-1"));
+        assert_ne!(uuid, derived_uuid("import-1", "shirt/SH-2"));
+        assert_ne!(uuid, derived_uuid("import-2", "shirt/SH-1"));
+
+        let parsed = Uuid::parse_str(&uuid).unwrap();
+        assert_eq!(parsed.get_version_num(), 4);
+    }
+
+    #[test]
+    fn derived_uuid_is_empty_without_client_mutation_id() {
+        assert_eq!(derived_uuid("", "shirt"), "");
+    }
+}
@@ -23,7 +23,9 @@ use graphql::microservice::CalculatePayoutPayload;
 use graphql::models::*;
 use graphql::schema::base_product as base_product_module;
 use graphql::schema::cart as cart_module;
+use graphql::schema::view_history as view_history_module;
 use graphql::schema::warehouse as warehouse_module;
+use graphql::schema::wishlist as wishlist_module;
 use schema::buy_now as buy_now_module;
 use schema::category as category_module;
 use schema::order as order_module;
@@ -77,9 +79,7 @@ graphql_object!(Query: Context |&self| {
 
     field me(&executor) -> FieldResult<Option<User>> as "Fetches viewer for users." {
         let context = executor.context();
-        let url = format!("{}/{}/current",
-            context.config.service_url(Service::Users),
-            Model::User.to_url());
+        let url = context.config.url(Service::Users).model(Model::User).path("current").build();
         context.request::<Option<User>>(Method::Get, url, None)
                     .wait()
     }
@@ -240,9 +240,7 @@ graphql_object!(Query: Context |&self| {
 
     field all_categories(&executor) -> FieldResult<Option<Category>> as "Fetches all categories tree." {
         let context = executor.context();
-        let url = format!("{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::Category.to_url());
+        let url = context.config.url(Service::Stores).model(Model::Category).build();
 
         context.request::<Option<Category>>(Method::Get, url, None)
             .wait()
@@ -255,10 +253,7 @@ graphql_object!(Query: Context |&self| {
 
     field category_by_slug(&executor, category_slug: String) -> FieldResult<Option<Category>> as "Find category by slug" {
         let context = executor.context();
-        let url = format!("{}/{}/by-slug/{}",
-            context.config.service_url(Service::Stores),
-            Model::Category.to_url(),
-            category_slug);
+        let url = context.config.url(Service::Stores).model(Model::Category).path("by-slug").segment(&category_slug).build();
 
         context.request::<Option<Category>>(Method::Get, url, None)
             .wait()
@@ -266,9 +261,7 @@ graphql_object!(Query: Context |&self| {
 
     field countries(&executor) -> FieldResult<Country> as "Fetches country tree." {
         let context = executor.context();
-        let url = format!("{}/{}",
-            context.config.service_url(Service::Delivery),
-            Model::Country.to_url());
+        let url = context.config.url(Service::Delivery).model(Model::Country).build();
 
         context.request::<Country>(Method::Get, url, None)
             .wait()
@@ -276,10 +269,7 @@ graphql_object!(Query: Context |&self| {
 
     field country(&executor, alpha3: String as "Alpha3 code") -> FieldResult<Option<Country>> as "Find country by alpha3 code." {
         let context = executor.context();
-        let url = format!("{}/{}/alpha3/{}",
-            context.config.service_url(Service::Delivery),
-            Model::Country.to_url(),
-            alpha3);
+        let url = context.config.url(Service::Delivery).model(Model::Country).path("alpha3").segment(&alpha3).build();
 
         context.request::<Option<Country>>(Method::Get, url, None)
             .wait()
@@ -297,6 +287,11 @@ graphql_object!(Query: Context |&self| {
         buy_now_module::calculate_buy_now_v1(context, product_id, quantity, coupon_code, shipping_id)
     }
 
+    field checkout_quote(&executor, input: CheckoutQuoteInput) -> FieldResult<CheckoutQuote> as "Freezes checkout prices of the cart or buy now into a signed, time-limited quote." {
+        let context = executor.context();
+        checkout_quote::run_checkout_quote(context, input)
+    }
+
     field calculate_buy_now_v2(&executor, input: CalculateBuyNowInput) -> FieldResult<BuyNowCheckout> as "Calculate values for buy now." {
         let context = executor.context();
         buy_now_module::calculate_buy_now(
@@ -311,8 +306,7 @@ graphql_object!(Query: Context |&self| {
 
     field currency_exchange(&executor) -> FieldResult<Option<Vec<CurrencyExchange>>> as "Fetches currency exchange." {
         let context = executor.context();
-        let url = format!("{}/currency_exchange",
-            context.config.service_url(Service::Stores));
+        let url = context.config.url(Service::Stores).path("currency_exchange").build();
 
         context.request::<Option<CurrencyExchangeInfo>>(Method::Get, url, None)
             .wait().map(|v| {
@@ -322,9 +316,7 @@ graphql_object!(Query: Context |&self| {
 
     field attributes(&executor) -> FieldResult<Option<Vec<Attribute>>> as "Fetches all attributes." {
         let context = executor.context();
-        let url = format!("{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::Attribute.to_url());
+        let url = context.config.url(Service::Stores).model(Model::Attribute).build();
 
         context.request::<Vec<Attribute>>(Method::Get, url, None)
             .wait()
@@ -333,9 +325,7 @@ graphql_object!(Query: Context |&self| {
 
     field custom_attributes(&executor) -> FieldResult<Option<Vec<CustomAttribute>>> as "Fetches all custom attributes." {
         let context = executor.context();
-        let url = format!("{}/{}",
-            context.config.service_url(Service::Stores),
-            Model::CustomAttribute.to_url());
+        let url = context.config.url(Service::Stores).model(Model::CustomAttribute).build();
 
         context.request::<Vec<CustomAttribute>>(Method::Get, url, None)
             .wait()
@@ -353,10 +343,7 @@ graphql_object!(Query: Context |&self| {
     field email_template(&executor, variant: TemplateVariant) -> FieldResult<String> as "Template email message endpoint" {
         let context = executor.context();
 
-        let url = format!(
-            "{}/templates/{}",
-            &context.config.service_url(Service::Notifications),
-            variant);
+        let url = context.config.url(Service::Notifications).path("templates").segment(&variant).build();
 
         context.request::<String>(Method::Get, url, None)
             .wait()
@@ -370,13 +357,7 @@ graphql_object!(Query: Context |&self| {
         let context = executor.context();
         let visibility = visibility.unwrap_or_default();
 
-        let url = format!(
-            "{}/{}/{}?visibility={}",
-            &context.config.service_url(Service::Stores),
-            Model::Store.to_url(),
-            id.to_string(),
-            visibility,
-        );
+        let url = context.config.url(Service::Stores).model(Model::Store).segment(id).query("visibility", &visibility).build();
 
         context.request::<Option<Store>>(Method::Get, url, None)
             .wait()
@@ -390,13 +371,14 @@ graphql_object!(Query: Context |&self| {
         let context = executor.context();
         let visibility = visibility.unwrap_or_default();
 
-        let url = format!(
-            "{}/{}/by-slug/{}?visibility={}",
-            &context.config.service_url(Service::Stores),
-            Model::Store.to_url(),
-            slug,
-            visibility,
-        );
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::Store)
+            .path("by-slug")
+            .segment(&slug)
+            .query("visibility", &visibility)
+            .build();
 
         context.request::<Option<Store>>(Method::Get, url, None)
             .wait()
@@ -410,23 +392,24 @@ graphql_object!(Query: Context |&self| {
         let visibility = visibility.unwrap_or_default();
 
         let url = match visibility {
-            Visibility::Published => format!(
-                "{}/{}/{}/update_view",
-                &context.config.service_url(Service::Stores),
-                Model::BaseProduct.to_url(),
-                id.to_string()
-            ),
-            Visibility::Active => format!(
-                "{}/{}/{}?visibility={}",
-                &context.config.service_url(Service::Stores),
-                Model::BaseProduct.to_url(),
-                id.to_string(),
-                visibility,
-            ),
+            Visibility::Published => context.config.url(Service::Stores).model(Model::BaseProduct).segment(id).path("update_view").build(),
+            Visibility::Active => context
+                .config
+                .url(Service::Stores)
+                .model(Model::BaseProduct)
+                .segment(id)
+                .query("visibility", &visibility)
+                .build(),
         };
 
-        context.request::<Option<BaseProduct>>(Method::Get, url, None)
-            .wait()
+        let base_product = context.request::<Option<BaseProduct>>(Method::Get, url, None)
+            .wait()?;
+
+        if let (Visibility::Published, Some(base_product)) = (visibility, base_product.as_ref()) {
+            view_history_module::record_view(context, base_product.id);
+        }
+
+        Ok(base_product)
     }
 
     field base_products(&executor, input: GetBaseProductsInput as "get base products input") -> FieldResult<Vec<BaseProduct>> as "Fetches base products by ids." {
@@ -459,37 +442,44 @@ graphql_object!(Query: Context |&self| {
         let visibility = visibility.unwrap_or_default();
 
         let url = match visibility {
-            Visibility::Published => format!(
-                "{}/{}/by-slug/{}/{}/by-slug/{}/update_view",
-                &context.config.service_url(Service::Stores),
-                Model::Store.to_url(),
-                store_slug,
-                Model::BaseProduct.to_url(),
-                base_product_slug
-            ),
-            Visibility::Active => format!(
-                "{}/{}/by-slug/{}/{}/by-slug/{}?visibility={}",
-                &context.config.service_url(Service::Stores),
-                Model::Store.to_url(),
-                store_slug,
-                Model::BaseProduct.to_url(),
-                base_product_slug,
-                visibility,
-            ),
+            Visibility::Published => context
+                .config
+                .url(Service::Stores)
+                .model(Model::Store)
+                .path("by-slug")
+                .segment(&store_slug)
+                .model(Model::BaseProduct)
+                .path("by-slug")
+                .segment(&base_product_slug)
+                .path("update_view")
+                .build(),
+            Visibility::Active => context
+                .config
+                .url(Service::Stores)
+                .model(Model::Store)
+                .path("by-slug")
+                .segment(&store_slug)
+                .model(Model::BaseProduct)
+                .path("by-slug")
+                .segment(&base_product_slug)
+                .query("visibility", &visibility)
+                .build(),
         };
 
-        context.request::<Option<BaseProduct>>(Method::Get, url, None)
-            .wait()
+        let base_product = context.request::<Option<BaseProduct>>(Method::Get, url, None)
+            .wait()?;
+
+        if let (Visibility::Published, Some(base_product)) = (visibility, base_product.as_ref()) {
+            view_history_module::record_view(context, base_product.id);
+        }
+
+        Ok(base_product)
     }
 
     field companies(&executor) -> FieldResult<Option<Vec<Company>>> as "Fetches all companies." {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}",
-            &context.config.service_url(Service::Delivery),
-            Model::Company.to_url()
-        );
+        let url = context.config.url(Service::Delivery).model(Model::Company).build();
 
         context.request::<Option<Vec<Company>>>(Method::Get, url, None)
             .wait()
@@ -498,12 +488,7 @@ graphql_object!(Query: Context |&self| {
     field company(&executor, id: i32 as "Int Id of a company.") -> FieldResult<Option<Company>> as "Fetches company by id." {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}/{}",
-            &context.config.service_url(Service::Delivery),
-            Model::Company.to_url(),
-            id.to_string()
-        );
+        let url = context.config.url(Service::Delivery).model(Model::Company).segment(id).build();
 
         context.request::<Option<Company>>(Method::Get, url, None)
             .wait()
@@ -512,12 +497,7 @@ graphql_object!(Query: Context |&self| {
     field package(&executor, id: i32 as "Int Id of a package.") -> FieldResult<Option<Packages>> as "Fetches package by id." {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}/{}",
-            &context.config.service_url(Service::Delivery),
-            Model::Package.to_url(),
-            id.to_string()
-        );
+        let url = context.config.url(Service::Delivery).model(Model::Package).segment(id).build();
 
         context.request::<Option<Packages>>(Method::Get, url, None)
             .wait()
@@ -526,12 +506,7 @@ graphql_object!(Query: Context |&self| {
     field company_package(&executor, id: i32 as "Int Id of a company_package.") -> FieldResult<Option<CompaniesPackages>> as "Fetches company_package by id." {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}/{}",
-            &context.config.service_url(Service::Delivery),
-            Model::CompanyPackage.to_url(),
-            id.to_string()
-        );
+        let url = context.config.url(Service::Delivery).model(Model::CompanyPackage).segment(id).build();
 
         context.request::<Option<CompaniesPackages>>(Method::Get, url, None)
             .wait()
@@ -542,6 +517,32 @@ graphql_object!(Query: Context |&self| {
         cart_module::get_cart(context, None).map(Some)
     }
 
+    field wishlist(&executor,
+        current_page : i32 as "Current page",
+        items_count : i32 as "Items count")
+            -> FieldResult<Connection<WishlistEntry, PageInfoSegments>> as "Fetches wishlist of the user or of the session for anonymous users." {
+        let context = executor.context();
+        let visitor = context.current_visitor("Could not get wishlist for unauthorized user.")?;
+
+        wishlist_module::find_wishlist(context, visitor, current_page, items_count)
+    }
+
+    field recently_viewed(&executor,
+        current_page : i32 as "Current page",
+        items_count : i32 as "Items count")
+            -> FieldResult<Connection<BaseProduct, PageInfoSegments>> as "Fetches recently viewed base products of the user or of the session for anonymous users." {
+        let context = executor.context();
+        let visitor = context.current_visitor("Could not get recently viewed products for unauthorized user.")?;
+
+        view_history_module::find_recently_viewed(context, visitor, current_page, items_count)
+    }
+
+    field view_history_settings(&executor) -> FieldResult<ViewHistorySettings> as "Fetches recently viewed products settings of the user or the session." {
+        let context = executor.context();
+
+        view_history_module::view_history_settings(context)
+    }
+
     field cart_v2(&executor, user_country_code: String as "User country code.") -> FieldResult<Option<Cart>> as "Fetches cart with country." {
         let context = executor.context();
 
@@ -570,10 +571,7 @@ graphql_object!(Query: Context |&self| {
 
     field store_slug_exists(&executor, slug: String as "Stores slug") -> FieldResult<bool> as "Checks store slug" {
         let context = executor.context();
-        let url = format!("{}/{}/slug_exists?slug={}",
-            context.config.service_url(Service::Stores),
-            Model::Store.to_url(),
-            slug);
+        let url = context.config.url(Service::Stores).model(Model::Store).path("slug_exists").query("slug", &slug).build();
         context.request::<bool>(Method::Get, url, None)
             .wait()
     }
@@ -587,12 +585,14 @@ graphql_object!(Query: Context |&self| {
         let context = executor.context();
 
         if !country_code.is_empty() {
-            let url = format!("{}/available_packages?country={}&weight={}&size={}",
-                context.config.service_url(Service::Delivery),
-                country_code,
-                size,
-                weight
-                );
+            let url = context
+                .config
+                .url(Service::Delivery)
+                .path("available_packages")
+                .query("country", &country_code)
+                .query("weight", size)
+                .query("size", weight)
+                .build();
 
             context.request::<Vec<AvailablePackages>>(Method::Get, url, None)
                 .map(From::from)
@@ -608,9 +608,7 @@ graphql_object!(Query: Context |&self| {
     field generate_coupon_code(&executor) -> FieldResult<String> as "New coupon code" {
         let context = executor.context();
 
-        let url = format!("{}/{}/generate_code",
-            context.config.service_url(Service::Stores),
-            Model::Coupon.to_url());
+        let url = context.config.url(Service::Stores).model(Model::Coupon).path("generate_code").build();
 
         context.request::<String>(Method::Get, url, None)
             .wait()
@@ -644,14 +642,16 @@ graphql_object!(Query: Context |&self| {
             graphql_value!({ "code": 300, "details": { "There is no country in warehouse address belonging to this store." }}),
         ))?;
 
-        let url = format!("{}/v2/available_packages_for_user/{}?delivery_from={}&delivery_to={}&volume={}&weight={}",
-            context.config.service_url(Service::Delivery),
-            base_product_id,
-            delivery_from,
-            user_country,
-            volume_cubic_cm,
-            weight_g,
-        );
+        let url = context
+            .config
+            .url(Service::Delivery)
+            .path("v2/available_packages_for_user")
+            .segment(base_product_id)
+            .query("delivery_from", &delivery_from)
+            .query("delivery_to", &user_country)
+            .query("volume", volume_cubic_cm)
+            .query("weight", weight_g)
+            .build();
 
         context.request::<AvailableShippingForUser>(Method::Get, url, None).wait()
     }
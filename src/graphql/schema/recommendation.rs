@@ -0,0 +1,128 @@
+//! "Customers also bought" recommendations
+use std::cmp;
+
+use futures::Future;
+use hyper::Method;
+use juniper::FieldResult;
+
+use stq_routes::model::Model;
+use stq_routes::service::Service;
+use stq_static_resources::ModerationStatus;
+use stq_types::{BaseProductId, CategoryId};
+
+use graphql::context::Context;
+use graphql::microservice::requests::GetBaseProductsRequest;
+use graphql::models::*;
+
+/// Best sellers are fetched by chunks until there are enough of them in the categories
+const BEST_SELLERS_REQUESTS: usize = 3;
+
+/// Products bought by customers who bought any of `base_products`, topped up with
+/// the best sellers of their categories when co-purchases are not enough,
+/// and with the most viewed products of the categories that have too few sales
+pub fn recommend(context: &Context, base_products: &[BaseProduct], count: Option<i32>) -> FieldResult<Vec<BaseProduct>> {
+    let records_limit = context.config.gateway.records_limit;
+    let count = cmp::max(0, cmp::min(count.unwrap_or(records_limit as i32), records_limit as i32)) as usize;
+    let excluded = base_products.iter().map(|base_product| base_product.id).collect::<Vec<_>>();
+    let recommendations = context.get_recommendations_service();
+
+    let mut also_bought = recommendations.also_bought(&excluded)?;
+    also_bought.truncate(count);
+    let found = base_products_by_ids(context, also_bought.clone())?;
+    let mut recommended = also_bought
+        .into_iter()
+        .filter_map(|base_product_id| found.iter().find(|base_product| base_product.id == base_product_id).cloned())
+        .filter(is_published)
+        .collect::<Vec<_>>();
+
+    let mut categories: Vec<CategoryId> = vec![];
+    for base_product in base_products {
+        if !categories.contains(&base_product.category_id) {
+            categories.push(base_product.category_id);
+        }
+    }
+
+    let best_sellers = recommendations.best_sellers()?;
+    for chunk in best_sellers.chunks(cmp::max(records_limit, 1)).take(BEST_SELLERS_REQUESTS) {
+        if recommended.len() >= count {
+            break;
+        }
+
+        let ids = chunk
+            .iter()
+            .cloned()
+            .filter(|id| !excluded.contains(id) && !recommended.iter().any(|recommended| recommended.id == *id))
+            .collect::<Vec<_>>();
+        let found = base_products_by_ids(context, ids.clone())?;
+        for id in ids {
+            if recommended.len() >= count {
+                break;
+            }
+            let best_seller = found
+                .iter()
+                .find(|base_product| base_product.id == id && categories.contains(&base_product.category_id) && is_published(base_product));
+            if let Some(best_seller) = best_seller {
+                recommended.push(best_seller.clone());
+            }
+        }
+    }
+
+    for category_id in categories {
+        if recommended.len() >= count {
+            break;
+        }
+
+        // fetch more to have enough after skipping the excluded and already recommended products
+        let fetch_count = count + excluded.len() + recommended.len();
+        for base_product in category_most_viewed(context, category_id, fetch_count as i32)? {
+            if recommended.len() >= count {
+                break;
+            }
+            let is_new = !excluded.contains(&base_product.id) && !recommended.iter().any(|recommended| recommended.id == base_product.id);
+            if is_new {
+                recommended.push(base_product);
+            }
+        }
+    }
+
+    Ok(recommended)
+}
+
+pub fn base_products_by_ids(context: &Context, ids: Vec<BaseProductId>) -> FieldResult<Vec<BaseProduct>> {
+    if ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    context.get_stores_microservice().get_base_products(GetBaseProductsRequest { ids })
+}
+
+fn category_most_viewed(context: &Context, category_id: CategoryId, count: i32) -> FieldResult<Vec<BaseProduct>> {
+    let url = context
+        .config
+        .url(Service::Stores)
+        .model(Model::BaseProduct)
+        .path("search")
+        .query("offset", 0)
+        .query("count", count)
+        .query("visibility", Visibility::Published)
+        .build();
+    let search_term = SearchProductInput {
+        name: String::new(),
+        options: Some(ProductsSearchOptionsInput {
+            category_id: Some(category_id.0),
+            sort_by: Some(ProductsSorting::Views),
+            status: Some(ModerationStatus::Published),
+            ..ProductsSearchOptionsInput::default()
+        }),
+    };
+    let body = serde_json::to_string(&search_term)?;
+
+    context.request::<Vec<BaseProduct>>(Method::Post, url, Some(body)).wait()
+}
+
+fn is_published(base_product: &BaseProduct) -> bool {
+    match base_product.status {
+        ModerationStatus::Published => base_product.is_active,
+        _ => false,
+    }
+}
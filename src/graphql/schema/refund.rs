@@ -0,0 +1,151 @@
+//! File containing refund object of graphql schema
+use juniper::{FieldError, FieldResult};
+
+use stq_api::orders::Order;
+use stq_static_resources::{Currency, OrderState};
+use stq_types::{OrderIdentifier, OrderSlug};
+
+use errors::FORBIDDEN_CODE;
+use graphql::context::Context;
+use graphql::models::*;
+use graphql::schema::order as order_module;
+
+graphql_object!(Refund: Context as "Refund" |&self| {
+    description: "Money returned to the buyer."
+
+    field raw_id() -> &i32 as "Unique int id" {
+        &self.id.0
+    }
+
+    field order(&executor) -> FieldResult<Option<GraphQLOrder>> as "Refunded order" {
+        executor.context().get_orders_microservice().get_order_by_id(self.order_id)
+    }
+
+    field order_billing(&executor) -> FieldResult<Option<OrderBilling>> as "Order billing after the refund" {
+        executor.context().get_billing_microservice().order(self.order_id)
+    }
+
+    field fee(&executor) -> FieldResult<Option<Fee>> as "Fee recalculated after the refund" {
+        executor.context().get_billing_microservice().get_fee_by_order_id(self.order_id)
+    }
+
+    field amount() -> Money as "Refunded amount" {
+        Money::from_f64(self.amount, self.currency).round()
+    }
+
+    field currency() -> &Currency as "Currency" {
+        &self.currency
+    }
+
+    field method() -> &RefundMethod as "Refund method" {
+        &self.method
+    }
+
+    field status() -> &RefundStatus as "Status" {
+        &self.status
+    }
+
+    field reason() -> &str as "Reason" {
+        &self.reason
+    }
+
+    field fee_amount() -> Money as "Part of the fee returned to the seller" {
+        Money::from_f64(self.fee_amount, self.currency).round()
+    }
+
+    field created_at() -> String as "Created at" {
+        self.created_at.to_rfc3339()
+    }
+});
+
+pub fn run_refund_order_mutation(context: &Context, input: RefundOrderInput) -> FieldResult<Refund> {
+    if context.user.is_none() {
+        return Err(FieldError::new(
+            "Could not refund order for unauthorized user.",
+            graphql_value!({ "code": 100, "details": { "No user id in request header." }}),
+        ));
+    }
+
+    let order = order_module::get_order(context, OrderIdentifier::Slug(OrderSlug(input.order_slug)))?.0;
+    refund_order(context, &order, input.amount, input.reason)
+}
+
+/// Refunds the whole remaining amount of the order or the given part of it
+pub fn refund_order(context: &Context, order: &Order, amount: Option<f64>, reason: String) -> FieldResult<Refund> {
+    check_can_refund(context, order)?;
+    make_refund(context, order, amount, reason)
+}
+
+/// Refunds the whole disputed order resolving the dispute, the caller must check that the current user
+/// is allowed to resolve it
+pub fn refund_disputed_order(context: &Context, order: &Order, reason: String) -> FieldResult<Refund> {
+    if order.state != OrderState::Dispute {
+        return Err(FieldError::new(
+            "Could not refund order.",
+            graphql_value!({ "code": 100, "details": { format!("Order {} is not disputed.", order.slug) }}),
+        ));
+    }
+    make_refund(context, order, None, reason)
+}
+
+fn make_refund(context: &Context, order: &Order, amount: Option<f64>, reason: String) -> FieldResult<Refund> {
+    let order_slug = order.slug;
+    let billing = context.get_billing_microservice();
+    let order_billing = billing.order(order.id)?.ok_or_else(|| {
+        FieldError::new(
+            "Order billing not found",
+            graphql_value!({ "code": 400, "details": { format!("billing of order {} not found", order_slug) }}),
+        )
+    })?;
+
+    match order_billing.state {
+        PaymentState::Captured | PaymentState::RefundNeeded | PaymentState::PaymentToSellerNeeded => {}
+        state => {
+            return Err(FieldError::new(
+                "Could not refund order.",
+                graphql_value!({ "code": 100, "details": { format!("Order {} can not be refunded in payment state {:?}.", order_slug, state) }}),
+            ))
+        }
+    }
+
+    let currency = order_billing.seller_currency;
+    let remaining = Money::from_f64(order_billing.total_amount, currency)
+        .checked_sub(Money::from_f64(order_billing.refunded_amount, currency))?
+        .round();
+    if remaining.amount <= Money::zero(currency).amount {
+        return Err(FieldError::new(
+            "Could not refund order.",
+            graphql_value!({ "code": 100, "details": { format!("Order {} is already refunded.", order_slug) }}),
+        ));
+    }
+
+    let amount = match amount {
+        Some(amount) => {
+            let amount = Money::from_f64(amount, currency).round();
+            if amount.amount <= Money::zero(currency).amount || amount.amount > remaining.amount {
+                return Err(FieldError::new(
+                    "Could not refund order.",
+                    graphql_value!({ "code": 100, "details": {
+                        format!("Refund amount must be greater than 0 and not greater than {} {}.", remaining, currency.code())
+                    }}),
+                ));
+            }
+            Some(amount.to_f64())
+        }
+        None => None,
+    };
+
+    billing.refund_order(order.id, NewRefund { amount, reason })
+}
+
+/// Refunds are made by financial managers and by managers of the store the order belongs to
+fn check_can_refund(context: &Context, order: &Order) -> FieldResult<()> {
+    if context.permissions().manages_store_billing(order.store)? {
+        return Ok(());
+    }
+
+    Err(FieldError::new(
+        "Could not refund order.",
+        graphql_value!({ "code": FORBIDDEN_CODE, "details": { "Only store managers and financial managers can refund orders." }}),
+    ))
+}
@@ -0,0 +1,234 @@
+//! File containing review object of graphql schema
+use std::cmp;
+
+use juniper;
+use juniper::ID as GraphqlID;
+use juniper::{FieldError, FieldResult};
+
+use stq_static_resources::OrderState;
+use stq_types::{OrderIdentifier, OrderSlug, StoresRole};
+
+use errors::FORBIDDEN_CODE;
+use graphql::context::Context;
+use graphql::models::*;
+use graphql::schema::base_product as base_product_module;
+use graphql::schema::order as order_module;
+use graphql::schema::store as store_module;
+
+graphql_object!(Review: Context as "Review" |&self| {
+    description: "Review of the bought product."
+
+    field raw_id() -> &i32 as "Unique int id" {
+        &self.id.0
+    }
+
+    field user_id() -> &i32 as "Author int id" {
+        &self.user_id.0
+    }
+
+    field store_id() -> &i32 as "Store int id" {
+        &self.store_id.0
+    }
+
+    field base_product_id() -> &i32 as "Base product int id" {
+        &self.base_product_id.0
+    }
+
+    field product_id() -> &i32 as "Bought product variant int id" {
+        &self.product_id.0
+    }
+
+    field base_product(&executor) -> FieldResult<Option<BaseProduct>> as "Base product" {
+        base_product_module::try_get_base_product(executor.context(), self.base_product_id, Visibility::Active)
+    }
+
+    field store(&executor) -> FieldResult<Option<Store>> as "Store" {
+        store_module::try_get_store(executor.context(), self.store_id, Visibility::Active)
+    }
+
+    field rating() -> &i32 as "Rating from 1 to 5" {
+        &self.rating
+    }
+
+    field text() -> &str as "Review text" {
+        &self.text
+    }
+
+    field reply() -> &Option<String> as "Reply of the seller" {
+        &self.reply
+    }
+
+    field replied_at() -> Option<String> as "Replied at" {
+        self.replied_at.map(|replied_at| replied_at.to_rfc3339())
+    }
+
+    field is_hidden() -> &bool as "Hidden by moderator" {
+        &self.is_hidden
+    }
+
+    field created_at() -> String as "Created at" {
+        self.created_at.to_rfc3339()
+    }
+
+    field updated_at() -> String as "Updated at" {
+        self.updated_at.to_rfc3339()
+    }
+});
+
+graphql_object!(Connection<Review, PageInfoReviews>: Context as "ReviewConnectionPages" |&self| {
+    description: "Review Connection"
+
+    field edges() -> &[Edge<Review>] {
+        &self.edges
+    }
+
+    field page_info() -> &PageInfoReviews {
+        &self.page_info
+    }
+});
+
+graphql_object!(Edge<Review>: Context as "ReviewEdge" |&self| {
+    description:"Review Edge"
+
+    field cursor() -> &juniper::ID {
+        &self.cursor
+    }
+
+    field node() -> &Review {
+        &self.node
+    }
+});
+
+/// Hidden reviews are found only for moderators
+pub fn find_reviews(
+    context: &Context,
+    current_page: i32,
+    items_count: i32,
+    mut search: ReviewSearch,
+) -> FieldResult<Connection<Review, PageInfoReviews>> {
+    search.include_hidden = is_moderator(context)?;
+
+    let current_page = cmp::max(current_page, 1);
+    let records_limit = context.config.gateway.records_limit;
+    let items_count = cmp::max(1, cmp::min(items_count, records_limit as i32));
+    let skip = items_count * (current_page - 1);
+    let reviews = context.get_stores_microservice().search_reviews(skip, items_count, search)?;
+    let total_pages = cmp::max(0, reviews.total_count as i32 - 1) / items_count + 1;
+    let edges = reviews
+        .reviews
+        .into_iter()
+        .map(|review| Edge::new(GraphqlID::from(review.id.to_string()), review))
+        .collect();
+    let page_info = PageInfoReviews {
+        current_page,
+        page_items_count: items_count,
+        total_pages,
+        total_count: reviews.total_count as i32,
+        rating_distribution: reviews.rating_distribution,
+    };
+    Ok(Connection::new(edges, page_info))
+}
+
+/// Only the buyer of the complete order can review its product, once per order
+pub fn run_create_review_mutation(context: &Context, input: CreateReviewInput) -> FieldResult<Review> {
+    let user_id = context.current_user_id("Could not create review for unauthorized user.")?;
+    if input.rating < MIN_RATING || input.rating > MAX_RATING {
+        return Err(review_error(format!("Rating must be from {} to {}.", MIN_RATING, MAX_RATING)));
+    }
+
+    let order_slug = OrderSlug(input.order_slug);
+    let order = order_module::get_order(context, OrderIdentifier::Slug(order_slug))?.0;
+    if order.customer != user_id {
+        return Err(FieldError::new(
+            "Could not create review.",
+            graphql_value!({ "code": FORBIDDEN_CODE, "details": { format!("Order {} belongs to another customer.", order_slug) }}),
+        ));
+    }
+    if order.state != OrderState::Complete {
+        return Err(review_error(format!("Order {} is not complete.", order_slug)));
+    }
+
+    let stores = context.get_stores_microservice();
+    let existing = stores.search_reviews(
+        0,
+        1,
+        ReviewSearch {
+            order_id: Some(order.id),
+            include_hidden: true,
+            ..Default::default()
+        },
+    )?;
+    if existing.total_count > 0 {
+        return Err(review_error(format!("Order {} is already reviewed.", order_slug)));
+    }
+
+    let base_product = base_product_module::get_base_product_by_product(context, order.product)?;
+    stores.create_review(NewReview {
+        user_id,
+        order_id: order.id,
+        store_id: order.store,
+        base_product_id: base_product.id,
+        product_id: order.product,
+        rating: input.rating,
+        text: input.text,
+    })
+}
+
+pub fn run_reply_to_review_mutation(context: &Context, input: ReplyToReviewInput) -> FieldResult<Review> {
+    let user_id = context.current_user_id("Could not reply to review for unauthorized user.")?;
+    let review = get_review(context, ReviewId(input.review_id))?;
+    let is_store_owner = store_module::try_get_store(context, review.store_id, Visibility::Active)?
+        .map(|store| store.user_id == user_id)
+        .unwrap_or(false);
+    if !is_store_owner {
+        return Err(FieldError::new(
+            "Could not reply to review.",
+            graphql_value!({ "code": FORBIDDEN_CODE, "details": { "Only the seller can reply to the review." }}),
+        ));
+    }
+
+    context.get_stores_microservice().update_review(
+        review.id,
+        UpdateReview {
+            reply: Some(input.reply),
+            ..Default::default()
+        },
+    )
+}
+
+pub fn run_set_review_hidden_mutation(context: &Context, input: SetReviewHiddenInput) -> FieldResult<Review> {
+    context.current_user_id("Could not hide review for unauthorized user.")?;
+    if !is_moderator(context)? {
+        return Err(FieldError::new(
+            "Could not hide review.",
+            graphql_value!({ "code": FORBIDDEN_CODE, "details": { "Only moderator can hide reviews." }}),
+        ));
+    }
+
+    let review = get_review(context, ReviewId(input.review_id))?;
+    context.get_stores_microservice().update_review(
+        review.id,
+        UpdateReview {
+            is_hidden: Some(input.hidden),
+            ..Default::default()
+        },
+    )
+}
+
+fn get_review(context: &Context, review_id: ReviewId) -> FieldResult<Review> {
+    context.get_stores_microservice().get_review(review_id)?.ok_or_else(|| {
+        FieldError::new(
+            "Review not found",
+            graphql_value!({ "code": 400, "details": { format!("review by id: {} not found", review_id) }}),
+        )
+    })
+}
+
+fn is_moderator(context: &Context) -> FieldResult<bool> {
+    let roles = context.permissions().store_roles()?;
+    Ok(roles.contains(&StoresRole::Superuser) || roles.contains(&StoresRole::Moderator))
+}
+
+fn review_error(details: String) -> FieldError {
+    FieldError::new("Could not process review.", graphql_value!({ "code": 100, "details": { details }}))
+}
@@ -0,0 +1,185 @@
+//! File containing saved search object of graphql schema
+use chrono::prelude::*;
+use chrono::Duration;
+use futures::Future;
+use hyper::Method;
+use juniper::{FieldError, FieldResult};
+
+use stq_routes::model::Model;
+use stq_routes::service::Service;
+use stq_static_resources::ModerationStatus;
+use stq_types::{BaseProductId, UserId};
+
+use errors::FORBIDDEN_CODE;
+use graphql::context::Context;
+use graphql::microservice::requests::{ClaimSavedSearchesRequest, SearchPublishedAfterRequest};
+use graphql::models::*;
+
+const MAX_SAVED_SEARCHES: usize = 20;
+/// New products notified at once, the rest are not notified
+const MAX_MATCHES: i32 = 100;
+/// Saved searches claimed at once by the alerts job
+const ALERTS_BATCH_SIZE: i32 = 100;
+
+graphql_object!(SavedSearch: Context as "SavedSearch" |&self| {
+    description: "Products search saved by the buyer."
+
+    field raw_id() -> &i32 as "Unique int id" {
+        &self.id.0
+    }
+
+    field name() -> &str as "Name of the search" {
+        &self.name
+    }
+
+    field search_filters() -> ProductsSearchFilters as "Search pattern with filters" {
+        ProductsSearchFilters::new(self.search_term.clone())
+    }
+
+    field alerts_enabled() -> &bool as "New products found by the search are notified" {
+        &self.alerts_enabled
+    }
+
+    field checked_at() -> String as "Last time new products were looked for" {
+        self.checked_at.to_rfc3339()
+    }
+
+    field created_at() -> String as "Created at" {
+        self.created_at.to_rfc3339()
+    }
+});
+
+pub fn saved_searches(context: &Context, user_id: UserId) -> FieldResult<Vec<SavedSearch>> {
+    context.get_stores_microservice().saved_searches(user_id)
+}
+
+/// Products published before the search is saved are not new matches
+pub fn run_save_search_mutation(context: &Context, input: SaveSearchInput) -> FieldResult<SavedSearch> {
+    let user_id = context.current_user_id("Could not save search for unauthorized user.")?;
+
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err(saved_search_error("Name of the search is empty.".to_string()));
+    }
+
+    let stores = context.get_stores_microservice();
+    if stores.saved_searches(user_id)?.len() >= MAX_SAVED_SEARCHES {
+        return Err(saved_search_error(format!("User can not have more than {} saved searches.", MAX_SAVED_SEARCHES)));
+    }
+
+    stores.create_saved_search(NewSavedSearch {
+        user_id,
+        name,
+        search_term: input.search_term,
+        alerts_enabled: input.alerts_enabled.unwrap_or(true),
+    })
+}
+
+pub fn run_delete_saved_search_mutation(context: &Context, input: DeleteSavedSearchInput) -> FieldResult<SavedSearch> {
+    let user_id = context.current_user_id("Could not delete saved search for unauthorized user.")?;
+    let saved_search_id = SavedSearchId(input.saved_search_id);
+
+    let stores = context.get_stores_microservice();
+    let saved_search = stores.get_saved_search(saved_search_id)?.ok_or_else(|| {
+        FieldError::new(
+            "Could not find saved search.",
+            graphql_value!({ "code": 400, "details": { format!("Saved search with id {} does not exist.", saved_search_id) }}),
+        )
+    })?;
+    if saved_search.user_id != user_id {
+        return Err(FieldError::new(
+            "Could not delete saved search.",
+            graphql_value!({ "code": FORBIDDEN_CODE, "details": { "Saved search belongs to another user." }}),
+        ));
+    }
+
+    stores.delete_saved_search(saved_search_id)?;
+    Ok(saved_search)
+}
+
+/// Claims saved searches with alerts enabled checked more than half an interval ago and notifies
+/// their users about new matches. Every gateway replica runs the job, claimed searches are skipped
+/// by the other replicas until they are checked or the claim expires after an interval.
+/// Errors are logged per saved search, so that one failure does not stop the others,
+/// failed searches stay claimed and are checked again after the claim expires
+pub fn notify_new_matches(context: &Context) {
+    let stores = context.get_stores_microservice();
+    let interval_s = context.config.saved_searches.alerts_interval_s;
+    loop {
+        let claimed = stores.claim_saved_searches(ClaimSavedSearchesRequest {
+            count: ALERTS_BATCH_SIZE,
+            checked_before: Utc::now() - Duration::seconds(interval_s as i64 / 2),
+            claim_duration_s: interval_s,
+        });
+        let saved_searches = match claimed {
+            Ok(saved_searches) => saved_searches,
+            Err(e) => {
+                error!("Could not claim saved searches for alerts: {:?}", e);
+                return;
+            }
+        };
+        if saved_searches.is_empty() {
+            return;
+        }
+
+        for saved_search in saved_searches {
+            let saved_search_id = saved_search.id;
+            if let Err(e) = check_saved_search(context, saved_search) {
+                warn!("Could not check saved search {} for new matches: {:?}", saved_search_id, e);
+            }
+        }
+    }
+}
+
+fn check_saved_search(context: &Context, saved_search: SavedSearch) -> FieldResult<()> {
+    // products published while the search runs are found by the next check
+    let checked_at = Utc::now();
+    let new_base_product_ids = search_matches(context, &saved_search.search_term, saved_search.checked_at)?;
+
+    if !new_base_product_ids.is_empty() {
+        context.get_notifications_microservice().send_saved_search_matches(SavedSearchMatches {
+            user_id: saved_search.user_id,
+            saved_search_id: saved_search.id,
+            saved_search_name: saved_search.name.clone(),
+            base_product_ids: new_base_product_ids,
+        })?;
+    }
+
+    context
+        .get_stores_microservice()
+        .update_saved_search(saved_search.id, UpdateSavedSearch { checked_at })?;
+    Ok(())
+}
+
+/// Base products found by the search and published after the time
+fn search_matches(context: &Context, search_term: &SearchProductInput, published_after: DateTime<Utc>) -> FieldResult<Vec<BaseProductId>> {
+    let url = context
+        .config
+        .url(Service::Stores)
+        .model(Model::BaseProduct)
+        .path("search/published_after")
+        .query("offset", 0)
+        .query("count", MAX_MATCHES)
+        .query("visibility", Visibility::Published)
+        .build();
+
+    let mut search_term = search_term.clone();
+    let mut options = search_term.options.unwrap_or_default();
+    options.status = Some(ModerationStatus::Published);
+    search_term.options = Some(options);
+    search_term.name = context.synonyms().expand(&search_term.name);
+
+    let body = serde_json::to_string(&SearchPublishedAfterRequest {
+        search_term,
+        published_after,
+    })?;
+
+    context
+        .request::<Vec<BaseProduct>>(Method::Post, url, Some(body))
+        .wait()
+        .map(|base_products| base_products.into_iter().map(|base_product| base_product.id).collect())
+}
+
+fn saved_search_error(details: String) -> FieldError {
+    FieldError::new("Could not save search.", graphql_value!({ "code": 100, "details": { details }}))
+}
@@ -1,11 +1,10 @@
 //! File containing search object of graphql schema
 use std::cmp;
-use std::str::FromStr;
 
 use futures::future;
 use futures::Future;
 use hyper::Method;
-use juniper::FieldResult;
+use juniper::{FieldError, FieldResult};
 use juniper::ID as GraphqlID;
 use serde_json;
 
@@ -16,13 +15,21 @@ use stq_types::CategoryId;
 
 use graphql::context::Context;
 use graphql::models::*;
+use graphql::schema::geo_search as geo_search_module;
+use graphql::schema::product_code as product_code_module;
+use graphql::schema::search_suggestion as search_suggestion_module;
+
+const DEFAULT_PRICE_BUCKETS: i32 = 10;
+const MAX_PRICE_BUCKETS: i32 = 50;
 
 graphql_object!(Search: Context as "Search" |&self| {
     description: "Searching endpoint."
 
     field find_product(&executor,
         first = None : Option<i32> as "First edges",
-        after = None : Option<GraphqlID>  as "Offset form beginning",
+        after = None : Option<GraphqlID>  as "Cursor of the edge to start after",
+        last = None : Option<i32> as "Last edges",
+        before = None : Option<GraphqlID>  as "Cursor of the edge to end before",
         search_term : SearchProductInput as "Search pattern",
         visibility: Option<Visibility> as "Specifies allowed visibility of the base product"
     ) -> FieldResult<Option<Connection<BaseProduct, PageInfoProductsSearch>>> as "Find products by name using relay connection." {
@@ -30,23 +37,23 @@ graphql_object!(Search: Context as "Search" |&self| {
         let context = executor.context();
         let visibility = visibility.unwrap_or_default();
 
-        let offset = after
-            .and_then(|id|{
-                i32::from_str(&id).map(|i| i + 1).ok()
-            })
-            .unwrap_or_default();
-
-
-        let records_limit = context.config.gateway.records_limit;
-        let count = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
-
-        let url = format!("{}/{}/search?offset={}&count={}&visibility={}",
-            context.config.service_url(Service::Stores),
-            Model::BaseProduct.to_url(),
-            offset,
-            count + 1,
-            visibility
-        );
+        let near = search_term.options.as_ref().and_then(|options| options.near.clone());
+        let sort_by = search_term.options.as_ref().and_then(|options| options.sort_by.as_ref()).map(|sort_by| format!("{:?}", sort_by));
+        let sort_key = match near {
+            Some(ref near) => {
+                geo_search_module::validate(near)?;
+                match (near.sort_by_distance(), sort_by) {
+                    (true, Some(_)) => return Err(FieldError::new(
+                        "Could not search by distance.",
+                        graphql_value!({ "code": 100, "details": { "Products can not be sorted by distance and by sort_by at once." }}),
+                    )),
+                    (true, None) => Some(geo_search_module::DISTANCE_SORT_KEY.to_string()),
+                    (false, sort_by) => sort_by,
+                }
+            }
+            None => sort_by,
+        };
+        let page = PageRequest::new(first, after, last, before, sort_key, context.config.gateway.records_limit)?;
 
         let mut options = search_term.options.clone().unwrap_or_default();
 
@@ -56,31 +63,80 @@ graphql_object!(Search: Context as "Search" |&self| {
 
         let mut search_term = search_term;
         search_term.options = Some(options);
+        // synonyms widen only the request to stores, search filters return the query of the user
+        let mut expanded_term = search_term.clone();
+        expanded_term.name = context.synonyms().expand(&search_term.name);
+
+        let body = serde_json::to_string(&expanded_term)?;
+
+        let page = match near {
+            Some(_) => page,
+            None => page.resolve_from_end(|| {
+                let url = context.config.url(Service::Stores).model(Model::BaseProduct).path("search/filters/count").build();
+                context.request::<i32>(Method::Post, url, Some(body.clone())).wait()
+            })?,
+        };
 
-        let body = serde_json::to_string(&search_term)?;
+        // results are filtered by distance in the gateway, so the nearest candidates are fetched from the start
+        let (offset, count) = match near {
+            Some(_) => (0, geo_search_module::GEO_CANDIDATES_LIMIT),
+            None => (page.offset, page.fetch_count()),
+        };
 
-        context.request::<Vec<BaseProduct>>(Method::Post, url, Some(body))
-            .map (|products| {
-                let mut product_edges = Edge::create_vec(products, offset);
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::BaseProduct)
+            .path("search")
+            .query("offset", offset)
+            .query("count", count)
+            .query("visibility", &visibility)
+            .build();
+
+        let mut products = context.request::<Vec<BaseProduct>>(Method::Post, url, Some(body)).wait()?;
+
+        let page = match near {
+            Some(ref near) => {
+                let located = geo_search_module::by_distance(context, near, products, |base_product| base_product.store_id)?;
+                let page = page.with_total_count(located.len() as i32);
+                products = located
+                    .into_iter()
+                    .skip(page.offset as usize)
+                    .take(page.fetch_count() as usize)
+                    .collect();
+                page
+            }
+            None => page,
+        };
 
-                let search_filters = ProductsSearchFilters::new(search_term);
-                let has_next_page = product_edges.len() as i32 == count + 1;
-                if has_next_page {
-                    product_edges.pop();
-                };
-                let has_previous_page = true;
-                let start_cursor =  product_edges.get(0).map(|e| e.cursor.clone());
-                let end_cursor = product_edges.iter().last().map(|e| e.cursor.clone());
-                let page_info = PageInfoProductsSearch {
-                    has_next_page,
-                    has_previous_page,
-                    search_filters: Some(search_filters),
-                    start_cursor,
-                    end_cursor};
-                Connection::new(product_edges, page_info)
-            })
-            .wait()
-            .map(Some)
+        let did_you_mean = if products.is_empty() && page.offset == 0 {
+            search_suggestion_module::did_you_mean(context, &search_term, &visibility)
+        } else {
+            vec![]
+        };
+
+        let (product_edges, page_info) = page.edges(products, |base_product| Some(base_product.id.0));
+
+        let search_filters = ProductsSearchFilters::new(search_term);
+        let page_info = PageInfoProductsSearch {
+            has_next_page: page_info.has_next_page,
+            has_previous_page: page_info.has_previous_page,
+            search_filters: Some(search_filters),
+            start_cursor: page_info.start_cursor,
+            end_cursor: page_info.end_cursor,
+            did_you_mean,
+        };
+        Ok(Some(Connection::new(product_edges, page_info)))
+    }
+
+    field find_product_by_code(&executor,
+        code: String as "Vendor code, EAN/UPC barcode or store slug",
+        first = None : Option<i32> as "First matches",
+        visibility: Option<Visibility> as "Specifies allowed visibility of the products and the store"
+    ) -> FieldResult<Vec<CodeMatch>> as "Finds products by vendor code or barcode and stores by slug, exact matches first." {
+        let context = executor.context();
+
+        product_code_module::find_by_code(context, &code, visibility.unwrap_or_default(), first)
     }
 
     field auto_complete_product_name(&executor,
@@ -91,21 +147,19 @@ graphql_object!(Search: Context as "Search" |&self| {
 
         let context = executor.context();
 
-        let offset = after
-            .and_then(|id|{
-                i32::from_str(&id).map(|i| i + 1).ok()
-            })
-            .unwrap_or_default();
+        let offset = Cursor::offset_after(after)?;
 
         let records_limit = context.config.gateway.records_limit;
         let count = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
 
-        let url = format!("{}/{}/auto_complete?offset={}&count={}",
-            context.config.service_url(Service::Stores),
-            Model::BaseProduct.to_url(),
-            offset,
-            count + 1,
-            );
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::BaseProduct)
+            .path("auto_complete")
+            .query("offset", offset)
+            .query("count", count + 1)
+            .build();
 
         let search_term = AutoCompleteProductNameInput {
             name,
@@ -122,14 +176,15 @@ graphql_object!(Search: Context as "Search" |&self| {
                 if has_next_page {
                     full_name_edges.pop();
                 };
-                let has_previous_page = true;
+                let has_previous_page = offset > 0;
                 let start_cursor =  full_name_edges.get(0).map(|e| e.cursor.clone());
                 let end_cursor = full_name_edges.iter().last().map(|e| e.cursor.clone());
                 let page_info = PageInfo {
                     has_next_page,
                     has_previous_page,
                     start_cursor,
-                    end_cursor};
+                    end_cursor,
+                    total_count: None};
                 Connection::new(full_name_edges, page_info)
             })
             .wait()
@@ -138,7 +193,9 @@ graphql_object!(Search: Context as "Search" |&self| {
 
     field find_store(&executor,
         first = None : Option<i32> as "First edges",
-        after = None : Option<GraphqlID>  as "Offset form beginning",
+        after = None : Option<GraphqlID>  as "Cursor of the edge to start after",
+        last = None : Option<i32> as "Last edges",
+        before = None : Option<GraphqlID>  as "Cursor of the edge to end before",
         search_term : SearchStoreInput as "Search store input",
         visibility: Option<Visibility> as "Specifies allowed visibility of the store"
     ) -> FieldResult<Option<Connection<Store, PageInfoStoresSearch>>> as "Finds stores by name using relay connection." {
@@ -146,46 +203,70 @@ graphql_object!(Search: Context as "Search" |&self| {
         let context = executor.context();
         let visibility = visibility.unwrap_or_default();
 
-        let offset = after
-            .and_then(|id|{
-                i32::from_str(&id).map(|i| i + 1).ok()
-            })
-            .unwrap_or_default();
-
-        let records_limit = context.config.gateway.records_limit;
-        let count = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
+        let near = search_term.options.as_ref().and_then(|options| options.near.clone());
+        let sort_key = match near {
+            Some(ref near) => {
+                geo_search_module::validate(near)?;
+                if near.sort_by_distance() {
+                    Some(geo_search_module::DISTANCE_SORT_KEY.to_string())
+                } else {
+                    None
+                }
+            }
+            None => None,
+        };
+        let page = PageRequest::new(first, after, last, before, sort_key, context.config.gateway.records_limit)?;
 
         let body = serde_json::to_string(&search_term)?;
 
-        println!("{}", body);
+        let page = match near {
+            Some(_) => page,
+            None => page.resolve_from_end(|| {
+                let url = context.config.url(Service::Stores).model(Model::Store).path("search/filters/count").build();
+                context.request::<i32>(Method::Post, url, Some(body.clone())).wait()
+            })?,
+        };
 
-        let url = format!("{}/{}/search?offset={}&count={}&visibility={}",
-            context.config.service_url(Service::Stores),
-            Model::Store.to_url(),
-            offset,
-            count + 1,
-            visibility
-        );
+        // results are filtered by distance in the gateway, so the nearest candidates are fetched from the start
+        let (offset, count) = match near {
+            Some(_) => (0, geo_search_module::GEO_CANDIDATES_LIMIT),
+            None => (page.offset, page.fetch_count()),
+        };
+
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::Store)
+            .path("search")
+            .query("offset", offset)
+            .query("count", count)
+            .query("visibility", &visibility)
+            .build();
 
         context.request::<Vec<Store>>(Method::Post, url, Some(body))
-            .and_then (|stores| {
-                let mut store_edges = Edge::create_vec(stores, offset);
-                let has_next_page = store_edges.len() as i32 == count + 1;
-                if has_next_page {
-                    store_edges.pop();
-                };
-                let has_previous_page = true;
-                let start_cursor =  store_edges.get(0).map(|e| e.cursor.clone());
-                let end_cursor = store_edges.iter().last().map(|e| e.cursor.clone());
+            .and_then(|stores| match near {
+                Some(ref near) => geo_search_module::by_distance(context, near, stores, |store| store.id).map(|stores| {
+                    let page = page.clone().with_total_count(stores.len() as i32);
+                    let stores = stores
+                        .into_iter()
+                        .skip(page.offset as usize)
+                        .take(page.fetch_count() as usize)
+                        .collect();
+                    (page, stores)
+                }),
+                None => Ok((page.clone(), stores)),
+            })
+            .and_then (|(page, stores): (PageRequest, Vec<Store>)| {
+                let (store_edges, page_info) = page.edges(stores, |store| Some(store.id.0));
 
                 let search_filters = StoresSearchFilters::new(search_term);
 
                 let page_info = PageInfoStoresSearch {
-                        has_next_page,
-                        has_previous_page,
+                        has_next_page: page_info.has_next_page,
+                        has_previous_page: page_info.has_previous_page,
                         search_filters,
-                        start_cursor,
-                        end_cursor
+                        start_cursor: page_info.start_cursor,
+                        end_cursor: page_info.end_cursor
                     };
 
                 future::ok(Connection::new(store_edges, page_info))
@@ -201,22 +282,20 @@ graphql_object!(Search: Context as "Search" |&self| {
             -> FieldResult<Option<Connection<String, PageInfo>>> as "Finds stores full name by part of the name." {
         let context = executor.context();
 
-        let offset = after
-            .and_then(|id|{
-                i32::from_str(&id).map(|i| i + 1).ok()
-            })
-            .unwrap_or_default();
+        let offset = Cursor::offset_after(after)?;
 
 
         let records_limit = context.config.gateway.records_limit;
         let count = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
 
-        let url = format!("{}/{}/auto_complete?offset={}&count={}",
-            context.config.service_url(Service::Stores),
-            Model::Store.to_url(),
-            offset,
-            count + 1
-            );
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::Store)
+            .path("auto_complete")
+            .query("offset", offset)
+            .query("count", count + 1)
+            .build();
 
         context.request::<Vec<String>>(Method::Post, url, Some(name))
             .map (|full_names| {
@@ -228,12 +307,13 @@ graphql_object!(Search: Context as "Search" |&self| {
                 let start_cursor =  full_name_edges.get(0).map(|e| e.cursor.clone());
                 let end_cursor = full_name_edges.iter().last().map(|e| e.cursor.clone());
 
-                let has_previous_page = true;
+                let has_previous_page = offset > 0;
                 let page_info = PageInfo {
                     has_next_page,
                     has_previous_page,
                     start_cursor,
-                    end_cursor};
+                    end_cursor,
+                    total_count: None};
                 Connection::new(full_name_edges, page_info)
             })
             .wait()
@@ -250,10 +330,7 @@ graphql_object!(ProductsSearchFilters: Context as "ProductsSearchFilters" |&self
 
         let body = serde_json::to_string(&self.search_term)?;
 
-        let url = format!("{}/{}/search/filters/price",
-                        context.config.service_url(Service::Stores),
-                        Model::BaseProduct.to_url(),
-                    );
+        let url = context.config.url(Service::Stores).model(Model::BaseProduct).path("search/filters/price").build();
         context.request::<RangeFilter>(Method::Post, url, Some(body))
             .wait()
             .map(Some)
@@ -264,10 +341,7 @@ graphql_object!(ProductsSearchFilters: Context as "ProductsSearchFilters" |&self
 
         let body = serde_json::to_string(&self.search_term)?;
 
-        let url = format!("{}/{}/search/filters/category",
-                        context.config.service_url(Service::Stores),
-                        Model::BaseProduct.to_url(),
-                    );
+        let url = context.config.url(Service::Stores).model(Model::BaseProduct).path("search/filters/category").build();
         context.request::<SearchCategory>(Method::Post, url, Some(body))
             .wait()
             .map(Some)
@@ -287,14 +361,84 @@ graphql_object!(ProductsSearchFilters: Context as "ProductsSearchFilters" |&self
 
         let body = serde_json::to_string(&search_term_only_category)?;
 
-        let url = format!("{}/{}/search/filters/attributes",
-                        context.config.service_url(Service::Stores),
-                        Model::BaseProduct.to_url(),
-                    );
+        let url = context.config.url(Service::Stores).model(Model::BaseProduct).path("search/filters/attributes").build();
         context.request::<Option<Vec<AttributeFilter>>>(Method::Post, url, Some(body))
             .wait()
     }
 
+    field facets(&executor,
+        price_buckets = None : Option<i32> as "Number of price histogram buckets, 10 by default")
+            -> FieldResult<ProductsFacets> as "Attribute values, price histogram and categories of the found products with counts."{
+        let context = executor.context();
+
+        let price_buckets = cmp::max(1, cmp::min(price_buckets.unwrap_or(DEFAULT_PRICE_BUCKETS), MAX_PRICE_BUCKETS));
+        let body = serde_json::to_string(&self.search_term)?;
+
+        let url = context.config.url(Service::Stores).model(Model::BaseProduct).path("search/facets").query("price_buckets", price_buckets).build();
+        let mut facets = context.request::<ProductsFacets>(Method::Post, url, Some(body))
+            .wait()?;
+        facets.mark_selected(&self.search_term);
+        fill_facets_details(context, &mut facets)?;
+
+        Ok(facets)
+    }
+
+});
+
+graphql_object!(ProductsFacets: Context as "ProductsFacets" |&self| {
+    description: "Facets of the found products."
+
+    field total_count() -> &i32 as "Found products count" {
+        &self.total_count
+    }
+
+    field attributes() -> &[AttributeFacet] as "Attributes of the category" {
+        &self.attributes
+    }
+
+    field price_histogram() -> &[PriceBucket] as "Price histogram" {
+        &self.price_histogram
+    }
+
+    field categories() -> &[CategoryFacet] as "Categories of the found products" {
+        &self.categories
+    }
+});
+
+graphql_object!(AttributeFacet: Context as "AttributeFacet" |&self| {
+    description: "Attribute values of the found products."
+
+    field attribute() -> &Option<Attribute> as "Attribute" {
+        &self.attribute
+    }
+
+    field values() -> &[FacetValue] as "Values with found products count" {
+        &self.values
+    }
+
+    field range() -> &Option<RangeFilter> as "Range of the values" {
+        &self.range
+    }
+});
+
+graphql_object!(CategoryFacet: Context as "CategoryFacet" |&self| {
+    description: "Category of the found products."
+
+    field category() -> &Option<Category> as "Category" {
+        &self.category
+    }
+
+    field raw_id() -> i32 as "Category int id" {
+        self.category_id.0
+    }
+
+    field count() -> &i32 as "Found products count" {
+        &self.count
+    }
+
+    field selected() -> &bool as "Category is in the current filters" {
+        &self.selected
+    }
 });
 
 graphql_object!(StoresSearchFilters: Context as "StoresSearchFilters" |&self| {
@@ -305,10 +449,7 @@ graphql_object!(StoresSearchFilters: Context as "StoresSearchFilters" |&self| {
 
         let body = serde_json::to_string(&self.search_term)?;
 
-        let url = format!("{}/{}/search/filters/count",
-                    context.config.service_url(Service::Stores),
-                    Model::Store.to_url(),
-                    );
+        let url = context.config.url(Service::Stores).model(Model::Store).path("search/filters/count").build();
 
         context.request::<i32>(Method::Post, url, Some(body))
             .wait()
@@ -319,10 +460,7 @@ graphql_object!(StoresSearchFilters: Context as "StoresSearchFilters" |&self| {
 
         let body = serde_json::to_string(&self.search_term)?;
 
-        let url = format!("{}/{}/search/filters/category",
-                    context.config.service_url(Service::Stores),
-                    Model::Store.to_url(),
-                    );
+        let url = context.config.url(Service::Stores).model(Model::Store).path("search/filters/category").build();
 
         context.request::<Category>(Method::Post, url, Some(body))
             .wait()
@@ -337,10 +475,7 @@ graphql_object!(StoresSearchFilters: Context as "StoresSearchFilters" |&self| {
 
         let body = serde_json::to_string(&self.search_term)?;
 
-        let url = format!("{}/{}/search/filters/country",
-                    context.config.service_url(Service::Stores),
-                    Model::Store.to_url(),
-                    );
+        let url = context.config.url(Service::Stores).model(Model::Store).path("search/filters/country").build();
 
         context.request::<Vec<String>>(Method::Post, url, Some(body))
             .wait()
@@ -348,3 +483,24 @@ graphql_object!(StoresSearchFilters: Context as "StoresSearchFilters" |&self| {
     }
 
 });
+
+/// Fetches all attributes and the categories tree once instead of a request per facet
+fn fill_facets_details(context: &Context, facets: &mut ProductsFacets) -> FieldResult<()> {
+    let attributes = if facets.attributes.is_empty() {
+        vec![]
+    } else {
+        let url = context.config.url(Service::Stores).model(Model::Attribute).build();
+        context.request::<Vec<Attribute>>(Method::Get, url, None).wait()?
+    };
+
+    let categories_tree = if facets.categories.is_empty() {
+        None
+    } else {
+        let url = context.config.url(Service::Stores).model(Model::Category).build();
+        context.request::<Option<Category>>(Method::Get, url, None).wait()?
+    };
+
+    facets.fill_details(&attributes, categories_tree.as_ref());
+
+    Ok(())
+}
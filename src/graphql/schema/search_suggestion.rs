@@ -0,0 +1,188 @@
+//! "Did you mean" suggestions for product searches finding nothing
+use std::fs::File;
+use std::io::ErrorKind;
+
+use futures::Future;
+use hyper::Method;
+use juniper::FieldResult;
+
+use stq_routes::model::Model;
+use stq_routes::service::Service;
+use stq_static_resources::ModerationStatus;
+
+use graphql::context::Context;
+use graphql::models::*;
+use text;
+
+const MAX_SUGGESTIONS: usize = 3;
+/// Every corrected word costs a request, so only the first words of long queries are corrected
+const MAX_CORRECTED_WORDS: usize = 2;
+/// Every candidate costs a search request, with the corrected words
+/// a search finding nothing makes at most 6 more requests
+const MAX_CHECKED_CANDIDATES: usize = 4;
+/// Product names fetched to collect the words similar to the misspelled one
+const VOCABULARY_NAMES_COUNT: i32 = 50;
+
+/// Transliteration of the query, its synonym replacements and spelling corrections,
+/// only the queries finding products with the same filters are suggested.
+/// `query` is the name searched before the synonyms expansion.
+/// Errors are logged because suggestions should not break the search
+pub fn did_you_mean(context: &Context, search_term: &SearchProductInput, visibility: &Visibility) -> Vec<String> {
+    match find_suggestions(context, search_term, visibility) {
+        Ok(suggestions) => suggestions,
+        Err(e) => {
+            warn!("Could not find suggestions for search of \"{}\": {:?}", search_term.name, e);
+            vec![]
+        }
+    }
+}
+
+fn find_suggestions(context: &Context, search_term: &SearchProductInput, visibility: &Visibility) -> FieldResult<Vec<String>> {
+    let query = text::words(&search_term.name).join(" ");
+    if query.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut candidates = vec![text::transliterate(&query)];
+    candidates.extend(synonym_queries(context, &query));
+    candidates.extend(corrected_queries(context, &query)?);
+
+    let mut checked: Vec<String> = vec![];
+    let mut suggestions: Vec<String> = vec![];
+    for candidate in candidates {
+        if suggestions.len() >= MAX_SUGGESTIONS || checked.len() >= MAX_CHECKED_CANDIDATES {
+            break;
+        }
+        if candidate == query || checked.contains(&candidate) {
+            continue;
+        }
+        let name = context.synonyms().expand(&candidate);
+        if finds_products(context, search_term, &name, visibility)? {
+            suggestions.push(candidate.clone());
+        }
+        checked.push(candidate);
+    }
+
+    Ok(suggestions)
+}
+
+/// Queries with one of the words replaced by its synonym in the language of the word
+fn synonym_queries(context: &Context, query: &str) -> Vec<String> {
+    let words = text::words(query);
+    let mut queries = vec![];
+    for (position, word) in words.iter().enumerate() {
+        for synonym in context.synonyms().of_word(word) {
+            queries.push(replace_word(&words, position, synonym));
+        }
+    }
+    queries
+}
+
+/// The query with all misspelled words corrected, followed by the queries with one word corrected.
+/// Correct words are those of published product names starting with the same two letters
+fn corrected_queries(context: &Context, query: &str) -> FieldResult<Vec<String>> {
+    let words = text::words(query);
+    let mut corrections = vec![];
+    let correctable_words = words.iter().enumerate().filter(|(_, word)| word.chars().count() >= 3);
+    for (position, word) in correctable_words.take(MAX_CORRECTED_WORDS) {
+        let prefix = word.chars().take(2).collect::<String>();
+        if let Some(correction) = closest_word(word, &vocabulary(context, &prefix)?) {
+            corrections.push((position, correction));
+        }
+    }
+
+    let mut queries = vec![];
+    if corrections.len() > 1 {
+        let mut corrected = words.clone();
+        for (position, correction) in &corrections {
+            corrected[*position] = correction.clone();
+        }
+        queries.push(corrected.join(" "));
+    }
+    for (position, correction) in &corrections {
+        queries.push(replace_word(&words, *position, correction));
+    }
+
+    Ok(queries)
+}
+
+/// The most similar other word, one typo is allowed in short words and two in longer ones
+fn closest_word(word: &str, vocabulary: &[String]) -> Option<String> {
+    let allowed_distance = if word.chars().count() <= 4 { 1 } else { 2 };
+    vocabulary
+        .iter()
+        .filter(|candidate| *candidate != word)
+        .map(|candidate| (text::edit_distance(word, candidate), candidate))
+        .filter(|(distance, _)| *distance <= allowed_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.clone())
+}
+
+/// Words of published product names starting with `prefix`
+fn vocabulary(context: &Context, prefix: &str) -> FieldResult<Vec<String>> {
+    let url = context
+        .config
+        .url(Service::Stores)
+        .model(Model::BaseProduct)
+        .path("auto_complete")
+        .query("offset", 0)
+        .query("count", VOCABULARY_NAMES_COUNT)
+        .build();
+
+    let search_term = AutoCompleteProductNameInput {
+        name: prefix.to_string(),
+        store_id: None,
+        status: Some(ModerationStatus::Published),
+    };
+
+    let body = serde_json::to_string(&search_term)?;
+
+    let full_names = context.request::<Vec<String>>(Method::Post, url, Some(body)).wait()?;
+
+    let mut words: Vec<String> = vec![];
+    for word in full_names.iter().flat_map(|full_name| text::words(full_name)) {
+        if word.starts_with(prefix) && !words.contains(&word) {
+            words.push(word);
+        }
+    }
+    Ok(words)
+}
+
+fn finds_products(context: &Context, search_term: &SearchProductInput, name: &str, visibility: &Visibility) -> FieldResult<bool> {
+    let url = context
+        .config
+        .url(Service::Stores)
+        .model(Model::BaseProduct)
+        .path("search")
+        .query("offset", 0)
+        .query("count", 1)
+        .query("visibility", visibility)
+        .build();
+
+    let mut search_term = search_term.clone();
+    search_term.name = name.to_string();
+
+    let body = serde_json::to_string(&search_term)?;
+
+    context
+        .request::<Vec<BaseProduct>>(Method::Post, url, Some(body))
+        .wait()
+        .map(|products| !products.is_empty())
+}
+
+/// Synonym groups of all languages, there are no synonyms when the file does not exist
+pub fn load_synonyms(path: &str) -> FieldResult<Synonyms> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(Synonyms::default()),
+        Err(e) => return Err(e.into()),
+    };
+    let groups: Vec<SynonymGroup> = serde_json::from_reader(file)?;
+    Ok(Synonyms::new(groups))
+}
+
+fn replace_word(words: &[String], position: usize, replacement: &str) -> String {
+    let mut words = words.to_vec();
+    words[position] = replacement.to_string();
+    words.join(" ")
+}
@@ -0,0 +1,141 @@
+//! File containing shipment object of graphql schema
+use futures::Future;
+use hyper::Method;
+use juniper::{FieldError, FieldResult};
+use serde_json;
+
+use stq_api::orders::Order;
+use stq_routes::model::Model;
+use stq_types::{CompanyId, OrderId, OrderSlug};
+
+use graphql::context::Context;
+use graphql::models::*;
+use graphql::schema::order;
+use graphql::schema::order_transition::TransitionChecker;
+
+graphql_object!(Shipment: Context as "Shipment" |&self| {
+    description: "Shipment of the order by the delivery company."
+
+    field raw_id() -> &i32 as "Unique int id" {
+        &self.id.0
+    }
+
+    field tracking_number() -> &str as "Tracking number" {
+        &self.tracking_number
+    }
+
+    field company(&executor) -> FieldResult<Option<Company>> as "Delivery company" {
+        match self.company_id {
+            Some(company_id) => executor.context().company(company_id),
+            None => Ok(None),
+        }
+    }
+
+    field tracking_url(&executor) -> FieldResult<Option<String>> as "Tracking page of the delivery company" {
+        let company = match self.company_id {
+            Some(company_id) => executor.context().company(company_id)?,
+            None => None,
+        };
+
+        Ok(company.and_then(|company| company.tracking_url(&self.tracking_number)))
+    }
+
+    field events(&executor) -> FieldResult<Vec<TrackingEvent>> as "Tracking events reported by the carrier, empty if the company is unknown" {
+        let context = executor.context();
+        let company = match self.company_id {
+            Some(company_id) => context.company(company_id)?,
+            None => None,
+        };
+
+        match company {
+            Some(company) => context.get_carrier_service().tracking_events(&company, &self.tracking_number),
+            None => Ok(vec![]),
+        }
+    }
+
+    field created_at() -> String as "Created at" {
+        self.created_at.to_rfc3339()
+    }
+});
+
+graphql_object!(TrackingEvent: Context as "TrackingEvent" |&self| {
+    description: "Shipment tracking event."
+
+    field status() -> &TrackingStatus as "Status" {
+        &self.status
+    }
+
+    field description() -> &str as "Description" {
+        &self.description
+    }
+
+    field location() -> &Option<String> as "Location" {
+        &self.location
+    }
+
+    field occurred_at() -> String as "Occurred at" {
+        self.occurred_at.to_rfc3339()
+    }
+});
+
+/// Saves shipment of the sent order, nothing is saved without tracking number
+pub fn create_shipment(
+    context: &Context,
+    order_id: OrderId,
+    company_id: Option<i32>,
+    tracking_number: Option<String>,
+) -> FieldResult<Option<Shipment>> {
+    let tracking_number = match tracking_number {
+        Some(tracking_number) => tracking_number,
+        None => return Ok(None),
+    };
+
+    context
+        .get_delivery_microservice()
+        .create_shipment(NewShipment {
+            order_id,
+            company_id: company_id.map(CompanyId),
+            tracking_number,
+        })
+        .map(Some)
+}
+
+/// Moves the order to sent state, its shipment is saved first and deleted if the order is not moved,
+/// so a sent order never lacks the shipment
+pub fn run_set_order_status_delivery_mutation(context: &Context, input: OrderStatusDeliveryInput) -> FieldResult<Option<GraphQLOrder>> {
+    let slug = input.order_slug;
+    let company_id = input.company_id;
+    let mut transition: OrderStatusDelivery = input.into();
+    transition.comment = order::delivery_comment(transition.comment, transition.track_id.as_ref());
+    transition.committer_role = TransitionChecker::new(context).check(OrderSlug(slug), &transition.state, Some(transition.committer_role))?;
+
+    let order_id = context
+        .get_orders_microservice()
+        .get_order_by_slug(OrderSlug(slug))?
+        .map(|order| order.0.id)
+        .ok_or_else(|| {
+            FieldError::new(
+                "Could not find order.",
+                graphql_value!({ "code": 400, "details": { "Order with such slug does not exist." }}),
+            )
+        })?;
+    let shipment = create_shipment(context, order_id, company_id, transition.track_id.clone())?;
+
+    let url = context.config.saga_url().model(Model::Order).segment(slug).path("set_state").build();
+    let body = serde_json::to_string(&transition)?;
+    let moved = context.request::<Option<Order>>(Method::Post, url, Some(body)).wait();
+
+    match (shipment, &moved) {
+        (Some(ref shipment), &Err(_)) | (Some(ref shipment), &Ok(None)) => {
+            if let Err(delete_error) = context.get_delivery_microservice().delete_shipment(shipment.id) {
+                error!(
+                    "Could not delete shipment {} of order {} which was not moved to sent state: {:?}",
+                    shipment.id, slug, delete_error
+                );
+            }
+        }
+        _ => {}
+    }
+
+    moved.map(|order| order.map(GraphQLOrder))
+}
@@ -11,6 +11,7 @@ use stq_types::{ProductId, WarehouseId, WarehouseIdentifier};
 
 use super::*;
 use graphql::context::Context;
+use graphql::microservice::requests::GetStocksRequest;
 use graphql::models::*;
 use graphql::schema::warehouse as warehouse_module;
 
@@ -34,12 +35,7 @@ graphql_object!(GraphQLStock: Context as "Stock" |&self| {
     field product(&executor) -> FieldResult<Option<Product>> as "Fetches product." {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}/{}",
-            &context.config.service_url(Service::Stores),
-            Model::Product.to_url(),
-            self.0.product_id.to_string()
-        );
+        let url = context.config.url(Service::Stores).model(Model::Product).segment(self.0.product_id).build();
 
         context.request::<Option<Product>>(Method::Get, url, None)
             .wait()
@@ -62,24 +58,33 @@ graphql_object!(GraphQLStock: Context as "Stock" |&self| {
 });
 
 pub fn try_get_stock_for_warehouse(context: &Context, warehouse_id: WarehouseId, product_id: ProductId) -> FieldResult<Option<Stock>> {
-    let url = format!(
-        "{}/{}/by-id/{}/products/{}",
-        context.config.service_url(Service::Warehouses),
-        Model::Warehouse.to_url(),
-        warehouse_id,
-        product_id,
-    );
+    let url = context
+        .config
+        .url(Service::Warehouses)
+        .model(Model::Warehouse)
+        .path("by-id")
+        .segment(warehouse_id)
+        .path("products")
+        .segment(product_id)
+        .build();
 
     context.request::<Option<Stock>>(Method::Get, url, None).wait()
 }
 
 pub fn get_stocks_for_product(context: &Context, product_id: ProductId) -> FieldResult<Vec<Stock>> {
-    let url = format!(
-        "{}/{}/by-product-id/{}",
-        context.config.service_url(Service::Warehouses),
-        Model::Stock.to_url(),
-        product_id,
-    );
+    let url = context.config.url(Service::Warehouses).model(Model::Stock).path("by-product-id").segment(product_id).build();
 
     context.request::<Vec<Stock>>(Method::Get, url, None).wait()
 }
+
+/// Stocks of several products in one request, e.g. of the products on a wishlist page
+pub fn get_stocks_for_products(context: &Context, product_ids: Vec<ProductId>) -> FieldResult<Vec<Stock>> {
+    if product_ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let url = context.config.url(Service::Warehouses).model(Model::Stock).path("by-product-ids").build();
+    let body = serde_json::to_string(&GetStocksRequest { product_ids })?;
+
+    context.request::<Vec<Stock>>(Method::Post, url, Some(body)).wait()
+}
@@ -22,6 +22,9 @@ use super::*;
 use errors::into_graphql;
 use graphql::context::Context;
 use graphql::models::*;
+use graphql::schema::dispute as dispute_module;
+use graphql::schema::order_return as order_return_module;
+use graphql::schema::review as review_module;
 use graphql::schema::warehouse as warehouse_module;
 use schema::admin::{base_products_search, base_products_search_pages};
 use schema::order as order_module;
@@ -46,12 +49,7 @@ graphql_object!(Store: Context as "Store" |&self| {
     field store_manager(&executor) -> FieldResult<Option<User>> as "Fetches store manager by user_id." {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}/{}",
-            &context.config.service_url(Service::Users),
-            Model::User.to_url(),
-            self.user_id.to_string()
-        );
+        let url = context.config.url(Service::Users).model(Model::User).segment(self.user_id).build();
 
         context.request::<Option<User>>(Method::Get, url, None)
             .wait()
@@ -127,6 +125,19 @@ graphql_object!(Store: Context as "Store" |&self| {
         &self.rating
     }
 
+    field reviews(&executor,
+        current_page : i32 as "Current page",
+        items_count : i32 as "Items count")
+            -> FieldResult<Connection<Review, PageInfoReviews>> as "Reviews of the store products." {
+        let context = executor.context();
+        let search = ReviewSearch {
+            store_id: Some(self.id),
+            ..Default::default()
+        };
+
+        review_module::find_reviews(context, current_page, items_count, search)
+    }
+
     field status() -> &ModerationStatus as "Moderation Status" {
         &self.status
     }
@@ -145,79 +156,70 @@ graphql_object!(Store: Context as "Store" |&self| {
 
     field base_products(&executor,
         first = None : Option<i32> as "First edges",
-        after = None : Option<GraphqlID> as "After base_product GraphQL id",
+        after = None : Option<GraphqlID> as "Cursor of the edge to start after",
+        last = None : Option<i32> as "Last edges",
+        before = None : Option<GraphqlID> as "Cursor of the edge to end before",
         skip_base_prod_id = None : Option<i32> as "Skip base prod id",
         visibility: Option<Visibility> as "Specifies visibility of the base products")
             -> FieldResult<Option<Connection<BaseProduct, PageInfo>>> as "Fetches base products of the store." {
         let context = executor.context();
 
-        let offset = after
-            .and_then(|val| ID::from_str(&*val).map(|id| id.raw_id + 1).ok())
-            .unwrap_or_default();
         let visibility = visibility.unwrap_or(Visibility::Active);
 
-        let records_limit = context.config.gateway.records_limit;
-        let count = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
-
-            let url = match skip_base_prod_id {
-                None => format!(
-                        "{}/{}/{}/products?offset={}&count={}&visibility={}",
-                        &context.config.service_url(Service::Stores),
-                        Model::Store.to_url(),
-                        self.id,
-                        offset,
-                        count + 1,
-                        visibility,
-                    ),
-                Some(id) => format!(
-                        "{}/{}/{}/products?skip_base_product_id={}&offset={}&count={}&visibility={}",
-                        &context.config.service_url(Service::Stores),
-                        Model::Store.to_url(),
-                        self.id,
-                        id,
-                        offset,
-                        count + 1,
-                        visibility,
-                    )
-            };
-
-            context.request::<Vec<BaseProduct>>(Method::Get, url, None)
-                .map (|base_products| {
-                    let mut base_product_edges: Vec<Edge<BaseProduct>> =  vec![];
-                    for base_product in base_products {
-                        let edge = Edge::new(
-                                juniper::ID::from(ID::new(Service::Stores, Model::BaseProduct, base_product.id.0).to_string()),
-                                base_product.clone()
-                            );
-                        base_product_edges.push(edge);
-                    }
-                    let has_next_page = base_product_edges.len() as i32 > count;
-                    if has_next_page {
-                        base_product_edges.pop();
-                    };
-                    let has_previous_page = true;
-                    let start_cursor =  base_product_edges.get(0).map(|e| e.cursor.clone());
-                    let end_cursor = base_product_edges.iter().last().map(|e| e.cursor.clone());
-                    let page_info = PageInfo {
-                        has_next_page,
-                        has_previous_page,
-                        start_cursor,
-                        end_cursor};
-                    Connection::new(base_product_edges, page_info)
+        let page = PageRequest::new(first, after, last, before, None, context.config.gateway.records_limit)?
+            .resolve_from_end(|| {
+                let url = context
+                    .config
+                    .url(Service::Stores)
+                    .model(Model::Store)
+                    .segment(self.id)
+                    .path("products/count")
+                    .query("visibility", &visibility)
+                    .build();
+                // the skipped base product is one of the store's, it is not counted
+                context.request::<i32>(Method::Get, url, None).wait().map(|count| match skip_base_prod_id {
+                    Some(_) => cmp::max(count - 1, 0),
+                    None => count,
                 })
-                .wait()
-                .map(Some)
-        }
+            })?;
+
+        let url = match skip_base_prod_id {
+            None => context
+                .config
+                .url(Service::Stores)
+                .model(Model::Store)
+                .segment(self.id)
+                .path("products")
+                .query("offset", page.offset)
+                .query("count", page.fetch_count())
+                .query("visibility", &visibility)
+                .build(),
+            Some(id) => context
+                .config
+                .url(Service::Stores)
+                .model(Model::Store)
+                .segment(self.id)
+                .path("products")
+                .query("skip_base_product_id", id)
+                .query("offset", page.offset)
+                .query("count", page.fetch_count())
+                .query("visibility", &visibility)
+                .build()
+        };
+
+        context.request::<Vec<BaseProduct>>(Method::Get, url, None)
+            .map (|base_products| {
+                let (base_product_edges, page_info) = page.edges(base_products, |base_product| Some(base_product.id.0));
+                Connection::new(base_product_edges, page_info)
+            })
+            .wait()
+            .map(Some)
+    }
 
     field products_count(&executor) -> FieldResult<i32> as "Fetches products count of the store." {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}/{}/products/count",
-            &context.config.service_url(Service::Stores),
-            Model::Store.to_url(),
-            self.id,
-        );
+        let url = context.config.url(Service::Stores).model(Model::Store).segment(self.id).path("products/count").build();
 
         context.request::<i32>(Method::Get, url, None)
             .wait()
@@ -226,12 +228,7 @@ graphql_object!(Store: Context as "Store" |&self| {
     field moderator_comment(&executor) -> FieldResult<Option<ModeratorStoreComments>> as "Fetches moderator comment by id." {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}/{}",
-            &context.config.service_url(Service::Stores),
-            Model::ModeratorStoreComment.to_url(),
-            self.id.to_string()
-        );
+        let url = context.config.url(Service::Stores).model(Model::ModeratorStoreComment).segment(self.id).build();
 
         context.request::<Option<ModeratorStoreComments>>(Method::Get, url, None)
             .wait()
@@ -244,92 +241,30 @@ graphql_object!(Store: Context as "Store" |&self| {
     }
 
     field orders(&executor,
-        current_page : i32 as "Current page",
-        items_count : i32 as "Items count",
+        current_page = None : Option<i32> as "Current page, orders are paged by first/after/last/before without it",
+        items_count = None : Option<i32> as "Items count",
+        first = None : Option<i32> as "First edges",
+        after = None : Option<GraphqlID>  as "Cursor of the edge to start after",
+        last = None : Option<i32> as "Last edges",
+        before = None : Option<GraphqlID>  as "Cursor of the edge to end before",
         search_term_options : SearchOrderOptionInput as "Search options pattern")
             -> FieldResult<Option<Connection<GraphQLOrder, PageInfoOrdersSearch>>> as "Fetches orders using relay connection." {
         let context = executor.context();
 
-        let offset = items_count * (current_page - 1);
-
         let records_limit = context.config.gateway.records_limit;
-        let count = cmp::min(items_count, records_limit as i32);
-
-        let created_from = match search_term_options.created_from.clone() {
-            Some(value) => {
-                match value.parse() {
-                    Ok(v) => Some(v),
-                    Err(_) => return Err(FieldError::new(
-                        "Parsing created_from error",
-                        graphql_value!({ "code": 300, "details": { "created_from has wrong format." }}),
-                    )),
-                }
-            },
-            None => None
+        let page = match current_page {
+            Some(current_page) => PageRequest::from_page_number(current_page, items_count.unwrap_or(records_limit as i32), records_limit),
+            None => PageRequest::new(first, after, last, before, None, records_limit)?,
         };
 
-        let created_to = match search_term_options.created_to.clone() {
-            Some(value) => {
-                match value.parse() {
-                    Ok(v) => Some(v),
-                    Err(_) => return Err(FieldError::new(
-                        "Parsing created_to error",
-                        graphql_value!({ "code": 300, "details": { "created_to has wrong format." }}),
-                    )),
-                }
-            },
-            None => None
-        };
-
-        let customer = search_term_options.email.clone().and_then(|email| {
-            let url = format!("{}/{}/by_email?email={}",
-                context.config.service_url(Service::Users),
-                Model::User.to_url(),
-                email);
-
-            context.request::<Option<User>>(Method::Get, url, None)
-                .wait()
-                .ok()
-                .and_then (|user| user.map(|u|u.id))
-        });
-
-        let search_term = OrderSearchTerms {
-                slug: search_term_options.slug.map(OrderSlug),
-                customer,
-                store: Some(self.id),
-                created_from,
-                created_to,
-                payment_status: search_term_options.payment_status,
-                state: search_term_options.order_status,
-                ..OrderSearchTerms::default()
-            };
+        let search_term = store_orders_search_terms(context, self.id, &search_term_options)?;
 
         let rpc_client = context.get_rest_api_client(Service::Orders);
         rpc_client.search(search_term)
             .sync()
             .map_err(into_graphql)
             .map(|res| res.into_iter().map(GraphQLOrder).collect())
-            .map (move |orders: Vec<GraphQLOrder>| {
-                let total_pages = (orders.iter().count() as f32 / items_count as f32).ceil() as i32;
-
-                let mut orders_edges: Vec<Edge<GraphQLOrder>> = orders
-                    .into_iter()
-                    .skip(offset as usize)
-                    .take(count as usize)
-                    .map(|order| Edge::new(
-                                juniper::ID::from(order.0.id.to_string()),
-                                order.clone()
-                            ))
-                    .collect();
-
-                let page_info = PageInfoOrdersSearch {
-                    total_pages,
-                    current_page,
-                    page_items_count: items_count,
-                    search_term_options: search_term_options.into()
-                };
-                Connection::new(orders_edges, page_info)
-            })
+            .map(|orders| order_module::orders_connection(orders, page, search_term_options.into()))
             .map(Some)
     }
 
@@ -339,6 +274,36 @@ graphql_object!(Store: Context as "Store" |&self| {
         order_module::try_get_order(context, OrderIdentifier::Slug(OrderSlug(slug)))
     }
 
+    field disputes(&executor,
+        current_page : i32 as "Current page",
+        items_count : i32 as "Items count",
+        status : Option<DisputeStatus> as "Dispute status")
+            -> FieldResult<Connection<Dispute, PageInfoSegments>> as "Disputes by orders of the store." {
+        let context = executor.context();
+        let search = DisputeSearch {
+            store_id: Some(self.id),
+            status,
+            ..Default::default()
+        };
+
+        dispute_module::find_disputes(context, current_page, items_count, search)
+    }
+
+    field returns(&executor,
+        current_page : i32 as "Current page",
+        items_count : i32 as "Items count",
+        status : Option<ReturnStatus> as "Return status")
+            -> FieldResult<Connection<OrderReturn, PageInfoSegments>> as "Returns by orders of the store." {
+        let context = executor.context();
+        let search = OrderReturnSearch {
+            store_id: Some(self.id),
+            status,
+            ..Default::default()
+        };
+
+        order_return_module::find_returns(context, current_page, items_count, search)
+    }
+
     field find_most_viewed_products(&executor,
         first = None : Option<i32> as "First edges",
         after = None : Option<GraphqlID>  as "Offset from beginning",
@@ -346,21 +311,19 @@ graphql_object!(Store: Context as "Store" |&self| {
             -> FieldResult<Option<Connection<BaseProduct, PageInfo>>> as "Find most viewed base products each one contains one variant." {
         let context = executor.context();
 
-        let offset = after
-            .and_then(|id|{
-                i32::from_str(&id).map(|i| i + 1).ok()
-            })
-            .unwrap_or_default();
+        let offset = Cursor::offset_after(after)?;
 
         let records_limit = context.config.gateway.records_limit;
         let count = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
 
-        let url = format!("{}/{}/most_viewed?offset={}&count={}",
-            context.config.service_url(Service::Stores),
-            Model::BaseProduct.to_url(),
-            offset,
-            count + 1
-            );
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::BaseProduct)
+            .path("most_viewed")
+            .query("offset", offset)
+            .query("count", count + 1)
+            .build();
 
         let mut options = if let Some(mut options) = search_term.options.clone() {
             options.store_id = Some(self.id.0);
@@ -386,14 +349,15 @@ graphql_object!(Store: Context as "Store" |&self| {
                 if has_next_page {
                     base_product_edges.pop();
                 };
-                let has_previous_page = true;
+                let has_previous_page = offset > 0;
                 let start_cursor =  base_product_edges.get(0).map(|e| e.cursor.clone());
                 let end_cursor = base_product_edges.iter().last().map(|e| e.cursor.clone());
                 let page_info = PageInfo {
                     has_next_page,
                     has_previous_page,
                     start_cursor,
-                    end_cursor};
+                    end_cursor,
+                    total_count: None};
                 Connection::new(base_product_edges, page_info)
             })
             .wait()
@@ -408,21 +372,19 @@ graphql_object!(Store: Context as "Store" |&self| {
             -> FieldResult<Option<Connection<BaseProduct, PageInfo>>> as "Find base products each one with most discount variant." {
         let context = executor.context();
 
-        let offset = after
-            .and_then(|id|{
-                i32::from_str(&id).map(|i| i + 1).ok()
-            })
-            .unwrap_or_default();
+        let offset = Cursor::offset_after(after)?;
 
         let records_limit = context.config.gateway.records_limit;
         let count = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
 
-        let url = format!("{}/{}/most_discount?offset={}&count={}",
-            context.config.service_url(Service::Stores),
-            Model::BaseProduct.to_url(),
-            offset,
-            count + 1
-            );
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::BaseProduct)
+            .path("most_discount")
+            .query("offset", offset)
+            .query("count", count + 1)
+            .build();
 
         let mut options = if let Some(mut options) = search_term.options.clone() {
             options.store_id = Some(self.id.0);
@@ -448,14 +410,15 @@ graphql_object!(Store: Context as "Store" |&self| {
                 if has_next_page {
                     base_product_edges.pop();
                 };
-                let has_previous_page = true;
+                let has_previous_page = offset > 0;
                 let start_cursor =  base_product_edges.get(0).map(|e| e.cursor.clone());
                 let end_cursor = base_product_edges.iter().last().map(|e| e.cursor.clone());
                 let page_info = PageInfo {
                     has_next_page,
                     has_previous_page,
                     start_cursor,
-                    end_cursor};
+                    end_cursor,
+                    total_count: None};
                 Connection::new(base_product_edges, page_info)
             })
             .wait()
@@ -464,7 +427,9 @@ graphql_object!(Store: Context as "Store" |&self| {
 
     field find_product(&executor,
         first = None : Option<i32> as "First edges",
-        after = None : Option<GraphqlID>  as "Offset form beginning",
+        after = None : Option<GraphqlID>  as "Cursor of the edge to start after",
+        last = None : Option<i32> as "Last edges",
+        before = None : Option<GraphqlID>  as "Cursor of the edge to end before",
         search_term : SearchProductInput as "Search pattern",
         visibility: Option<Visibility> as "Specifies allowed visibility of the base product"
         )
@@ -474,23 +439,8 @@ graphql_object!(Store: Context as "Store" |&self| {
 
         let visibility = visibility.unwrap_or_default();
 
-        let offset = after
-            .and_then(|id|{
-                i32::from_str(&id).map(|i| i + 1).ok()
-            })
-            .unwrap_or_default();
-
-
-        let records_limit = context.config.gateway.records_limit;
-        let count = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
-
-        let url = format!("{}/{}/search?offset={}&count={}&visibility={}",
-            context.config.service_url(Service::Stores),
-            Model::BaseProduct.to_url(),
-            offset,
-            count + 1,
-            visibility
-            );
+        let sort_key = search_term.options.as_ref().and_then(|options| options.sort_by.as_ref()).map(|sort_by| format!("{:?}", sort_by));
+        let page = PageRequest::new(first, after, last, before, sort_key, context.config.gateway.records_limit)?;
 
         let mut options = search_term.options.clone().unwrap_or_default();
 
@@ -505,23 +455,32 @@ graphql_object!(Store: Context as "Store" |&self| {
 
         let body = serde_json::to_string(&search_term)?;
 
+        let page = page.resolve_from_end(|| {
+            let url = context.config.url(Service::Stores).model(Model::BaseProduct).path("search/filters/count").build();
+            context.request::<i32>(Method::Post, url, Some(body.clone())).wait()
+        })?;
+
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::BaseProduct)
+            .path("search")
+            .query("offset", page.offset)
+            .query("count", page.fetch_count())
+            .query("visibility", &visibility)
+            .build();
+
         context.request::<Vec<BaseProduct>>(Method::Post, url, Some(body))
             .map (|products| {
-                let mut product_edges = Edge::create_vec(products, offset);
+                let (product_edges, page_info) = page.edges(products, |base_product| Some(base_product.id.0));
                 let search_filters = ProductsSearchFilters::new(search_term);
-                let has_next_page = product_edges.len() as i32 == count + 1;
-                if has_next_page {
-                    product_edges.pop();
-                };
-                let has_previous_page = true;
-                let start_cursor =  product_edges.get(0).map(|e| e.cursor.clone());
-                let end_cursor = product_edges.iter().last().map(|e| e.cursor.clone());
                 let page_info = PageInfoProductsSearch {
-                    has_next_page,
-                    has_previous_page,
+                    has_next_page: page_info.has_next_page,
+                    has_previous_page: page_info.has_previous_page,
                     search_filters: Some(search_filters),
-                    start_cursor,
-                    end_cursor};
+                    start_cursor: page_info.start_cursor,
+                    end_cursor: page_info.end_cursor,
+                    did_you_mean: vec![]};
                 Connection::new(product_edges, page_info)
             })
             .wait()
@@ -536,21 +495,19 @@ graphql_object!(Store: Context as "Store" |&self| {
 
         let context = executor.context();
 
-        let offset = after
-            .and_then(|id|{
-                i32::from_str(&id).map(|i| i + 1).ok()
-            })
-            .unwrap_or_default();
+        let offset = Cursor::offset_after(after)?;
 
         let records_limit = context.config.gateway.records_limit;
         let count = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
 
-        let url = format!("{}/{}/auto_complete?offset={}&count={}",
-            context.config.service_url(Service::Stores),
-            Model::BaseProduct.to_url(),
-            offset,
-            count + 1,
-            );
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::BaseProduct)
+            .path("auto_complete")
+            .query("offset", offset)
+            .query("count", count + 1)
+            .build();
 
         let search_term = AutoCompleteProductNameInput {
             name,
@@ -567,14 +524,15 @@ graphql_object!(Store: Context as "Store" |&self| {
                 if has_next_page {
                     full_name_edges.pop();
                 };
-                let has_previous_page = true;
+                let has_previous_page = offset > 0;
                 let start_cursor =  full_name_edges.get(0).map(|e| e.cursor.clone());
                 let end_cursor = full_name_edges.iter().last().map(|e| e.cursor.clone());
                 let page_info = PageInfo {
                     has_next_page,
                     has_previous_page,
                     start_cursor,
-                    end_cursor};
+                    end_cursor,
+                    total_count: None};
                 Connection::new(full_name_edges, page_info)
             })
             .wait()
@@ -583,7 +541,9 @@ graphql_object!(Store: Context as "Store" |&self| {
 
     field deprecated "use findProductsAdminPages" find_products_admin(&executor,
         first = None : Option<i32> as "First edges",
-        after = None : Option<GraphqlID>  as "Base64 Id of a base_product",
+        after = None : Option<GraphqlID>  as "Cursor of the edge to start after",
+        last = None : Option<i32> as "Last edges",
+        before = None : Option<GraphqlID>  as "Cursor of the edge to end before",
         search_term : SearchModeratorBaseProductInput as "Search pattern"
     ) -> FieldResult<Option<Connection<BaseProduct, PageInfo>>> as "Searching base_products by moderator using relay connection." {
 
@@ -592,7 +552,8 @@ graphql_object!(Store: Context as "Store" |&self| {
             search_term.store_id = Some(self.id.0);
         }
 
-        base_products_search(executor.context(), first, after, search_term)
+        let page = PageRequest::new(first, after, last, before, None, executor.context().config.gateway.records_limit)?;
+        base_products_search(executor.context(), page, search_term)
     }
 
     field find_products_admin_pages(&executor,
@@ -611,10 +572,7 @@ graphql_object!(Store: Context as "Store" |&self| {
 
     field coupons(&executor) -> FieldResult<Vec<Coupon>> {
         let context = executor.context();
-        let url = format!("{}/{}/stores/{}",
-            context.config.service_url(Service::Stores),
-            Model::Coupon.to_url(),
-            self.id);
+        let url = context.config.url(Service::Stores).model(Model::Coupon).path("stores").segment(self.id).build();
         context.request::<Vec<Coupon>>(Method::Get, url, None).wait()
     }
 
@@ -807,17 +765,61 @@ fn orders_billing(
         current_page,
         page_items_count: items_count,
         total_pages,
+        total_count: orders.total_count as i32,
     };
     Ok(Connection::new(orders_edges, page_info))
 }
 
+/// Converts orders search options of the store to orders microservice search terms
+pub fn store_orders_search_terms(
+    context: &Context,
+    store_id: StoreId,
+    options: &SearchOrderOptionInput,
+) -> FieldResult<OrderSearchTerms> {
+    let created_from = match options.created_from {
+        Some(ref value) => Some(value.parse().map_err(|_| {
+            FieldError::new(
+                "Parsing created_from error",
+                graphql_value!({ "code": 300, "details": { "created_from has wrong format." }}),
+            )
+        })?),
+        None => None,
+    };
+
+    let created_to = match options.created_to {
+        Some(ref value) => Some(value.parse().map_err(|_| {
+            FieldError::new(
+                "Parsing created_to error",
+                graphql_value!({ "code": 300, "details": { "created_to has wrong format." }}),
+            )
+        })?),
+        None => None,
+    };
+
+    let customer = options.email.as_ref().and_then(|email| {
+        let url = context.config.url(Service::Users).model(Model::User).path("by_email").query("email", email).build();
+
+        context
+            .request::<Option<User>>(Method::Get, url, None)
+            .wait()
+            .ok()
+            .and_then(|user| user.map(|u| u.id))
+    });
+
+    Ok(OrderSearchTerms {
+        slug: options.slug.map(OrderSlug),
+        customer,
+        store: Some(store_id),
+        created_from,
+        created_to,
+        payment_status: options.payment_status,
+        state: options.order_status.clone(),
+        ..OrderSearchTerms::default()
+    })
+}
+
 pub fn get_store_id_by_product(context: &Context, product_id: ProductId) -> FieldResult<StoreId> {
-    let url_store_id = format!(
-        "{}/{}/store_id?product_id={}",
-        context.config.service_url(Service::Stores),
-        Model::Product.to_url(),
-        product_id
-    );
+    let url_store_id = context.config.url(Service::Stores).model(Model::Product).path("store_id").query("product_id", product_id).build();
 
     context
         .request::<Option<StoreId>>(Method::Get, url_store_id, None)
@@ -835,13 +837,7 @@ pub fn get_store_id_by_product(context: &Context, product_id: ProductId) -> Fiel
 }
 
 pub fn try_get_store(context: &Context, store_id: StoreId, visibility: Visibility) -> FieldResult<Option<Store>> {
-    let url_store = format!(
-        "{}/{}/{}?visibility={}",
-        context.config.service_url(Service::Stores),
-        Model::Store.to_url(),
-        store_id,
-        visibility
-    );
+    let url_store = context.config.url(Service::Stores).model(Model::Store).segment(store_id).query("visibility", &visibility).build();
 
     context.request::<Option<Store>>(Method::Get, url_store, None).wait()
 }
@@ -876,12 +872,7 @@ pub fn run_send_to_moderation_store(context: &Context, store_id: StoreId) -> Fie
 }
 
 fn send_to_moderation(context: &Context, store_id: StoreId) -> FieldResult<Store> {
-    let url = format!(
-        "{}/{}/{}/moderation",
-        context.config.saga_microservice.url.clone(),
-        Model::Store.to_url(),
-        store_id
-    );
+    let url = context.config.saga_url().model(Model::Store).segment(store_id).path("moderation").build();
 
     context.request::<Store>(Method::Post, url, None).wait()
 }
@@ -906,11 +897,7 @@ pub fn run_moderation_status_store(context: &Context, input: StoreModerateInput)
 }
 
 fn validate_change_moderation_status(context: &Context, payload: &StoreModerate) -> FieldResult<bool> {
-    let url = format!(
-        "{}/{}/validate_change_moderation_status",
-        context.config.service_url(Service::Stores),
-        Model::Store.to_url()
-    );
+    let url = context.config.url(Service::Stores).model(Model::Store).path("validate_change_moderation_status").build();
 
     let body: String = serde_json::to_string(&payload)?.to_string();
 
@@ -918,11 +905,7 @@ fn validate_change_moderation_status(context: &Context, payload: &StoreModerate)
 }
 
 fn send_to_moderate(context: &Context, payload: StoreModerate) -> FieldResult<Store> {
-    let url = format!(
-        "{}/{}/moderate",
-        context.config.saga_microservice.url.clone(),
-        Model::Store.to_url()
-    );
+    let url = context.config.saga_url().model(Model::Store).path("moderate").build();
 
     let body: String = serde_json::to_string(&payload)?.to_string();
 
@@ -930,12 +913,7 @@ fn send_to_moderate(context: &Context, payload: StoreModerate) -> FieldResult<St
 }
 
 pub fn run_send_to_draft_store_mutation(context: &Context, store_id: StoreId) -> FieldResult<Store> {
-    let url = format!(
-        "{}/{}/{}/draft",
-        context.config.service_url(Service::Stores),
-        Model::Store.to_url(),
-        store_id
-    );
+    let url = context.config.url(Service::Stores).model(Model::Store).segment(store_id).path("draft").build();
 
     context.request::<Store>(Method::Post, url, None).wait()
 }
@@ -967,12 +945,7 @@ pub fn run_update_store_mutation(context: &Context, input: UpdateStoreInput) ->
 }
 
 pub fn validate_update_store(context: &Context, store_id: StoreId) -> FieldResult<bool> {
-    let url = format!(
-        "{}/{}/{}/validate_update",
-        context.config.service_url(Service::Stores),
-        Model::Store.to_url(),
-        store_id
-    );
+    let url = context.config.url(Service::Stores).model(Model::Store).segment(store_id).path("validate_update").build();
 
     context.request::<bool>(Method::Get, url, None).wait()
 }
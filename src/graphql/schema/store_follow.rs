@@ -0,0 +1,136 @@
+//! File containing store following and store news feed of graphql schema
+use std::cmp;
+
+use chrono::prelude::*;
+use chrono::Duration;
+use juniper;
+use juniper::ID as GraphqlID;
+use juniper::{FieldError, FieldResult};
+
+use stq_types::{StoreId, UserId};
+
+use graphql::context::Context;
+use graphql::models::*;
+use graphql::schema::store as store_module;
+
+/// Feed shows news of the last days only
+const FEED_PERIOD_DAYS: i64 = 30;
+
+graphql_object!(FeedItem: Context as "FeedItem" |&self| {
+    description: "News of the followed store."
+
+    field kind() -> FeedItemKind as "Kind of the news" {
+        self.kind()
+    }
+
+    field store(&executor) -> FieldResult<Option<Store>> as "Store" {
+        store_module::try_get_store(executor.context(), self.store_id, Visibility::Active)
+    }
+
+    field base_product() -> &Option<BaseProduct> as "Published base product" {
+        &self.base_product
+    }
+
+    field coupon() -> &Option<Coupon> as "New coupon" {
+        &self.coupon
+    }
+
+    field published_at() -> String as "Published at" {
+        self.published_at.to_rfc3339()
+    }
+});
+
+graphql_object!(Connection<FeedItem, PageInfoSegments>: Context as "FeedItemConnectionPages" |&self| {
+    description: "FeedItem Connection"
+
+    field edges() -> &[Edge<FeedItem>] {
+        &self.edges
+    }
+
+    field page_info() -> &PageInfoSegments {
+        &self.page_info
+    }
+});
+
+graphql_object!(Edge<FeedItem>: Context as "FeedItemEdge" |&self| {
+    description:"FeedItem Edge"
+
+    field cursor() -> &juniper::ID {
+        &self.cursor
+    }
+
+    field node() -> &FeedItem {
+        &self.node
+    }
+});
+
+/// Followed stores, stores that are not active anymore are skipped
+pub fn followed_stores(context: &Context, user_id: UserId) -> FieldResult<Vec<Store>> {
+    let follows = context.get_stores_microservice().followed_stores(user_id)?;
+    let mut stores = vec![];
+    for follow in follows {
+        if let Some(store) = store_module::try_get_store(context, follow.store_id, Visibility::Active)? {
+            stores.push(store);
+        }
+    }
+    Ok(stores)
+}
+
+/// Newly published base products and active coupons of the followed stores, newest first
+pub fn find_feed(
+    context: &Context,
+    user_id: UserId,
+    current_page: i32,
+    items_count: i32,
+) -> FieldResult<Connection<FeedItem, PageInfoSegments>> {
+    let current_page = cmp::max(current_page, 1);
+    let records_limit = context.config.gateway.records_limit;
+    let items_count = cmp::max(1, cmp::min(items_count, records_limit as i32));
+    let skip = items_count * (current_page - 1);
+
+    let since = Utc::now() - Duration::days(FEED_PERIOD_DAYS);
+    let feed = context.get_stores_microservice().feed(user_id, since, skip, items_count)?;
+
+    let total_count = feed.total_count as i32;
+    let total_pages = cmp::max(0, total_count - 1) / items_count + 1;
+    let edges = feed
+        .items
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| Edge::new(GraphqlID::from((skip as usize + i).to_string()), item))
+        .collect();
+    let page_info = PageInfoSegments {
+        current_page,
+        page_items_count: items_count,
+        total_pages,
+        total_count,
+    };
+    Ok(Connection::new(edges, page_info))
+}
+
+pub fn run_follow_store_mutation(context: &Context, input: FollowStoreInput) -> FieldResult<Store> {
+    let user_id = context.current_user_id("Could not follow store for unauthorized user.")?;
+    let store_id = StoreId(input.store_id);
+    let store = store_module::try_get_store(context, store_id, Visibility::Published)?.ok_or_else(|| {
+        FieldError::new(
+            "Could not follow store.",
+            graphql_value!({ "code": 400, "details": { format!("Store {} not found.", store_id) }}),
+        )
+    })?;
+
+    context
+        .get_stores_microservice()
+        .follow_store(NewStoreFollow { user_id, store_id })?;
+    Ok(store)
+}
+
+pub fn run_unfollow_store_mutation(context: &Context, input: UnfollowStoreInput) -> FieldResult<Option<Store>> {
+    let user_id = context.current_user_id("Could not unfollow store for unauthorized user.")?;
+    let store_id = StoreId(input.store_id);
+
+    match context.get_stores_microservice().unfollow_store(user_id, store_id)? {
+        Some(_) => store_module::try_get_store(context, store_id, Visibility::Active),
+        None => Ok(None),
+    }
+}
+
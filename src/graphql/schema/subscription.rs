@@ -161,6 +161,7 @@ pub fn subscription_payments(
         current_page,
         page_items_count: items_count,
         total_pages,
+        total_count: result.total_count as i32,
     };
     Ok(Connection::new(subscription_payments_edges, page_info))
 }
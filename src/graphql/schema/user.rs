@@ -21,7 +21,13 @@ use super::*;
 use errors::into_graphql;
 use graphql::context::Context;
 use graphql::models::*;
+use graphql::schema::dispute as dispute_module;
+use graphql::schema::order_return as order_return_module;
+use graphql::schema::saved_search as saved_search_module;
+use graphql::schema::store_follow as store_follow_module;
+use graphql::schema::view_history as view_history_module;
 use graphql::schema::warehouse as warehouse_module;
+use graphql::schema::wishlist as wishlist_module;
 use schema::order as order_module;
 
 const MIN_ID: i32 = 0;
@@ -127,12 +133,7 @@ graphql_object!(User: Context as "User" |&self| {
         let context = executor.context();
 
         if let Some(ref alpha3) = self.country {
-            let find_by_alpha3_url = format!(
-                "{}/{}/alpha3/{}",
-                context.config.service_url(Service::Delivery),
-                Model::Country.to_url(),
-                alpha3
-            );
+            let find_by_alpha3_url = context.config.url(Service::Delivery).model(Model::Country).path("alpha3").segment(alpha3).build();
             context.request::<Option<Country>>(Method::Get, find_by_alpha3_url, None).wait()
         } else {
             Ok(None)
@@ -146,9 +147,7 @@ graphql_object!(User: Context as "User" |&self| {
     field roles_on_user_microservices(&executor) -> Option<Vec<UserMicroserviceRole>> as "Fetches user roles on users microservice." {
         let context = executor.context();
 
-        let url = format!("{}/roles/by-user-id/{}",
-            context.config.service_url(Service::Users),
-            self.id);
+        let url = context.config.url(Service::Users).path("roles/by-user-id").segment(self.id).build();
 
         context.request::<Vec<UserMicroserviceRole>>(Method::Get, url, None)
             .wait().ok()
@@ -157,9 +156,7 @@ graphql_object!(User: Context as "User" |&self| {
     field roles_on_stores_microservices(&executor) -> Option<Vec<StoresMicroserviceRole>> as "Fetches user roles on stores microservice." {
         let context = executor.context();
 
-        let url = format!("{}/roles/by-user-id/{}",
-            context.config.service_url(Service::Stores),
-            self.id);
+        let url = context.config.url(Service::Stores).path("roles/by-user-id").segment(self.id).build();
 
         context.request::<Vec<StoresMicroserviceRole>>(Method::Get, url, None)
             .wait().ok()
@@ -189,13 +186,14 @@ graphql_object!(User: Context as "User" |&self| {
         let records_limit = context.config.gateway.records_limit;
         let first = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
 
-        let url = format!("{}/{}?offset={}&count={}&visibility={}",
-            context.config.service_url(Service::Stores),
-            Model::Store.to_url(),
-            raw_id,
-            first + 1,
-            visibility
-        );
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::Store)
+            .query("offset", raw_id)
+            .query("count", first + 1)
+            .query("visibility", &visibility)
+            .build();
 
         context.request::<Vec<Store>>(Method::Get, url, None)
             .map (|stores| {
@@ -210,14 +208,15 @@ graphql_object!(User: Context as "User" |&self| {
                 if has_next_page {
                     store_edges.pop();
                 };
-                let has_previous_page = true;
+                let has_previous_page = raw_id > MIN_ID;
                 let start_cursor =  store_edges.get(0).map(|e| e.cursor.clone());
                 let end_cursor = store_edges.iter().last().map(|e| e.cursor.clone());
                 let page_info = PageInfo {
                     has_next_page,
                     has_previous_page,
                     start_cursor,
-                    end_cursor};
+                    end_cursor,
+                    total_count: None};
                 Connection::new(store_edges, page_info)
             })
             .wait()
@@ -227,12 +226,7 @@ graphql_object!(User: Context as "User" |&self| {
     field deprecated "use query store" store(&executor, id: i32 as "Int id of a store.") -> FieldResult<Option<Store>> as "Fetches store by id." {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}/{}",
-            &context.config.service_url(Service::Stores),
-            Model::Store.to_url(),
-            id.to_string()
-        );
+        let url = context.config.url(Service::Stores).model(Model::Store).segment(id).build();
 
         context.request::<Option<Store>>(Method::Get, url, None)
             .wait()
@@ -241,12 +235,7 @@ graphql_object!(User: Context as "User" |&self| {
     field product(&executor, id: i32 as "Int id of a product.") -> FieldResult<Option<Product>> as "Fetches product by id." {
         let context = executor.context();
 
-        let url = format!(
-            "{}/{}/{}",
-            &context.config.service_url(Service::Stores),
-            Model::Product.to_url(),
-            id.to_string()
-        );
+        let url = context.config.url(Service::Stores).model(Model::Product).segment(id).build();
 
         context.request::<Option<Product>>(Method::Get, url, None)
             .wait()
@@ -266,11 +255,7 @@ graphql_object!(User: Context as "User" |&self| {
         let records_limit = context.config.gateway.records_limit;
         let first = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
 
-        let url = format!("{}/{}?offset={}&count={}",
-            context.config.service_url(Service::Stores),
-            Model::Product.to_url(),
-            raw_id,
-            first + 1);
+        let url = context.config.url(Service::Stores).model(Model::Product).query("offset", raw_id).query("count", first + 1).build();
 
         context.request::<Vec<Product>>(Method::Get, url, None)
             .map (|products| {
@@ -285,14 +270,15 @@ graphql_object!(User: Context as "User" |&self| {
                 if has_next_page {
                     product_edges.pop();
                 };
-                let has_previous_page = true;
+                let has_previous_page = raw_id > MIN_ID;
                 let start_cursor =  product_edges.get(0).map(|e| e.cursor.clone());
                 let end_cursor = product_edges.iter().last().map(|e| e.cursor.clone());
                 let page_info = PageInfo {
                     has_next_page,
                     has_previous_page,
                     start_cursor,
-                    end_cursor};
+                    end_cursor,
+                    total_count: None};
                 Connection::new(product_edges, page_info)
             })
             .wait()
@@ -306,13 +292,7 @@ graphql_object!(User: Context as "User" |&self| {
         let context = executor.context();
         let visibility = visibility.unwrap_or(Visibility::Active);
 
-        let url = format!(
-            "{}/{}/{}?visibility={}",
-            &context.config.service_url(Service::Stores),
-            Model::BaseProduct.to_url(),
-            id.to_string(),
-            visibility
-        );
+        let url = context.config.url(Service::Stores).model(Model::BaseProduct).segment(id).query("visibility", &visibility).build();
 
         context.request::<Option<BaseProduct>>(Method::Get, url, None)
             .wait()
@@ -334,13 +314,14 @@ graphql_object!(User: Context as "User" |&self| {
         let records_limit = context.config.gateway.records_limit;
         let first = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
 
-        let url = format!("{}/{}?offset={}&count={}&visibility={}",
-            context.config.service_url(Service::Stores),
-            Model::BaseProduct.to_url(),
-            raw_id,
-            first + 1,
-            visibility
-        );
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::BaseProduct)
+            .query("offset", raw_id)
+            .query("count", first + 1)
+            .query("visibility", &visibility)
+            .build();
 
         context.request::<Vec<BaseProduct>>(Method::Get, url, None)
             .map (|base_products| {
@@ -355,14 +336,15 @@ graphql_object!(User: Context as "User" |&self| {
                 if has_next_page {
                     base_product_edges.pop();
                 };
-                let has_previous_page = true;
+                let has_previous_page = raw_id > MIN_ID;
                 let start_cursor =  base_product_edges.get(0).map(|e| e.cursor.clone());
                 let end_cursor = base_product_edges.iter().last().map(|e| e.cursor.clone());
                 let page_info = PageInfo {
                     has_next_page,
                     has_previous_page,
                     start_cursor,
-                    end_cursor};
+                    end_cursor,
+                    total_count: None};
                 Connection::new(base_product_edges, page_info)
             })
             .wait()
@@ -372,10 +354,7 @@ graphql_object!(User: Context as "User" |&self| {
     field wizard_store(&executor) -> FieldResult<Option<WizardStore>> as "Fetches wizard store." {
         let context = executor.context();
 
-        let url = format!("{}/{}",
-            &context.config.service_url(Service::Stores),
-            Model::WizardStore.to_url(),
-            );
+        let url = context.config.url(Service::Stores).model(Model::WizardStore).build();
 
         context.request::<Option<WizardStore>>(Method::Get, url, None)
             .wait()
@@ -384,10 +363,7 @@ graphql_object!(User: Context as "User" |&self| {
     field delivery_addresses_full(&executor) -> FieldResult<Option<Vec<UserDeliveryAddress>>> as "Fetches delivery addresses for user." {
         let context = executor.context();
 
-        let url = format!("{}/{}/{}/addresses",
-            context.config.service_url(Service::Delivery),
-            Model::User.to_url(),
-            self.id);
+        let url = context.config.url(Service::Delivery).model(Model::User).segment(self.id).path("addresses").build();
 
         context.request::<Vec<UserDeliveryAddress>>(Method::Get, url, None)
             .wait()
@@ -397,10 +373,7 @@ graphql_object!(User: Context as "User" |&self| {
     field deprecated "use query delivery_addresses_full" delivery_addresses(&executor) -> FieldResult<Option<Vec<UserDeliveryAddress>>> as "Fetches delivery addresses for user." {
         let context = executor.context();
 
-        let url = format!("{}/{}/delivery_addresses/{}",
-            context.config.service_url(Service::Users),
-            Model::User.to_url(),
-            self.id);
+        let url = context.config.url(Service::Users).model(Model::User).path("delivery_addresses").segment(self.id).build();
 
         context.request::<Vec<UserDeliveryAddress>>(Method::Get, url, None)
             .wait()
@@ -408,16 +381,21 @@ graphql_object!(User: Context as "User" |&self| {
     }
 
     field orders(&executor,
-        current_page : i32 as "Current page",
-        items_count : i32 as "Items count",
+        current_page = None : Option<i32> as "Current page, orders are paged by first/after/last/before without it",
+        items_count = None : Option<i32> as "Items count",
+        first = None : Option<i32> as "First edges",
+        after = None : Option<GraphqlID>  as "Cursor of the edge to start after",
+        last = None : Option<i32> as "Last edges",
+        before = None : Option<GraphqlID>  as "Cursor of the edge to end before",
         search_term_options : SearchOrderOptionInput as "Search options pattern")
             -> FieldResult<Option<Connection<GraphQLOrder, PageInfoOrdersSearch>>> as "Fetches orders using relay connection." {
         let context = executor.context();
 
-        let offset = items_count * (current_page - 1);
-
         let records_limit = context.config.gateway.records_limit;
-        let count = cmp::min(items_count, records_limit as i32);
+        let page = match current_page {
+            Some(current_page) => PageRequest::from_page_number(current_page, items_count.unwrap_or(records_limit as i32), records_limit),
+            None => PageRequest::new(first, after, last, before, None, records_limit)?,
+        };
 
         let created_from = match search_term_options.created_from.clone() {
             Some(value) => {
@@ -446,10 +424,7 @@ graphql_object!(User: Context as "User" |&self| {
         };
 
         let customer = search_term_options.email.clone().and_then(|email| {
-            let url = format!("{}/{}/by_email?email={}",
-                context.config.service_url(Service::Users),
-                Model::User.to_url(),
-                email);
+            let url = context.config.url(Service::Users).model(Model::User).path("by_email").query("email", &email).build();
 
             context.request::<Option<User>>(Method::Get, url, None)
                 .wait()
@@ -473,27 +448,7 @@ graphql_object!(User: Context as "User" |&self| {
             .sync()
             .map_err(into_graphql)
             .map(|res| res.into_iter().map(GraphQLOrder).collect())
-            .map (move |orders: Vec<GraphQLOrder>| {
-                let total_pages = (orders.iter().count() as f32 / items_count as f32).ceil() as i32;
-
-                let mut orders_edges: Vec<Edge<GraphQLOrder>> = orders
-                    .into_iter()
-                    .skip(offset as usize)
-                    .take(count as usize)
-                    .map(|order| Edge::new(
-                                juniper::ID::from(order.0.id.to_string()),
-                                order.clone()
-                            ))
-                    .collect();
-
-                let page_info = PageInfoOrdersSearch {
-                    total_pages,
-                    current_page,
-                    page_items_count: items_count,
-                    search_term_options: search_term_options.into()
-                };
-                Connection::new(orders_edges, page_info)
-            })
+            .map(|orders| order_module::orders_connection(orders, page, search_term_options.into()))
             .map(Some)
     }
 
@@ -503,6 +458,75 @@ graphql_object!(User: Context as "User" |&self| {
         order_module::try_get_order(context, OrderIdentifier::Slug(OrderSlug(slug)))
     }
 
+    field disputes(&executor,
+        current_page : i32 as "Current page",
+        items_count : i32 as "Items count",
+        status : Option<DisputeStatus> as "Dispute status")
+            -> FieldResult<Connection<Dispute, PageInfoSegments>> as "Disputes opened by the user." {
+        let context = executor.context();
+        let search = DisputeSearch {
+            customer_id: Some(self.id),
+            status,
+            ..Default::default()
+        };
+
+        dispute_module::find_disputes(context, current_page, items_count, search)
+    }
+
+    field returns(&executor,
+        current_page : i32 as "Current page",
+        items_count : i32 as "Items count",
+        status : Option<ReturnStatus> as "Return status")
+            -> FieldResult<Connection<OrderReturn, PageInfoSegments>> as "Returns opened by the user." {
+        let context = executor.context();
+        let search = OrderReturnSearch {
+            customer_id: Some(self.id),
+            status,
+            ..Default::default()
+        };
+
+        order_return_module::find_returns(context, current_page, items_count, search)
+    }
+
+    field wishlist(&executor,
+        current_page : i32 as "Current page",
+        items_count : i32 as "Items count")
+            -> FieldResult<Connection<WishlistEntry, PageInfoSegments>> as "Products saved by the user." {
+        let context = executor.context();
+
+        wishlist_module::find_wishlist(context, self.id.into(), current_page, items_count)
+    }
+
+    field followed_stores(&executor) -> FieldResult<Vec<Store>> as "Stores followed by the user." {
+        let context = executor.context();
+
+        store_follow_module::followed_stores(context, self.id)
+    }
+
+    field feed(&executor,
+        current_page : i32 as "Current page",
+        items_count : i32 as "Items count")
+            -> FieldResult<Connection<FeedItem, PageInfoSegments>> as "News of the followed stores: published base products and new coupons." {
+        let context = executor.context();
+
+        store_follow_module::find_feed(context, self.id, current_page, items_count)
+    }
+
+    field recently_viewed(&executor,
+        current_page : i32 as "Current page",
+        items_count : i32 as "Items count")
+            -> FieldResult<Connection<BaseProduct, PageInfoSegments>> as "Recently viewed base products, newest first." {
+        let context = executor.context();
+
+        view_history_module::find_recently_viewed(context, self.id.into(), current_page, items_count)
+    }
+
+    field saved_searches(&executor) -> FieldResult<Vec<SavedSearch>> as "Products searches saved by the user." {
+        let context = executor.context();
+
+        saved_search_module::saved_searches(context, self.id)
+    }
+
 
     field warehouse(&executor, slug: String as "Slug of a warehouse.") -> FieldResult<Option<GraphQLWarehouse>> as "Fetches warehouse by slug." {
         let context = executor.context();
@@ -512,9 +536,7 @@ graphql_object!(User: Context as "User" |&self| {
 
     field invoice(&executor, id: String as "Invoice id") -> FieldResult<Option<Invoice>> as "Invoice" {
         let context = executor.context();
-        let url = format!("{}/invoices/by-id/{}",
-            context.config.service_url(Service::Billing),
-            id);
+        let url = context.config.url(Service::Billing).path("invoices/by-id").segment(id).build();
 
         context.request::<Option<Invoice>>(Method::Get, url, None)
             .wait()
@@ -602,8 +624,7 @@ graphql_object!(Edge<CartProduct>: Context as "CartProductEdge" |&self| {
 });
 
 pub fn get_user_by_id(context: &Context, user_id: UserId) -> Result<User, FieldError> {
-    let users_url = context.config.service_url(Service::Users);
-    let url = format!("{}/{}/{}", users_url, Model::User.to_url(), user_id);
+    let url = context.config.url(Service::Users).model(Model::User).segment(user_id).build();
     context
         .request::<Option<User>>(Method::Get, url, None)
         .wait()?
@@ -614,8 +635,7 @@ pub fn get_user_by_id(context: &Context, user_id: UserId) -> Result<User, FieldE
 }
 
 pub fn run_verify_email(context: &Context, input: VerifyEmailApply) -> FieldResult<VerifyEmailApplyOutput> {
-    let saga_addr = context.config.saga_microservice.url.clone();
-    let url = format!("{}/email_verify_apply", saga_addr);
+    let url = context.config.saga_url().path("email_verify_apply").build();
     let body = serde_json::to_string(&input)?;
     let result = context.request::<EmailVerifyApplyToken>(Method::Post, url, Some(body)).wait()?;
 
@@ -628,12 +648,7 @@ pub fn run_verify_email(context: &Context, input: VerifyEmailApply) -> FieldResu
 
 pub fn change_alpha2_to_alpha3(context: &Context, additional_data: &mut NewUserAdditionalData) {
     additional_data.country = additional_data.country.clone().and_then(|alpha2| {
-        let find_by_alpha2_url = format!(
-            "{}/{}/alpha2/{}",
-            context.config.service_url(Service::Delivery),
-            Model::Country.to_url(),
-            alpha2
-        );
+        let find_by_alpha2_url = context.config.url(Service::Delivery).model(Model::Country).path("alpha2").segment(&alpha2).build();
         let country: Option<Country> = match context.request::<Option<Country>>(Method::Get, find_by_alpha2_url, None).wait() {
             Ok(country) => country,
             Err(err) => {
@@ -646,10 +661,21 @@ pub fn change_alpha2_to_alpha3(context: &Context, additional_data: &mut NewUserA
 }
 
 pub fn existing_reset_token(context: &Context, input: ExistingResetTokenInput) -> FieldResult<ResetToken> {
-    let users_url = context.config.service_url(Service::Users);
     let url = match input.token_type {
-        TokenTypeInput::EmailVerify => format!("{}/{}/{}/email_verify_token", users_url, Model::User.to_url(), input.user_id),
-        TokenTypeInput::PasswordReset => format!("{}/{}/{}/password_reset_token", users_url, Model::User.to_url(), input.user_id),
+        TokenTypeInput::EmailVerify => context
+            .config
+            .url(Service::Users)
+            .model(Model::User)
+            .segment(input.user_id)
+            .path("email_verify_token")
+            .build(),
+        TokenTypeInput::PasswordReset => context
+            .config
+            .url(Service::Users)
+            .model(Model::User)
+            .segment(input.user_id)
+            .path("password_reset_token")
+            .build(),
     };
     context.request::<ResetToken>(Method::Get, url, None).wait()
 }
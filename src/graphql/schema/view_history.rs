@@ -0,0 +1,86 @@
+//! File containing recently viewed products of graphql schema
+use std::cmp;
+
+use juniper::ID as GraphqlID;
+use futures::Future;
+use juniper::FieldResult;
+
+use stq_routes::model::Model;
+use stq_routes::service::Service;
+use stq_types::BaseProductId;
+
+use graphql::context::Context;
+use graphql::microservice::requests::GetBaseProductsRequest;
+use graphql::models::*;
+
+/// Recently viewed base products, products that are not published anymore are skipped
+pub fn find_recently_viewed(
+    context: &Context,
+    visitor: Visitor,
+    current_page: i32,
+    items_count: i32,
+) -> FieldResult<Connection<BaseProduct, PageInfoSegments>> {
+    let current_page = cmp::max(current_page, 1);
+    let records_limit = context.config.gateway.records_limit;
+    let items_count = cmp::max(1, cmp::min(items_count, records_limit as i32));
+    let skip = items_count * (current_page - 1);
+    let stores = context.get_stores_microservice();
+    let history = stores.view_history(visitor, skip, items_count)?;
+    let base_products = stores.get_base_products(GetBaseProductsRequest {
+        ids: history.items.iter().map(|item| item.base_product_id).collect(),
+    })?;
+    let total_pages = cmp::max(0, history.total_count as i32 - 1) / items_count + 1;
+    let edges = history
+        .items
+        .into_iter()
+        .filter_map(|item| base_products.iter().find(|base_product| base_product.id == item.base_product_id).cloned())
+        .map(|base_product| {
+            Edge::new(
+                GraphqlID::from(ID::new(Service::Stores, Model::BaseProduct, base_product.id.0).to_string()),
+                base_product,
+            )
+        })
+        .collect();
+    let page_info = PageInfoSegments {
+        current_page,
+        page_items_count: items_count,
+        total_pages,
+        total_count: history.total_count as i32,
+    };
+    Ok(Connection::new(edges, page_info))
+}
+
+/// Records view of the base product page by the current visitor in background,
+/// errors are logged because they should not prevent showing the product
+pub fn record_view(context: &Context, base_product_id: BaseProductId) {
+    if let Some(visitor) = context.visitor() {
+        let request = context
+            .get_stores_microservice()
+            .record_view(visitor, NewView { base_product_id })
+            .map_err(move |e| warn!("Could not record view of base product {} by {:?}: {:?}", base_product_id, visitor, e));
+        context.spawn(request);
+    }
+}
+
+pub fn view_history_settings(context: &Context) -> FieldResult<ViewHistorySettings> {
+    let visitor = context.current_visitor("Could not get recently viewed products settings for unauthorized user.")?;
+    context.get_stores_microservice().view_history_settings(visitor)
+}
+
+pub fn run_clear_view_history_mutation(context: &Context, _input: ClearViewHistoryInput) -> FieldResult<ViewHistorySettings> {
+    let visitor = context.current_visitor("Could not clear recently viewed products for unauthorized user.")?;
+    let stores = context.get_stores_microservice();
+    stores.clear_view_history(visitor)?;
+    stores.view_history_settings(visitor)
+}
+
+/// Turning the history off also clears it
+pub fn run_set_view_history_enabled_mutation(context: &Context, input: SetViewHistoryEnabledInput) -> FieldResult<ViewHistorySettings> {
+    let visitor = context.current_visitor("Could not change recently viewed products settings for unauthorized user.")?;
+    let stores = context.get_stores_microservice();
+    let settings = stores.update_view_history_settings(visitor, ViewHistorySettings { enabled: input.enabled })?;
+    if !settings.enabled {
+        stores.clear_view_history(visitor)?;
+    }
+    Ok(settings)
+}
@@ -1,6 +1,5 @@
 //! File containing warehouse object of graphql schema
 use std::cmp;
-use std::str::FromStr;
 
 use futures::Future;
 use hyper::Method;
@@ -54,13 +53,7 @@ graphql_object!(GraphQLWarehouse: Context as "Warehouse" |&self| {
         let context = executor.context();
         let visibility = visibility.unwrap_or(Visibility::Active);
 
-        let url = format!(
-            "{}/{}/{}?visibility={}",
-            &context.config.service_url(Service::Stores),
-            Model::Store.to_url(),
-            self.0.store_id.to_string(),
-            visibility
-        );
+        let url = context.config.url(Service::Stores).model(Model::Store).segment(self.0.store_id).query("visibility", &visibility).build();
 
         context.request::<Option<Store>>(Method::Get, url, None)
             .wait()
@@ -83,13 +76,15 @@ graphql_object!(GraphQLWarehouse: Context as "Warehouse" |&self| {
         let records_limit = context.config.gateway.records_limit;
         let count = cmp::min(items_count, records_limit as i32);
 
-        let url = format!("{}/{}/search?offset={}&count={}&visibility={}",
-            context.config.service_url(Service::Stores),
-            Model::BaseProduct.to_url(),
-            offset,
-            count,
-            visibility
-        );
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::BaseProduct)
+            .path("search")
+            .query("offset", offset)
+            .query("count", count)
+            .query("visibility", &visibility)
+            .build();
 
         let search_term = if let Some(search_term) = search_term {
             let options = if let Some(mut options) = search_term.options {
@@ -157,10 +152,7 @@ graphql_object!(GraphQLWarehouse: Context as "Warehouse" |&self| {
 
                     let body = serde_json::to_string(&search_term)?;
 
-                    let url = format!("{}/{}/search/filters/count",
-                        context.config.service_url(Service::Stores),
-                        Model::BaseProduct.to_url(),
-                        );
+                    let url = context.config.url(Service::Stores).model(Model::BaseProduct).path("search/filters/count").build();
 
                     let total_items = context.request::<i32>(Method::Post, url, Some(body))
                         .wait()?;
@@ -186,21 +178,19 @@ graphql_object!(GraphQLWarehouse: Context as "Warehouse" |&self| {
 
         let context = executor.context();
 
-        let offset = after
-            .and_then(|id|{
-                i32::from_str(&id).map(|i| i + 1).ok()
-            })
-            .unwrap_or_default();
+        let offset = Cursor::offset_after(after)?;
 
         let records_limit = context.config.gateway.records_limit;
         let count = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
 
-        let url = format!("{}/{}/auto_complete?offset={}&count={}",
-            context.config.service_url(Service::Stores),
-            Model::BaseProduct.to_url(),
-            offset,
-            count + 1,
-            );
+        let url = context
+            .config
+            .url(Service::Stores)
+            .model(Model::BaseProduct)
+            .path("auto_complete")
+            .query("offset", offset)
+            .query("count", count + 1)
+            .build();
 
         let search_term = AutoCompleteProductNameInput {
             name,
@@ -217,14 +207,15 @@ graphql_object!(GraphQLWarehouse: Context as "Warehouse" |&self| {
                 if has_next_page {
                     full_name_edges.pop();
                 };
-                let has_previous_page = true;
+                let has_previous_page = offset > 0;
                 let start_cursor =  full_name_edges.get(0).map(|e| e.cursor.clone());
                 let end_cursor = full_name_edges.iter().last().map(|e| e.cursor.clone());
                 let page_info = PageInfo {
                     has_next_page,
                     has_previous_page,
                     start_cursor,
-                    end_cursor};
+                    end_cursor,
+                    total_count: None};
                 Connection::new(full_name_edges, page_info)
             })
             .wait()
@@ -258,28 +249,18 @@ graphql_object!(Edge<GraphQLStock>: Context as "StocksEdge" |&self| {
 });
 
 pub fn get_warehouses_for_store(context: &Context, store_id: StoreId) -> FieldResult<Vec<Warehouse>> {
-    let url = format!(
-        "{}/{}/by-store/{}",
-        context.config.service_url(Service::Warehouses),
-        Model::Warehouse.to_url(),
-        store_id,
-    );
+    let url = context.config.url(Service::Warehouses).model(Model::Warehouse).path("by-store").segment(store_id).build();
 
     context.request::<Vec<Warehouse>>(Method::Get, url, None).wait()
 }
 
 pub fn try_get_warehouse(context: &Context, warehouse_id: WarehouseIdentifier) -> FieldResult<Option<GraphQLWarehouse>> {
-    let warehouse_route = match warehouse_id {
-        WarehouseIdentifier::Id(id) => format!("by-id/{}", id),
-        WarehouseIdentifier::Slug(slug) => format!("by-slug/{}", slug),
-    };
-
-    let url = format!(
-        "{}/{}/{}",
-        context.config.service_url(Service::Warehouses),
-        Model::Warehouse.to_url(),
-        warehouse_route
-    );
+    let warehouses_url = context.config.url(Service::Warehouses).model(Model::Warehouse);
+    let url = match warehouse_id {
+        WarehouseIdentifier::Id(id) => warehouses_url.path("by-id").segment(id),
+        WarehouseIdentifier::Slug(slug) => warehouses_url.path("by-slug").segment(slug),
+    }
+    .build();
 
     context
         .request::<Option<Warehouse>>(Method::Get, url, None)
@@ -0,0 +1,194 @@
+//! File containing wishlist object of graphql schema
+use std::cmp;
+
+use juniper;
+use juniper::ID as GraphqlID;
+use juniper::{FieldError, FieldResult};
+
+use stq_types::{ProductId, SessionId, UserId};
+
+use graphql::context::Context;
+use graphql::microservice::requests::GetProductsRequest;
+use graphql::models::*;
+use graphql::schema::stock as stock_module;
+
+graphql_object!(WishlistEntry: Context as "WishlistItem" |&self| {
+    description: "Product saved by the customer."
+
+    field product_id() -> &i32 as "Product int id" {
+        &self.item.product_id.0
+    }
+
+    field product() -> &Option<Product> as "Product, empty if it was deleted" {
+        &self.product
+    }
+
+    field added_at() -> String as "Added at" {
+        self.item.added_at.to_rfc3339()
+    }
+
+    field price_drop() -> bool as "Product discount has grown since the product was saved" {
+        self.price_drop()
+    }
+
+    field in_stock() -> bool as "Product is in stock in any warehouse" {
+        self.in_stock
+    }
+
+    field back_in_stock() -> bool as "Product was out of stock when saved and is in stock now" {
+        !self.item.in_stock_at_add && self.in_stock
+    }
+});
+
+graphql_object!(Connection<WishlistEntry, PageInfoSegments>: Context as "WishlistItemConnectionPages" |&self| {
+    description: "WishlistItem Connection"
+
+    field edges() -> &[Edge<WishlistEntry>] {
+        &self.edges
+    }
+
+    field page_info() -> &PageInfoSegments {
+        &self.page_info
+    }
+});
+
+graphql_object!(Edge<WishlistEntry>: Context as "WishlistItemEdge" |&self| {
+    description:"WishlistItem Edge"
+
+    field cursor() -> &juniper::ID {
+        &self.cursor
+    }
+
+    field node() -> &WishlistEntry {
+        &self.node
+    }
+});
+
+impl WishlistEntry {
+    fn price_drop(&self) -> bool {
+        let discount_at_add = self.item.discount_at_add.unwrap_or_default();
+        self.product
+            .as_ref()
+            .and_then(|product| product.discount)
+            .map(|discount| discount > discount_at_add)
+            .unwrap_or(false)
+    }
+}
+
+pub fn find_wishlist(
+    context: &Context,
+    visitor: Visitor,
+    current_page: i32,
+    items_count: i32,
+) -> FieldResult<Connection<WishlistEntry, PageInfoSegments>> {
+    let current_page = cmp::max(current_page, 1);
+    let records_limit = context.config.gateway.records_limit;
+    let items_count = cmp::max(1, cmp::min(items_count, records_limit as i32));
+    let skip = items_count * (current_page - 1);
+    let stores = context.get_stores_microservice();
+    let wishlist = stores.wishlist(visitor, skip, items_count)?;
+    let product_ids = wishlist.items.iter().map(|item| item.product_id).collect::<Vec<_>>();
+    let products = stores.get_products(GetProductsRequest { ids: product_ids.clone() })?;
+    let stocks = stock_module::get_stocks_for_products(context, product_ids)?;
+    let total_pages = cmp::max(0, wishlist.total_count as i32 - 1) / items_count + 1;
+    let edges = wishlist
+        .items
+        .into_iter()
+        .map(|item| {
+            let product = products.iter().find(|product| product.id == item.product_id).cloned();
+            let in_stock = product.is_some()
+                && stocks
+                    .iter()
+                    .filter(|stock| stock.product_id == item.product_id)
+                    .map(|stock| stock.quantity.0)
+                    .sum::<i32>()
+                    > 0;
+            Edge::new(
+                GraphqlID::from(item.product_id.to_string()),
+                WishlistEntry { item, product, in_stock },
+            )
+        })
+        .collect();
+    let page_info = PageInfoSegments {
+        current_page,
+        page_items_count: items_count,
+        total_pages,
+        total_count: wishlist.total_count as i32,
+    };
+    Ok(Connection::new(edges, page_info))
+}
+
+pub fn run_add_to_wishlist_mutation(context: &Context, input: AddToWishlistInput) -> FieldResult<WishlistEntry> {
+    let visitor = context.current_visitor("Could not add product to wishlist for unauthorized user.")?;
+    let stores = context.get_stores_microservice();
+    let product_id = ProductId(input.product_id);
+    let product = stores.get_product(product_id)?.ok_or_else(|| {
+        FieldError::new(
+            "Could not add product to wishlist.",
+            graphql_value!({ "code": 400, "details": { format!("Product {} not found.", product_id) }}),
+        )
+    })?;
+
+    let in_stock = product_in_stock(context, &product)?;
+    let item = stores.add_to_wishlist(
+        visitor,
+        NewWishlistItem {
+            product_id,
+            discount_at_add: product.discount,
+            in_stock_at_add: in_stock,
+        },
+    )?;
+
+    Ok(WishlistEntry {
+        item,
+        product: Some(product),
+        in_stock,
+    })
+}
+
+pub fn run_remove_from_wishlist_mutation(context: &Context, input: RemoveFromWishlistInput) -> FieldResult<Option<WishlistEntry>> {
+    let visitor = context.current_visitor("Could not remove product from wishlist for unauthorized user.")?;
+    let stores = context.get_stores_microservice();
+    let product_id = ProductId(input.product_id);
+
+    let item = match stores.remove_from_wishlist(visitor, product_id)? {
+        Some(item) => item,
+        None => return Ok(None),
+    };
+    let product = stores.get_product(product_id)?;
+    let in_stock = match product {
+        Some(ref product) => product_in_stock(context, product)?,
+        None => false,
+    };
+
+    Ok(Some(WishlistEntry { item, product, in_stock }))
+}
+
+/// Merges wishlist of the session into the wishlist of the user who has just logged in.
+/// Unlike the cart it is always merged, saving a product twice changes nothing.
+/// Errors are logged because they should not prevent the login
+pub fn merge_session_wishlist_on_login(context: &Context, jwt: &JWT) {
+    let session_id = match context.session_id {
+        Some(session_id) => session_id,
+        None => return,
+    };
+
+    let result = context
+        .verify_jwt(jwt)
+        .and_then(|payload| merge_session_wishlist(context, session_id, payload.user_id));
+    if let Err(e) = result {
+        warn!("Could not merge session {} wishlist into user wishlist: {:?}", session_id.0, e);
+    }
+}
+
+/// Products saved in both wishlists keep the state of the user wishlist, session wishlist is cleared
+pub fn merge_session_wishlist(context: &Context, session_id: SessionId, user_id: UserId) -> FieldResult<Vec<WishlistItem>> {
+    context.get_stores_microservice().merge_wishlist(MergeWishlist {
+        from_session_id: session_id,
+        to_user_id: user_id,
+    })
+}
+
+fn product_in_stock(context: &Context, product: &Product) -> FieldResult<bool> {
+    product.get_quantity(context).map(|quantity| quantity.unwrap_or_default() > 0)
+}
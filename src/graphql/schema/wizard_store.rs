@@ -1,6 +1,5 @@
 //! File containing wizard store object of graphql schema
 use std::cmp;
-use std::str::FromStr;
 
 use futures::Future;
 use hyper::Method;
@@ -39,13 +38,7 @@ graphql_object!(WizardStore: Context as "WizardStore" |&self| {
 
         if let Some(ref store_id) = self.store_id {
             let context = executor.context();
-            let url = format!(
-                "{}/{}/{}?visibility={}",
-                &context.config.service_url(Service::Stores),
-                Model::Store.to_url(),
-                store_id.to_string(),
-                visibility
-            );
+            let url = context.config.url(Service::Stores).model(Model::Store).segment(store_id).query("visibility", &visibility).build();
             context.request::<Option<Store>>(Method::Get, url, None)
                 .wait()
         } else {
@@ -57,12 +50,7 @@ graphql_object!(WizardStore: Context as "WizardStore" |&self| {
         if let Some(ref store_id) = self.store_id {
             let context = executor.context();
 
-            let url = format!(
-                "{}/{}/{}",
-                &context.config.service_url(Service::Stores),
-                Model::ModeratorStoreComment.to_url(),
-                store_id
-            );
+            let url = context.config.url(Service::Stores).model(Model::ModeratorStoreComment).segment(store_id).build();
 
             context.request::<Option<ModeratorStoreComments>>(Method::Get, url, None)
                 .wait()
@@ -118,24 +106,21 @@ graphql_object!(WizardStore: Context as "WizardStore" |&self| {
             -> FieldResult<Option<Connection<BaseProduct, PageInfo>>> as "Fetches wizard step three." {
         let context = executor.context();
 
-        let offset = after
-            .and_then(|id|{
-                i32::from_str(&id).map(|i| i + 1).ok()
-            })
-            .unwrap_or_default();
+        let offset = Cursor::offset_after(after)?;
 
         let records_limit = context.config.gateway.records_limit;
         let count = cmp::min(first.unwrap_or(records_limit as i32), records_limit as i32);
 
         if let Some(ref store_id) = self.store_id {
-            let url =  format!(
-                        "{}/{}/{}/products?offset={}&count={}",
-                        &context.config.service_url(Service::Stores),
-                        Model::Store.to_url(),
-                        store_id,
-                        offset,
-                        count + 1
-                    );
+            let url =  context
+                .config
+                .url(Service::Stores)
+                .model(Model::Store)
+                .segment(store_id)
+                .path("products")
+                .query("offset", offset)
+                .query("count", count + 1)
+                .build();
 
             context.request::<Vec<BaseProduct>>(Method::Get, url, None)
                 .map (|base_products| {
@@ -144,14 +129,15 @@ graphql_object!(WizardStore: Context as "WizardStore" |&self| {
                     if has_next_page {
                         base_product_edges.pop();
                     };
-                    let has_previous_page = true;
+                    let has_previous_page = offset > 0;
                     let start_cursor =  base_product_edges.get(0).map(|e| e.cursor.clone());
                     let end_cursor = base_product_edges.iter().last().map(|e| e.cursor.clone());
                     let page_info = PageInfo {
                         has_next_page,
                         has_previous_page,
                         start_cursor,
-                        end_cursor};
+                        end_cursor,
+                        total_count: None};
                     Connection::new(base_product_edges, page_info)
                 })
                 .wait()
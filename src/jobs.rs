@@ -0,0 +1,50 @@
+//! Periodic jobs run by the gateway next to the http server
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::prelude::*;
+use futures_cpupool::CpuPool;
+use tokio_core::reactor::{Handle, Interval};
+
+use stq_http::client::{ClientHandle, TimeLimitedHttpClient};
+
+use config::Config;
+use graphql::context::Context;
+use graphql::models::Synonyms;
+use graphql::schema::saved_search as saved_search_module;
+
+/// Re-runs saved searches every `saved_searches.alerts_interval_s` and notifies users about new matches,
+/// replicas of the gateway share the saved searches by claiming them. Checks run on the cpu pool
+/// because microservice requests of the graphql context block,
+/// the next check starts only after the previous one has finished
+pub fn start_saved_search_alerts(handle: &Handle, http_client: ClientHandle, cpu_pool: CpuPool, config: Config, synonyms: Arc<Synonyms>) {
+    if config.saved_searches.alerts_interval_s == 0 {
+        info!("Saved search alerts are turned off");
+        return;
+    }
+
+    let interval = Duration::from_secs(config.saved_searches.alerts_interval_s);
+    let request_timeout = Duration::from_millis(config.gateway.http_timeout_ms);
+    let ticks = match Interval::new(interval, handle) {
+        Ok(ticks) => ticks,
+        Err(e) => {
+            error!("Could not start saved search alerts: {}", e);
+            return;
+        }
+    };
+
+    handle.spawn(
+        ticks
+            .map_err(|e| error!("Saved search alerts timer failed: {}", e))
+            .for_each(move |_| {
+                let client = TimeLimitedHttpClient::new(http_client.clone(), request_timeout);
+                let config = config.clone();
+                let synonyms = synonyms.clone();
+                cpu_pool.spawn_fn(move || {
+                    let context = Context::new(client, None, None, None, None, config, None).with_synonyms(synonyms);
+                    saved_search_module::notify_new_matches(&context);
+                    Ok(())
+                })
+            }),
+    );
+}
@@ -26,6 +26,7 @@ extern crate serde;
 extern crate serde_derive;
 #[macro_use]
 extern crate serde_json;
+extern crate sha2;
 extern crate tokio_core;
 extern crate tokio_signal;
 extern crate uuid;
@@ -40,7 +41,11 @@ pub mod config;
 pub mod controller;
 pub mod errors;
 pub mod graphql;
+pub mod jobs;
 pub mod sentry_integration;
+pub mod spreadsheet;
+pub mod text;
+pub mod url;
 
 use std::fs::File;
 use std::io::prelude::*;
@@ -80,6 +85,14 @@ pub fn start(config: Config) {
     let cpu_pool = CpuPool::new(config.gateway.graphql_thread_pool_size);
     let jwt_leeway = config.jwt.leeway;
     let schema = Arc::new(schema::create());
+    let co_purchases_cache = Arc::new(graphql::microservice::CoPurchasesCache::default());
+    let synonyms = Arc::new(schema::search_suggestion::load_synonyms(&config.search.synonyms_path).unwrap_or_else(|e| {
+        error!("Could not load synonyms from {}, search starts without them: {:?}", config.search.synonyms_path, e);
+        graphql::models::Synonyms::default()
+    }));
+    let controller_handle = handle.clone();
+
+    jobs::start_saved_search_alerts(&handle, client_handle.clone(), cpu_pool.clone(), config.clone(), synonyms.clone());
 
     let serve = Http::new()
         .serve_addr_handle(&address, &*handle, {
@@ -93,6 +106,9 @@ pub fn start(config: Config) {
                     jwt_leeway,
                     config.clone(),
                     schema.clone(),
+                    (*controller_handle).clone(),
+                    co_purchases_cache.clone(),
+                    synonyms.clone(),
                 ))
                 .with_middleware(move |mut resp| {
                     let contains_acao = resp.headers().has::<AccessControlAllowOrigin>();
@@ -104,7 +120,14 @@ pub fn start(config: Config) {
                     resp
                 });
 
-                Ok(app)
+                Ok(controller::downloads::DownloadsService::new(
+                    app,
+                    client_handle.clone(),
+                    cpu_pool.clone(),
+                    jwt_public_key.clone(),
+                    jwt_leeway,
+                    config.clone(),
+                ))
             }
         })
         .unwrap_or_else(|reason| {
@@ -0,0 +1,496 @@
+//! Readers and writers of simple single sheet tables in CSV and XLSX formats
+use std::fmt::Write;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cell {
+    Text(String),
+    Number(f64),
+}
+
+impl Cell {
+    pub fn text<S: Into<String>>(value: S) -> Self {
+        Cell::Text(value.into())
+    }
+
+    pub fn optional_text(value: Option<String>) -> Self {
+        Cell::Text(value.unwrap_or_default())
+    }
+}
+
+pub type Row = Vec<Cell>;
+
+/// Writes rows as RFC 4180 CSV
+pub fn to_csv(rows: &[Row]) -> String {
+    let mut csv = String::new();
+    for row in rows {
+        let line = row
+            .iter()
+            .map(|cell| match *cell {
+                Cell::Text(ref value) => escape_csv(value),
+                Cell::Number(value) => value.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        csv.push_str(&line);
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// Parses RFC 4180 CSV into records of text fields, empty lines are skipped
+pub fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut field_started = false;
+    let mut line = 1;
+    let mut chars = text.trim_left_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                c => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c)
+                }
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() && !field_started => {
+                in_quotes = true;
+                field_started = true;
+            }
+            '"' => return Err(format!("Unexpected quote in line {}.", line)),
+            ',' => {
+                record.push(field.clone());
+                field.clear();
+                field_started = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' | '\n' => {
+                line += 1;
+                if field_started || !field.is_empty() || !record.is_empty() {
+                    record.push(field.clone());
+                    records.push(record.clone());
+                }
+                record.clear();
+                field.clear();
+                field_started = false;
+            }
+            c => {
+                field.push(c);
+                field_started = true;
+            }
+        }
+    }
+
+    if in_quotes {
+        return Err(format!("Quoted field is not closed in line {}.", line));
+    }
+    if field_started || !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/// Writes rows as the only sheet of XLSX workbook
+pub fn to_xlsx(sheet_name: &str, rows: &[Row]) -> Vec<u8> {
+    let content_types = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+        r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+        r#"<Default Extension="xml" ContentType="application/xml"/>"#,
+        r#"<Override PartName="/xl/workbook.xml" "#,
+        r#"ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#,
+        r#"<Override PartName="/xl/worksheets/sheet1.xml" "#,
+        r#"ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+        r#"</Types>"#,
+    );
+    let rels = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        r#"<Relationship Id="rId1" "#,
+        r#"Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>"#,
+        r#"</Relationships>"#,
+    );
+    let workbook_rels = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        r#"<Relationship Id="rId1" "#,
+        r#"Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>"#,
+        r#"</Relationships>"#,
+    );
+    let workbook = format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" "#,
+            r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">"#,
+            r#"<sheets><sheet name="{}" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+        ),
+        escape_xml(sheet_name)
+    );
+
+    let mut zip = ZipWriter::default();
+    zip.add_file("[Content_Types].xml", content_types.as_bytes());
+    zip.add_file("_rels/.rels", rels.as_bytes());
+    zip.add_file("xl/workbook.xml", workbook.as_bytes());
+    zip.add_file("xl/_rels/workbook.xml.rels", workbook_rels.as_bytes());
+    zip.add_file("xl/worksheets/sheet1.xml", worksheet(rows).as_bytes());
+    zip.finish()
+}
+
+fn worksheet(rows: &[Row]) -> String {
+    let mut sheet = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+    ));
+    for (i, row) in rows.iter().enumerate() {
+        let _ = write!(sheet, r#"<row r="{}">"#, i + 1);
+        for cell in row {
+            match *cell {
+                Cell::Text(ref value) => {
+                    let _ = write!(sheet, r#"<c t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#, escape_xml(value));
+                }
+                Cell::Number(value) => {
+                    let _ = write!(sheet, "<c><v>{}</v></c>", value);
+                }
+            }
+        }
+        sheet.push_str("</row>");
+    }
+    sheet.push_str("</sheetData></worksheet>");
+    sheet
+}
+
+/// Quotes the value if needed. Values that spreadsheet applications would evaluate as formulas are prefixed with `'`,
+/// signed numbers like phone numbers or negative amounts are kept as is.
+fn escape_csv(value: &str) -> String {
+    let value = if is_formula(value) { format!("'{}", value) } else { value.to_string() };
+
+    if value.contains(|c: char| c == ',' || c == '"' || c == '\r' || c == '\n') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn is_formula(value: &str) -> bool {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some('=') | Some('@') => true,
+        Some('+') | Some('-') => !chars.all(|c| c.is_ascii_digit() || c.is_whitespace() || ".,()-".contains(c)),
+        _ => false,
+    }
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            // other control characters are not allowed in XML 1.0
+            c if (c as u32) < 0x20 => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Minimal ZIP archive writer, files are stored without compression
+#[derive(Default)]
+struct ZipWriter {
+    data: Vec<u8>,
+    central_directory: Vec<u8>,
+    files_count: u16,
+}
+
+impl ZipWriter {
+    // 1980-01-01 00:00 in MS-DOS format
+    const DOS_TIME: u16 = 0;
+    const DOS_DATE: u16 = 0x21;
+
+    fn add_file(&mut self, name: &str, content: &[u8]) {
+        let offset = self.data.len() as u32;
+        let crc = crc32(content);
+        let size = content.len() as u32;
+
+        push_u32(&mut self.data, 0x0403_4b50);
+        push_u16(&mut self.data, 20);
+        push_u16(&mut self.data, 0);
+        push_u16(&mut self.data, 0);
+        push_u16(&mut self.data, ZipWriter::DOS_TIME);
+        push_u16(&mut self.data, ZipWriter::DOS_DATE);
+        push_u32(&mut self.data, crc);
+        push_u32(&mut self.data, size);
+        push_u32(&mut self.data, size);
+        push_u16(&mut self.data, name.len() as u16);
+        push_u16(&mut self.data, 0);
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(content);
+
+        let cd = &mut self.central_directory;
+        push_u32(cd, 0x0201_4b50);
+        push_u16(cd, 20);
+        push_u16(cd, 20);
+        push_u16(cd, 0);
+        push_u16(cd, 0);
+        push_u16(cd, ZipWriter::DOS_TIME);
+        push_u16(cd, ZipWriter::DOS_DATE);
+        push_u32(cd, crc);
+        push_u32(cd, size);
+        push_u32(cd, size);
+        push_u16(cd, name.len() as u16);
+        push_u16(cd, 0);
+        push_u16(cd, 0);
+        push_u16(cd, 0);
+        push_u16(cd, 0);
+        push_u32(cd, 0);
+        push_u32(cd, offset);
+        cd.extend_from_slice(name.as_bytes());
+
+        self.files_count += 1;
+    }
+
+    fn finish(self) -> Vec<u8> {
+        let ZipWriter {
+            mut data,
+            central_directory,
+            files_count,
+        } = self;
+        let central_directory_offset = data.len() as u32;
+        let central_directory_size = central_directory.len() as u32;
+        data.extend_from_slice(&central_directory);
+
+        push_u32(&mut data, 0x0605_4b50);
+        push_u16(&mut data, 0);
+        push_u16(&mut data, 0);
+        push_u16(&mut data, files_count);
+        push_u16(&mut data, files_count);
+        push_u32(&mut data, central_directory_size);
+        push_u32(&mut data, central_directory_offset);
+        push_u16(&mut data, 0);
+        data
+    }
+}
+
+fn push_u16(buf: &mut Vec<u8>, value: u16) {
+    buf.push(value as u8);
+    buf.push((value >> 8) as u8);
+}
+
+fn push_u32(buf: &mut Vec<u8>, value: u32) {
+    push_u16(buf, value as u16);
+    push_u16(buf, (value >> 16) as u16);
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            let mask = (!(crc & 1)).wrapping_add(1);
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Files of the archive read through the central directory, checking sizes and checksums of the entries
+    fn unzip(data: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let u16_at = |at: usize| u16::from(data[at]) | u16::from(data[at + 1]) << 8;
+        let u32_at = |at: usize| u32::from(u16_at(at)) | u32::from(u16_at(at + 2)) << 16;
+
+        let end = data.len() - 22;
+        assert_eq!(u32_at(end), 0x0605_4b50);
+        let files_count = u16_at(end + 10) as usize;
+        let mut entry = u32_at(end + 16) as usize;
+        assert_eq!(entry + u32_at(end + 12) as usize, end);
+
+        let mut files = vec![];
+        for _ in 0..files_count {
+            assert_eq!(u32_at(entry), 0x0201_4b50);
+            let crc = u32_at(entry + 16);
+            let size = u32_at(entry + 24) as usize;
+            let name_len = u16_at(entry + 28) as usize;
+            let name = String::from_utf8(data[entry + 46..entry + 46 + name_len].to_vec()).unwrap();
+
+            let local = u32_at(entry + 42) as usize;
+            assert_eq!(u32_at(local), 0x0403_4b50);
+            assert_eq!(u32_at(local + 14), crc);
+            assert_eq!(u32_at(local + 22) as usize, size);
+            assert_eq!(&data[local + 30..local + 30 + name_len], name.as_bytes());
+            let content_start = local + 30 + name_len + u16_at(local + 28) as usize;
+            let content = data[content_start..content_start + size].to_vec();
+            assert_eq!(crc32(&content), crc);
+
+            files.push((name, content));
+            entry += 46 + name_len;
+        }
+        files
+    }
+
+    #[test]
+    fn crc32_matches_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn zip_round_trip() {
+        let mut zip = ZipWriter::default();
+        zip.add_file("a.txt", b"first");
+        zip.add_file("dir/b.txt", b"");
+        zip.add_file("c.txt", "третий".as_bytes());
+
+        let files = unzip(&zip.finish());
+        assert_eq!(
+            files,
+            vec![
+                ("a.txt".to_string(), b"first".to_vec()),
+                ("dir/b.txt".to_string(), vec![]),
+                ("c.txt".to_string(), "третий".as_bytes().to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn xlsx_contains_workbook_parts_and_cells() {
+        let rows = vec![
+            vec![Cell::text("Order"), Cell::text("Total <EUR> & fee")],
+            vec![Cell::Number(1.0), Cell::Number(10.5)],
+        ];
+        let files = unzip(&to_xlsx("Orders \"2018\"", &rows));
+
+        let names = files.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "[Content_Types].xml",
+                "_rels/.rels",
+                "xl/workbook.xml",
+                "xl/_rels/workbook.xml.rels",
+                "xl/worksheets/sheet1.xml",
+            ]
+        );
+
+        let workbook = String::from_utf8(files[2].1.clone()).unwrap();
+        assert!(workbook.contains(r#"<sheet name="Orders &quot;2018&quot;" sheetId="1" r:id="rId1"/>"#));
+
+        let sheet = String::from_utf8(files[4].1.clone()).unwrap();
+        assert!(sheet.contains(concat!(
+            r#"<row r="1"><c t="inlineStr"><is><t xml:space="preserve">Order</t></is></c>"#,
+            r#"<c t="inlineStr"><is><t xml:space="preserve">Total &lt;EUR&gt; &amp; fee</t></is></c></row>"#,
+        )));
+        assert!(sheet.contains(r#"<row r="2"><c><v>1</v></c><c><v>10.5</v></c></row>"#));
+    }
+
+    #[test]
+    fn xml_drops_control_characters() {
+        assert_eq!(escape_xml("a\u{0}b\u{1b}c\td"), "abc\td");
+    }
+
+    #[test]
+    fn csv_quotes_separators_quotes_and_line_breaks() {
+        let rows = vec![vec![
+            Cell::text("plain"),
+            Cell::text("a,b"),
+            Cell::text("say \"hi\""),
+            Cell::text("two\r\nlines"),
+            Cell::Number(2.5),
+        ]];
+        assert_eq!(to_csv(&rows), "plain,\"a,b\",\"say \"\"hi\"\"\",\"two\r\nlines\",2.5\r\n");
+    }
+
+    #[test]
+    fn csv_prefixes_formulas() {
+        let rows = vec![vec![
+            Cell::text("=SUM(A1)"),
+            Cell::text("+cmd|' /C calc'!A0"),
+            Cell::text("-2+3+A1"),
+            Cell::text("@x"),
+            Cell::text("=a,b"),
+        ]];
+        assert_eq!(to_csv(&rows), "'=SUM(A1),'+cmd|' /C calc'!A0,'-2+3+A1,'@x,\"'=a,b\"\r\n");
+    }
+
+    #[test]
+    fn csv_keeps_phone_numbers_and_negative_numbers() {
+        let rows = vec![vec![Cell::text("+49 (30) 123-45-67"), Cell::text("-12.50"), Cell::Number(-3.5)]];
+        assert_eq!(to_csv(&rows), "+49 (30) 123-45-67,-12.50,-3.5\r\n");
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let rows = vec![
+            vec![Cell::text("Name"), Cell::text("Comment")],
+            vec![Cell::text("Товар, 1"), Cell::text("line \"one\"\nline two")],
+            vec![Cell::text(""), Cell::Number(3.0)],
+        ];
+        let parsed = parse_csv(&to_csv(&rows)).unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                vec!["Name".to_string(), "Comment".to_string()],
+                vec!["Товар, 1".to_string(), "line \"one\"\nline two".to_string()],
+                vec!["".to_string(), "3".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_csv_reads_quoted_fields() {
+        let parsed = parse_csv("a,\"b,c\",\"d \"\"e\"\"\"\r\n\"multi\nline\",,\"\"\n").unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                vec!["a".to_string(), "b,c".to_string(), "d \"e\"".to_string()],
+                vec!["multi\nline".to_string(), "".to_string(), "".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_csv_skips_bom_and_empty_lines() {
+        let parsed = parse_csv("\u{feff}name,price\n\n\r\nshirt,10\r\n").unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                vec!["name".to_string(), "price".to_string()],
+                vec!["shirt".to_string(), "10".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_csv_keeps_last_line_without_line_break() {
+        assert_eq!(parse_csv("a,b").unwrap(), vec![vec!["a".to_string(), "b".to_string()]]);
+        assert_eq!(parse_csv("a,").unwrap(), vec![vec!["a".to_string(), "".to_string()]]);
+        assert_eq!(parse_csv("").unwrap(), Vec::<Vec<String>>::new());
+    }
+
+    #[test]
+    fn parse_csv_rejects_stray_and_unclosed_quotes() {
+        assert_eq!(parse_csv("a,b\"c\n").unwrap_err(), "Unexpected quote in line 1.");
+        assert_eq!(parse_csv("a\n\"b,c\nd").unwrap_err(), "Quoted field is not closed in line 3.");
+    }
+}
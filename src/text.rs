@@ -0,0 +1,186 @@
+//! Text helpers for the bilingual (Cyrillic and Latin) catalog search
+use std::cmp;
+
+const CYRILLIC_TO_LATIN: &[(char, &str)] = &[
+    ('а', "a"),
+    ('б', "b"),
+    ('в', "v"),
+    ('г', "g"),
+    ('д', "d"),
+    ('е', "e"),
+    ('ё', "e"),
+    ('ж', "zh"),
+    ('з', "z"),
+    ('и', "i"),
+    ('й', "y"),
+    ('к', "k"),
+    ('л', "l"),
+    ('м', "m"),
+    ('н', "n"),
+    ('о', "o"),
+    ('п', "p"),
+    ('р', "r"),
+    ('с', "s"),
+    ('т', "t"),
+    ('у', "u"),
+    ('ф', "f"),
+    ('х', "kh"),
+    ('ц', "ts"),
+    ('ч', "ch"),
+    ('ш', "sh"),
+    ('щ', "shch"),
+    ('ъ', ""),
+    ('ы', "y"),
+    ('ь', ""),
+    ('э', "e"),
+    ('ю', "yu"),
+    ('я', "ya"),
+];
+
+/// Longer sequences go first, so that e.g. "shch" is not read as "sh" + "ch"
+const LATIN_TO_CYRILLIC: &[(&str, &str)] = &[
+    ("shch", "щ"),
+    ("zh", "ж"),
+    ("kh", "х"),
+    ("ts", "ц"),
+    ("ch", "ч"),
+    ("sh", "ш"),
+    ("yu", "ю"),
+    ("ya", "я"),
+    ("yo", "ё"),
+    ("a", "а"),
+    ("b", "б"),
+    ("c", "к"),
+    ("d", "д"),
+    ("e", "е"),
+    ("f", "ф"),
+    ("g", "г"),
+    ("h", "х"),
+    ("i", "и"),
+    ("j", "дж"),
+    ("k", "к"),
+    ("l", "л"),
+    ("m", "м"),
+    ("n", "н"),
+    ("o", "о"),
+    ("p", "п"),
+    ("q", "к"),
+    ("r", "р"),
+    ("s", "с"),
+    ("t", "т"),
+    ("u", "у"),
+    ("v", "в"),
+    ("w", "в"),
+    ("x", "кс"),
+    ("y", "ы"),
+    ("z", "з"),
+];
+
+pub fn is_cyrillic(text: &str) -> bool {
+    text.chars().any(|c| c >= '\u{0400}' && c <= '\u{04FF}')
+}
+
+/// Transliterates Cyrillic text to Latin and Latin text to Cyrillic, the result is lowercase
+pub fn transliterate(text: &str) -> String {
+    let text = text.to_lowercase();
+    if is_cyrillic(&text) {
+        to_latin(&text)
+    } else {
+        to_cyrillic(&text)
+    }
+}
+
+fn to_latin(text: &str) -> String {
+    text.chars()
+        .map(|c| match CYRILLIC_TO_LATIN.iter().find(|(cyrillic, _)| *cyrillic == c) {
+            Some((_, latin)) => latin.to_string(),
+            None => c.to_string(),
+        })
+        .collect()
+}
+
+fn to_cyrillic(text: &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        match LATIN_TO_CYRILLIC.iter().find(|(latin, _)| rest.starts_with(latin)) {
+            Some((latin, cyrillic)) => {
+                // "y" after a vowel is "й" as in "may" - "май"
+                let after_vowel = result.chars().last().map(|last| "аеёиоуыэюя".contains(last)).unwrap_or(false);
+                if *latin == "y" && after_vowel {
+                    result.push('й');
+                } else {
+                    result.push_str(cyrillic);
+                }
+                rest = &rest[latin.len()..];
+            }
+            None => {
+                result.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    result
+}
+
+/// Levenshtein distance of the words in characters
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    let mut current = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let substitution = previous[j - 1] + (a[i - 1] != b[j - 1]) as usize;
+            current[j] = cmp::min(substitution, cmp::min(previous[j], current[j - 1]) + 1);
+        }
+        previous.copy_from_slice(&current);
+    }
+    previous[b.len()]
+}
+
+/// Lowercase words of the text, punctuation is dropped
+pub fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transliterates_cyrillic_to_latin() {
+        assert_eq!(transliterate("Щука"), "shchuka");
+        assert_eq!(transliterate("телефон"), "telefon");
+        assert_eq!(transliterate("объём"), "obem");
+    }
+
+    #[test]
+    fn transliterates_latin_to_cyrillic() {
+        assert_eq!(transliterate("Shchuka"), "щука");
+        assert_eq!(transliterate("telefon"), "телефон");
+        assert_eq!(transliterate("may"), "май");
+        assert_eq!(transliterate("iphone 7"), "ипхоне 7");
+    }
+
+    #[test]
+    fn edit_distance_counts_characters() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("phone", "phone"), 0);
+        assert_eq!(edit_distance("phone", ""), 5);
+        assert_eq!(edit_distance("phnoe", "phone"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("телефн", "телефон"), 1);
+    }
+
+    #[test]
+    fn words_are_lowercase_without_punctuation() {
+        assert_eq!(words("Smart TV, 4K!"), vec!["smart", "tv", "4k"]);
+        assert_eq!(words("  Телефон-смартфон  "), vec!["телефон", "смартфон"]);
+        assert!(words(" ,.! ").is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+//! Builder of microservice urls.
+//! Every value coming from the request is percent-encoded, so it can not
+//! change the path or add query parameters to the upstream request.
+use std::fmt;
+use std::fmt::Write;
+
+use stq_routes::model::Model;
+
+/// Url of a microservice endpoint, can only be created with `UrlBuilder`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Url(String);
+
+impl Url {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Display for Url {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct UrlBuilder {
+    path: String,
+    query: Vec<(String, String)>,
+}
+
+impl UrlBuilder {
+    pub fn new<S: Into<String>>(base: S) -> Self {
+        let mut path = base.into();
+        while path.ends_with('/') {
+            path.pop();
+        }
+
+        Self { path, query: vec![] }
+    }
+
+    /// Appends static path, e.g. `"search/filters/count"`. Never pass user input here, use `segment` instead.
+    pub fn path(mut self, path: &'static str) -> Self {
+        self.path.push('/');
+        self.path.push_str(path);
+        self
+    }
+
+    pub fn model(mut self, model: Model) -> Self {
+        self.path.push('/');
+        self.path.push_str(&model.to_url());
+        self
+    }
+
+    /// Appends single percent-encoded path segment, `/` inside the value is encoded too,
+    /// as well as the dots of `.` and `..` segments, which would otherwise change the parent path
+    pub fn segment<T: fmt::Display>(mut self, segment: T) -> Self {
+        let segment = segment.to_string();
+        self.path.push('/');
+        if segment == "." || segment == ".." {
+            self.path.push_str(&segment.replace('.', "%2E"));
+        } else {
+            self.path.push_str(&encode(&segment));
+        }
+        self
+    }
+
+    /// Appends percent-encoded query parameter
+    pub fn query<T: fmt::Display>(mut self, key: &str, value: T) -> Self {
+        self.query.push((encode(key), encode(&value.to_string())));
+        self
+    }
+
+    pub fn build(self) -> Url {
+        let UrlBuilder { mut path, query } = self;
+        for (i, (key, value)) in query.into_iter().enumerate() {
+            path.push(if i == 0 { '?' } else { '&' });
+            path.push_str(&key);
+            path.push('=');
+            path.push_str(&value);
+        }
+
+        Url(path)
+    }
+}
+
+/// Percent-encodes everything except unreserved characters of RFC 3986
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),
+            _ => {
+                let _ = write!(encoded, "%{:02X}", byte);
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builder() -> UrlBuilder {
+        UrlBuilder::new("http://stores:8000/")
+    }
+
+    #[test]
+    fn joins_path_and_query() {
+        let url = builder().path("stores").segment(42).path("products").query("offset", 0).query("count", 10).build();
+        assert_eq!(url.as_str(), "http://stores:8000/stores/42/products?offset=0&count=10");
+    }
+
+    #[test]
+    fn segment_encodes_reserved_characters() {
+        let url = builder().path("stores").segment("a/b?c&d#e+f").build();
+        assert_eq!(url.as_str(), "http://stores:8000/stores/a%2Fb%3Fc%26d%23e%2Bf");
+    }
+
+    #[test]
+    fn segment_can_not_go_up() {
+        assert_eq!(builder().path("stores").segment("..").build().as_str(), "http://stores:8000/stores/%2E%2E");
+        assert_eq!(builder().path("stores").segment(".").build().as_str(), "http://stores:8000/stores/%2E");
+        assert_eq!(
+            builder().path("stores").segment("../users").build().as_str(),
+            "http://stores:8000/stores/..%2Fusers"
+        );
+    }
+
+    #[test]
+    fn segment_encodes_percent_of_encoded_values() {
+        let url = builder().path("stores").segment("..%2Fusers").build();
+        assert_eq!(url.as_str(), "http://stores:8000/stores/..%252Fusers");
+    }
+
+    #[test]
+    fn segment_encodes_non_ascii_as_utf8() {
+        let url = builder().path("stores").segment("магазин é").build();
+        assert_eq!(
+            url.as_str(),
+            "http://stores:8000/stores/%D0%BC%D0%B0%D0%B3%D0%B0%D0%B7%D0%B8%D0%BD%20%C3%A9"
+        );
+    }
+
+    #[test]
+    fn query_value_can_not_add_parameters() {
+        let url = builder().path("search").query("name", "a&count=1000#x").query("offset", 0).build();
+        assert_eq!(url.as_str(), "http://stores:8000/search?name=a%26count%3D1000%23x&offset=0");
+    }
+
+    #[test]
+    fn query_encodes_plus_slash_and_question_mark() {
+        let url = builder().path("search").query("name", "1+1/2?").build();
+        assert_eq!(url.as_str(), "http://stores:8000/search?name=1%2B1%2F2%3F");
+    }
+
+    #[test]
+    fn query_key_is_encoded() {
+        let url = builder().path("search").query("a=b&c", "d").build();
+        assert_eq!(url.as_str(), "http://stores:8000/search?a%3Db%26c=d");
+    }
+
+    #[test]
+    fn keeps_unreserved_characters() {
+        let url = builder().path("search").segment("A-z_0.9~").query("q", "A-z_0.9~").build();
+        assert_eq!(url.as_str(), "http://stores:8000/search/A-z_0.9~?q=A-z_0.9~");
+    }
+}